@@ -0,0 +1,127 @@
+//! Times each [`Broadphase`] rebuilding and answering a tick's worth of
+//! queries, over fields of fallers spread evenly and bunched into clusters,
+//! and checks they agree on what overlaps.
+//!
+//! Run with `cargo bench --bench broadphase`, optionally followed by entity
+//! counts to try, e.g. `cargo bench --bench broadphase -- 50 5000`.
+
+#[path = "../src/engine/broadphase.rs"]
+mod broadphase;
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy_ecs::prelude::*;
+use broadphase::{Broadphase, BroadphaseKind};
+use macroquad::prelude::*;
+
+const FIELD: Vec2 = vec2(400., 300.);
+const TICKS: u32 = 50;
+const DEFAULT_COUNTS: [u32; 4] = [100, 500, 1_000, 2_500];
+
+/// A small deterministic generator, so every structure sees the same field.
+struct Lcg(u64);
+
+impl Lcg {
+    fn next(&mut self) -> f32 {
+        self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+        (self.0 >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+fn field(count: u32, clustered: bool) -> Vec<(Entity, Rect)> {
+    let mut rng = Lcg(count as u64);
+    let centers: Vec<Vec2> = (0..4)
+        .map(|_| vec2(rng.next(), rng.next()) * FIELD)
+        .collect();
+
+    (0..count)
+        .map(|i| {
+            let center = if clustered {
+                let offset = vec2(rng.next() - 0.5, rng.next() - 0.5) * 40.;
+                centers[i as usize % centers.len()] + offset
+            } else {
+                vec2(rng.next(), rng.next()) * FIELD
+            };
+            let size = 4. + rng.next() * 20.;
+
+            (
+                Entity::from_raw(i),
+                Rect::new(center.x - size / 2., center.y - size / 2., size, size),
+            )
+        })
+        .collect()
+}
+
+/// Rebuilds and queries every box against the rest, as a tick would.
+/// Returns how many real overlaps were found.
+fn tick(index: &mut dyn Broadphase, boxes: &[(Entity, Rect)], nearby: &mut Vec<Entity>) -> usize {
+    index.clear();
+
+    for &(entity, rect) in boxes {
+        index.insert(entity, rect);
+    }
+
+    let mut hits = 0;
+
+    for &(_, rect) in boxes {
+        index.gather(rect, nearby);
+        hits += nearby
+            .iter()
+            .filter(|other| boxes[other.index() as usize].1.overlaps(&rect))
+            .count();
+    }
+
+    hits
+}
+
+fn time(kind: BroadphaseKind, boxes: &[(Entity, Rect)]) -> (Duration, usize) {
+    let mut index = kind.build();
+    let mut nearby = Vec::new();
+    let hits = tick(index.as_mut(), boxes, &mut nearby);
+
+    let start = Instant::now();
+
+    for _ in 0..TICKS {
+        black_box(tick(index.as_mut(), black_box(boxes), &mut nearby));
+    }
+
+    (start.elapsed() / TICKS, hits)
+}
+
+fn main() {
+    let counts: Vec<u32> = std::env::args()
+        .skip(1)
+        .filter_map(|arg| arg.parse().ok())
+        .collect();
+    let counts = if counts.is_empty() {
+        DEFAULT_COUNTS.to_vec()
+    } else {
+        counts
+    };
+
+    println!(
+        "{:>10} {:>10} {:>14} {:>14}",
+        "entities", "layout", "grid", "quadtree"
+    );
+
+    for count in counts {
+        for clustered in [false, true] {
+            let boxes = field(count, clustered);
+            let (grid, grid_hits) = time(BroadphaseKind::Grid, &boxes);
+            let (quadtree, quadtree_hits) = time(BroadphaseKind::Quadtree, &boxes);
+
+            assert_eq!(grid_hits, quadtree_hits, "broadphases disagree");
+
+            println!(
+                "{:>10} {:>10} {:>14?} {:>14?}",
+                count,
+                if clustered { "clustered" } else { "even" },
+                grid,
+                quadtree
+            );
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Times a tick shaped like the game's `GameSet::Simulation` phase, once on
+//! a single thread and once on bevy's multi-threaded executor, over a
+//! crowded world. The systems stand in for movement, lifetimes, cooldowns
+//! and particles: some touch the same components and have to take turns,
+//! the rest are free to run side by side.
+//!
+//! Run with `cargo bench --bench executor`, optionally followed by entity
+//! counts to try, e.g. `cargo bench --bench executor -- 1000 50000`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use bevy_ecs::{prelude::*, schedule::ExecutorKind};
+
+const DT: f32 = 1. / 60.;
+const WARMUP_TICKS: u32 = 20;
+const TICKS: u32 = 200;
+const DEFAULT_COUNTS: [u32; 3] = [1_000, 10_000, 100_000];
+
+#[derive(Component)]
+struct Position(f32, f32);
+
+#[derive(Component)]
+struct Velocity(f32, f32);
+
+#[derive(Component)]
+struct Weave {
+    amplitude: f32,
+    frequency: f32,
+    elapsed: f32,
+}
+
+#[derive(Component)]
+struct Lifetime(f32);
+
+#[derive(Component)]
+struct Cooldown(f32);
+
+#[derive(Component)]
+struct Flash {
+    elapsed: f32,
+    visible: bool,
+}
+
+#[derive(Component)]
+struct Fade {
+    alpha: f32,
+    rate: f32,
+}
+
+fn weave(mut q_weavers: Query<(&mut Weave, &mut Velocity)>) {
+    for (mut weave, mut velocity) in q_weavers.iter_mut() {
+        weave.elapsed += DT;
+
+        let angular = std::f32::consts::TAU * weave.frequency;
+        velocity.0 = weave.amplitude * angular * (angular * weave.elapsed).cos();
+    }
+}
+
+fn apply_velocity(mut q_moving: Query<(&mut Position, &Velocity)>) {
+    for (mut position, velocity) in q_moving.iter_mut() {
+        position.0 += velocity.0 * DT;
+        position.1 += velocity.1 * DT;
+    }
+}
+
+fn tick_lifetimes(mut q_lifetimes: Query<&mut Lifetime>) {
+    for mut lifetime in q_lifetimes.iter_mut() {
+        lifetime.0 = (lifetime.0 - DT).rem_euclid(10.);
+    }
+}
+
+fn tick_cooldowns(mut q_cooldowns: Query<&mut Cooldown>) {
+    for mut cooldown in q_cooldowns.iter_mut() {
+        cooldown.0 = (cooldown.0 - DT).max(0.).sqrt().powi(2);
+    }
+}
+
+fn tick_flash(mut q_flashing: Query<&mut Flash>) {
+    for mut flash in q_flashing.iter_mut() {
+        flash.elapsed += DT;
+        flash.visible = (flash.elapsed * 20.).sin() > 0.;
+    }
+}
+
+fn fade_particles(mut q_particles: Query<&mut Fade>) {
+    for mut fade in q_particles.iter_mut() {
+        fade.alpha = (fade.alpha - fade.rate * DT).exp().ln().max(0.);
+    }
+}
+
+fn world_with(count: u32) -> World {
+    let mut world = World::new();
+
+    world.spawn_batch((0..count).map(|i| {
+        let f = i as f32;
+        (
+            Position(f % 320., f % 240.),
+            Velocity(0., 30.),
+            Weave {
+                amplitude: 8.,
+                frequency: 0.5 + (f % 7.) * 0.1,
+                elapsed: 0.,
+            },
+            Lifetime(5. + f % 3.),
+            Cooldown(f % 1.),
+            Flash {
+                elapsed: 0.,
+                visible: true,
+            },
+            Fade {
+                alpha: 1.,
+                rate: 0.2,
+            },
+        )
+    }));
+
+    world
+}
+
+fn schedule(kind: ExecutorKind) -> Schedule {
+    let mut schedule = Schedule::default();
+    schedule.set_executor_kind(kind);
+    schedule.add_systems((
+        (weave, apply_velocity).chain(),
+        tick_lifetimes,
+        tick_cooldowns,
+        tick_flash,
+        fade_particles,
+    ));
+    schedule
+}
+
+/// The average time of one tick.
+fn time(count: u32, kind: ExecutorKind) -> Duration {
+    let mut world = world_with(count);
+    let mut schedule = schedule(kind);
+
+    for _ in 0..WARMUP_TICKS {
+        schedule.run(&mut world);
+    }
+
+    let start = Instant::now();
+
+    for _ in 0..TICKS {
+        schedule.run(black_box(&mut world));
+    }
+
+    start.elapsed() / TICKS
+}
+
+fn main() {
+    let counts: Vec<u32> = std::env::args()
+        .skip(1)
+        .filter_map(|arg| arg.parse().ok())
+        .collect();
+    let counts = if counts.is_empty() {
+        DEFAULT_COUNTS.to_vec()
+    } else {
+        counts
+    };
+
+    println!(
+        "{:>10} {:>14} {:>14} {:>8}",
+        "entities", "single", "multi", "speedup"
+    );
+
+    for count in counts {
+        let single = time(count, ExecutorKind::SingleThreaded);
+        let multi = time(count, ExecutorKind::MultiThreaded);
+
+        println!(
+            "{:>10} {:>14?} {:>14?} {:>7.2}x",
+            count,
+            single,
+            multi,
+            single.as_secs_f64() / multi.as_secs_f64()
+        );
+    }
+}
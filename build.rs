@@ -0,0 +1,44 @@
+//! With the `embed` feature, bakes every file under `assets/` into the
+//! binary. See `src/engine/files.rs` for how they're read back.
+
+use std::{env, fs, path::Path};
+
+fn collect(dir: &Path, files: &mut Vec<String>) {
+    for entry in fs::read_dir(dir).unwrap().flatten() {
+        let path = entry.path();
+
+        if path.is_dir() {
+            collect(&path, files);
+        } else {
+            let parts: Vec<_> = path.iter().map(|part| part.to_string_lossy()).collect();
+            files.push(parts.join("/"));
+        }
+    }
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=assets");
+
+    if env::var_os("CARGO_FEATURE_EMBED").is_none() {
+        return;
+    }
+
+    let mut files = Vec::new();
+    collect(Path::new("assets"), &mut files);
+    files.sort();
+
+    let root = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let entries: String = files
+        .iter()
+        .map(|file| {
+            format!(
+                "    ({:?}, include_bytes!({:?})),\n",
+                file,
+                format!("{}/{}", root, file)
+            )
+        })
+        .collect();
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded.rs");
+    fs::write(out, format!("&[\n{}]\n", entries)).unwrap();
+}
@@ -0,0 +1,47 @@
+//! The only code that talks to macroquad's audio. Without the `audio`
+//! feature nothing is loaded or played, so the game still builds where
+//! there's no sound library to link against.
+
+#[cfg(feature = "audio")]
+mod imp {
+    use macroquad::audio::{self, PlaySoundParams};
+
+    pub use audio::Sound;
+
+    pub async fn load(bytes: &[u8]) -> Result<Sound, String> {
+        audio::load_sound_from_bytes(bytes)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub fn play(sound: &Sound, volume: f32, looped: bool) {
+        audio::play_sound(sound, PlaySoundParams { looped, volume });
+    }
+
+    pub fn stop(sound: &Sound) {
+        audio::stop_sound(sound);
+    }
+
+    pub fn set_volume(sound: &Sound, volume: f32) {
+        audio::set_sound_volume(sound, volume);
+    }
+}
+
+#[cfg(not(feature = "audio"))]
+mod imp {
+    #[derive(Clone, Debug)]
+    pub struct Sound;
+
+    pub async fn load(_bytes: &[u8]) -> Result<Sound, String> {
+        Ok(Sound)
+    }
+
+    pub fn play(_sound: &Sound, _volume: f32, _looped: bool) {}
+
+    pub fn stop(_sound: &Sound) {}
+
+    pub fn set_volume(_sound: &Sound, _volume: f32) {}
+}
+
+pub use imp::Sound;
+pub(super) use imp::{load, play, set_volume, stop};
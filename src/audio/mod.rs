@@ -0,0 +1,15 @@
+mod backend;
+mod music;
+mod plugin;
+mod settings;
+mod sfx;
+mod sounds;
+mod wav;
+
+pub use backend::*;
+pub use music::*;
+pub use plugin::*;
+pub use settings::*;
+pub use sfx::*;
+pub use sounds::*;
+pub use wav::*;
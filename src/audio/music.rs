@@ -0,0 +1,216 @@
+//! A looping track for whatever's going on, crossfaded into the next one
+//! when that changes. While playing, stems are layered on top of each other
+//! as the run gets more intense. Tracks, stems and the fade length come from
+//! [`MUSIC_PATH`].
+
+use std::collections::HashMap;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use super::{AudioSettings, SoundAssets, backend};
+use crate::domain::{Boss, Faller, Lives};
+use crate::engine::{CurrentState, GameState, Time, read_string};
+
+pub const MUSIC_PATH: &str = "assets/music.ron";
+
+/// Fallers on screen for the crowd to count fully towards intensity.
+const CROWD: usize = 24;
+
+/// How much a full crowd, a boss and being on the last life each add to
+/// intensity. Together they can exceed 1, which intensity is capped at.
+const CROWD_WEIGHT: f32 = 0.6;
+const BOSS_WEIGHT: f32 = 0.4;
+const LAST_LIFE_WEIGHT: f32 = 0.3;
+
+/// How fast intensity follows the action, per second, so stems don't
+/// flicker in and out as fallers come and go.
+const INTENSITY_RATE: f32 = 0.5;
+
+/// What the music is for.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MusicCue {
+    Menu,
+    Playing,
+    /// Playing with a boss on screen. Without a track for it, the stems
+    /// keep playing instead.
+    Boss,
+    GameOver,
+}
+
+impl MusicCue {
+    /// The cue for the current state, or `None` while loading.
+    fn current(state: &CurrentState, boss: bool) -> Option<Self> {
+        if state.get() == GameState::Loading {
+            None
+        } else if state.contains(GameState::GameOver) {
+            Some(MusicCue::GameOver)
+        } else if !state.contains(GameState::Playing) {
+            Some(MusicCue::Menu)
+        } else if boss {
+            Some(MusicCue::Boss)
+        } else {
+            Some(MusicCue::Playing)
+        }
+    }
+}
+
+/// One stem of the layered music.
+#[derive(Deserialize, Clone, Debug)]
+pub struct MusicLayer {
+    pub sound: String,
+    /// Intensity, from 0 to 1, at which the stem fades in.
+    pub from: f32,
+}
+
+#[derive(Deserialize, Default)]
+pub struct MusicConfig {
+    /// Seconds for one track to fade out while the next fades in.
+    pub crossfade: f32,
+    /// The sound looped for each cue. Cues left out are silent.
+    pub tracks: HashMap<MusicCue, String>,
+    /// Stems looped together under [`MusicCue::Playing`], on top of its
+    /// track if it has one. They should all be the same length to stay in
+    /// time.
+    #[serde(default)]
+    pub layers: Vec<MusicLayer>,
+}
+
+impl MusicConfig {
+    pub async fn load(path: &str) -> Result<Self, String> {
+        let src = read_string(path).await?;
+        ron::from_str(&src).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Music {
+    pub config: MusicConfig,
+    /// Each track that's playing, fading in or fading out, and its volume.
+    playing: Vec<(String, f32)>,
+    /// How hairy the run is, from 0 to 1.
+    pub intensity: f32,
+    /// The volume of each of the config's layers, while they're playing.
+    /// They're started and stopped all at once so they stay in time.
+    layers: Option<Vec<f32>>,
+}
+
+pub fn reset_intensity(mut music: ResMut<Music>) {
+    music.intensity = 0.;
+}
+
+/// Eases [`Music::intensity`] toward how crowded the screen is, whether a
+/// boss is up and whether the player is on their last life.
+pub fn update_intensity(
+    mut music: ResMut<Music>,
+    q_fallers: Query<(), With<Faller>>,
+    q_bosses: Query<(), With<Boss>>,
+    lives: Res<Lives>,
+    time: Res<Time>,
+) {
+    let crowd = (q_fallers.iter().count() as f32 / CROWD as f32).min(1.);
+    let boss = if q_bosses.is_empty() { 0. } else { 1. };
+    let last_life = if lives.0 <= 1 { 1. } else { 0. };
+
+    let target = (crowd * CROWD_WEIGHT + boss * BOSS_WEIGHT + last_life * LAST_LIFE_WEIGHT).min(1.);
+    let step = INTENSITY_RATE * time.unscaled_dt;
+
+    music.intensity += (target - music.intensity).clamp(-step, step);
+}
+
+/// Starts the track for the current cue, fades it up and fades every other
+/// track down, stopping them once silent. Runs on unscaled time so slow
+/// motion and pausing don't stall a fade. Muting silences tracks without
+/// stopping them.
+pub fn update_music(
+    mut music: ResMut<Music>,
+    sounds: Res<SoundAssets>,
+    settings: Res<AudioSettings>,
+    state: Res<CurrentState>,
+    q_bosses: Query<(), With<Boss>>,
+    time: Res<Time>,
+) {
+    let music = &mut *music;
+
+    let mut cue = MusicCue::current(&state, !q_bosses.is_empty());
+
+    if cue == Some(MusicCue::Boss) && !music.config.tracks.contains_key(&MusicCue::Boss) {
+        cue = Some(MusicCue::Playing);
+    }
+
+    let wanted = cue.and_then(|cue| music.config.tracks.get(&cue)).cloned();
+
+    if let Some(wanted) = &wanted
+        && !music.playing.iter().any(|(name, _)| name == wanted)
+        && let Some(sound) = sounds.get(wanted)
+    {
+        backend::play(sound, 0., true);
+        music.playing.push((wanted.clone(), 0.));
+    }
+
+    let step = if music.config.crossfade > 0. {
+        time.unscaled_dt / music.config.crossfade
+    } else {
+        1.
+    };
+
+    music.playing.retain_mut(|(name, volume)| {
+        let Some(sound) = sounds.get(name) else {
+            return false;
+        };
+
+        if wanted.as_ref() == Some(name) {
+            *volume = (*volume + step).min(1.);
+        } else {
+            *volume -= step;
+
+            if *volume <= 0. {
+                backend::stop(sound);
+                return false;
+            }
+        }
+
+        backend::set_volume(sound, *volume * settings.music_volume());
+        true
+    });
+
+    let layered = cue == Some(MusicCue::Playing) && !music.config.layers.is_empty();
+
+    if layered && music.layers.is_none() {
+        for layer in &music.config.layers {
+            if let Some(sound) = sounds.get(&layer.sound) {
+                backend::play(sound, 0., true);
+            }
+        }
+
+        music.layers = Some(vec![0.; music.config.layers.len()]);
+    }
+
+    let Some(volumes) = &mut music.layers else {
+        return;
+    };
+
+    for (layer, volume) in music.config.layers.iter().zip(volumes.iter_mut()) {
+        let target = if layered && music.intensity >= layer.from {
+            1.
+        } else {
+            0.
+        };
+        *volume += (target - *volume).clamp(-step, step);
+
+        if let Some(sound) = sounds.get(&layer.sound) {
+            backend::set_volume(sound, *volume * settings.music_volume());
+        }
+    }
+
+    if !layered && volumes.iter().all(|&volume| volume <= 0.) {
+        for layer in &music.config.layers {
+            if let Some(sound) = sounds.get(&layer.sound) {
+                backend::stop(sound);
+            }
+        }
+
+        music.layers = None;
+    }
+}
@@ -0,0 +1,133 @@
+use bevy_ecs::prelude::*;
+
+use super::{
+    ActiveSounds, AudioSettings, MUSIC_PATH, Music, MusicConfig, PlaySound, PlaySoundAt, SFX_PANS,
+    SFX_PATH, SFX_PITCHES, SOUNDS_DIR, SfxTable, SoundAssets, SoundThrottle, gameplay_sfx,
+    menu_sfx, play_sounds, reset_intensity, spatialize_sounds, stop_sounds_on_exit,
+    update_intensity, update_music,
+};
+use crate::domain::{apply_damage, collect_pickups, despawn_dead};
+use crate::engine::{
+    App, CurrentState, FixedUpdate, GameSet, GameState, OnEnter, Plugin, PostUpdate,
+    StateTransition, in_stack, in_state,
+};
+
+/// Loaded sounds and the [`PlaySound`] event that plays them, the
+/// [`SfxTable`] hooking them up to gameplay and menus, and [`Music`]. Build
+/// with the `audio` feature to hear anything.
+pub struct AudioPlugin;
+
+impl Plugin for AudioPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SoundAssets>()
+            .init_resource::<SfxTable>()
+            .init_resource::<AudioSettings>()
+            .init_resource::<ActiveSounds>()
+            .init_resource::<SoundThrottle>()
+            .init_resource::<Music>()
+            .add_event::<PlaySound>()
+            .add_event::<PlaySoundAt>()
+            .load(
+                SOUNDS_DIR,
+                async {
+                    let table = SfxTable::load(SFX_PATH).await?;
+                    let music = MusicConfig::load(MUSIC_PATH).await?;
+
+                    let tracks: Vec<&str> = music
+                        .tracks
+                        .values()
+                        .chain(music.layers.iter().map(|layer| &layer.sound))
+                        .map(String::as_str)
+                        .collect();
+
+                    let mut sounds = SoundAssets::default();
+                    sounds.load(&table.sounds(), &SFX_PANS, &SFX_PITCHES).await;
+                    sounds.load(&tracks, &[0.], &[1.]).await;
+
+                    Ok((table, music, sounds))
+                },
+                |world, (table, music, sounds)| {
+                    world.insert_resource(table);
+                    world.resource_mut::<Music>().config = music;
+                    world.insert_resource(sounds);
+                },
+            )
+            .add_systems(
+                FixedUpdate,
+                gameplay_sfx
+                    .in_set(GameSet::Collision)
+                    .after(apply_damage)
+                    .after(collect_pickups)
+                    .before(despawn_dead)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                PostUpdate,
+                (
+                    menu_sfx.run_if(|state: Res<CurrentState>| state.get().is_menu()),
+                    spatialize_sounds,
+                    play_sounds,
+                )
+                    .chain(),
+            )
+            .add_systems(OnEnter(GameState::Playing), reset_intensity)
+            .add_systems(
+                PostUpdate,
+                (
+                    update_intensity.run_if(in_stack(GameState::Playing)),
+                    update_music,
+                )
+                    .chain(),
+            )
+            .add_systems(StateTransition, stop_sounds_on_exit);
+    }
+}
+
+// With the `audio` feature, loading sounds needs a window.
+#[cfg(all(test, not(feature = "audio")))]
+mod tests {
+    use super::*;
+    use crate::domain::{DeathEvent, Faller, FireEvent, HitEvent, Lives, PickupEvent};
+    use crate::engine::HeadlessPlugin;
+
+    #[test]
+    fn crowded_screen_raises_intensity_past_the_layers() {
+        let mut app = App::new();
+        app.add_plugins(HeadlessPlugin)
+            .add_plugins(AudioPlugin)
+            .add_event::<FireEvent>()
+            .add_event::<HitEvent>()
+            .add_event::<DeathEvent>()
+            .add_event::<PickupEvent>()
+            .insert_resource(Lives(3));
+
+        app.startup();
+        app.world
+            .resource_mut::<CurrentState>()
+            .set(GameState::Playing);
+
+        for _ in 0..40 {
+            app.world.spawn(Faller);
+        }
+
+        for _ in 0..180 {
+            app.update();
+            app.world.clear_trackers();
+        }
+
+        let music = app.world.resource::<Music>();
+        let highest = music
+            .config
+            .layers
+            .iter()
+            .map(|layer| layer.from)
+            .fold(0., f32::max);
+
+        assert!(highest > 0., "the shipped music has layers to fade in");
+        assert!(
+            music.intensity > highest,
+            "intensity only reached {}",
+            music.intensity
+        );
+    }
+}
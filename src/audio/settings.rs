@@ -0,0 +1,51 @@
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Volumes, in percent, and whether everything is muted. Saved in the
+/// settings file.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(default)]
+pub struct AudioSettings {
+    /// Scales both music and sound effects.
+    pub master: u32,
+    pub music: u32,
+    pub sfx: u32,
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 100,
+            music: 80,
+            sfx: 100,
+            muted: false,
+        }
+    }
+}
+
+impl AudioSettings {
+    pub const STEP: u32 = 10;
+
+    /// `volume` moved `steps` of [`AudioSettings::STEP`] up or down,
+    /// staying within 0 to 100.
+    pub fn adjust(volume: u32, steps: i32) -> u32 {
+        (volume as i32 + steps * Self::STEP as i32).clamp(0, 100) as u32
+    }
+
+    pub fn music_volume(&self) -> f32 {
+        self.volume(self.music)
+    }
+
+    pub fn sfx_volume(&self) -> f32 {
+        self.volume(self.sfx)
+    }
+
+    fn volume(&self, channel: u32) -> f32 {
+        if self.muted {
+            return 0.;
+        }
+
+        (self.master * channel) as f32 / 10_000.
+    }
+}
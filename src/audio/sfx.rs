@@ -0,0 +1,133 @@
+//! Which sound plays for what. Gameplay and menus only send their usual
+//! events; [`SfxTable`], read from [`SFX_PATH`], decides what they sound
+//! like, so sounds can be swapped without touching code.
+
+use std::collections::HashMap;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{PlaySound, PlaySoundAt};
+use crate::domain::{
+    DeathEvent, Faller, FireEvent, HitEvent, Pickup, PickupEvent, Player, Position,
+};
+use crate::engine::{Action, Actions, read_string};
+
+pub const SFX_PATH: &str = "assets/sfx.ron";
+
+/// Something that happened which can have a sound.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Sfx {
+    /// [`FireEvent`].
+    Shoot,
+    /// [`DeathEvent`] of a [`Faller`].
+    EnemyExplosion,
+    /// [`HitEvent`] on the [`Player`].
+    PlayerHit,
+    /// [`PickupEvent`] of a gem.
+    Gem,
+    /// [`PickupEvent`] of anything but a gem.
+    PowerUp,
+    /// Moving the highlight, or changing a value, in a menu.
+    MenuMove,
+    MenuConfirm,
+    MenuBack,
+}
+
+/// The sound for each [`Sfx`]. Ones left out of the table are silent.
+#[derive(Resource, Default)]
+pub struct SfxTable(HashMap<Sfx, String>);
+
+impl SfxTable {
+    pub async fn load(path: &str) -> Result<Self, String> {
+        let src = read_string(path).await?;
+        ron::from_str(&src).map(Self).map_err(|e| e.to_string())
+    }
+
+    /// Every sound in the table, for loading.
+    pub fn sounds(&self) -> Vec<&str> {
+        self.0.values().map(String::as_str).collect()
+    }
+
+    fn play(&self, sfx: Sfx, ev_sound: &mut EventWriter<PlaySound>) {
+        if let Some(name) = self.0.get(&sfx) {
+            ev_sound.send(PlaySound::new(name));
+        }
+    }
+
+    fn play_at(&self, sfx: Sfx, position: Vec2, ev_sound: &mut EventWriter<PlaySoundAt>) {
+        if let Some(name) = self.0.get(&sfx) {
+            ev_sound.send(PlaySoundAt {
+                sound: PlaySound::new(name),
+                position,
+            });
+        }
+    }
+}
+
+/// Runs in the tick's collision phase, before the dead are despawned, so it
+/// can still tell what died and where. Sounds are positioned where they
+/// happened.
+pub fn gameplay_sfx(
+    table: Res<SfxTable>,
+    mut ev_fire: EventReader<FireEvent>,
+    mut ev_hit: EventReader<HitEvent>,
+    mut ev_death: EventReader<DeathEvent>,
+    mut ev_pickup: EventReader<PickupEvent>,
+    mut ev_sound: EventWriter<PlaySoundAt>,
+    q_player: Option<Single<(Entity, &Position), With<Player>>>,
+    q_fallers: Query<&Position, With<Faller>>,
+) {
+    let player = q_player.map(|player| (player.0, player.1.0));
+    let at_player = player.map_or(Vec2::ZERO, |(_, position)| position);
+
+    for _ in ev_fire.read() {
+        table.play_at(Sfx::Shoot, at_player, &mut ev_sound);
+    }
+
+    for ev in ev_hit.read() {
+        if player.is_some_and(|(entity, _)| entity == ev.entity) {
+            table.play_at(Sfx::PlayerHit, at_player, &mut ev_sound);
+        }
+    }
+
+    for ev in ev_death.read() {
+        if let Ok(position) = q_fallers.get(ev.entity) {
+            table.play_at(Sfx::EnemyExplosion, position.0, &mut ev_sound);
+        }
+    }
+
+    for ev in ev_pickup.read() {
+        let sfx = match ev.pickup {
+            Pickup::Gem(_) => Sfx::Gem,
+            _ => Sfx::PowerUp,
+        };
+
+        table.play_at(sfx, at_player, &mut ev_sound);
+    }
+}
+
+/// Runs while a menu is on top of the stack.
+pub fn menu_sfx(table: Res<SfxTable>, actions: Res<Actions>, mut ev_sound: EventWriter<PlaySound>) {
+    let moved = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+    ]
+    .into_iter()
+    .any(|action| actions.is_pressed(action));
+
+    if moved {
+        table.play(Sfx::MenuMove, &mut ev_sound);
+    }
+
+    if actions.is_pressed(Action::Confirm) {
+        table.play(Sfx::MenuConfirm, &mut ev_sound);
+    }
+
+    if actions.is_pressed(Action::Back) {
+        table.play(Sfx::MenuBack, &mut ev_sound);
+    }
+}
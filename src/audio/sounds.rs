@@ -0,0 +1,259 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{AudioSettings, Pcm, Sound, backend};
+use crate::engine::{CurrentState, GameState, Screen, StateExited, Time, read_file};
+
+/// Where sounds are loaded from, as `{SOUNDS_DIR}/{name}.wav` or `.ogg`.
+pub const SOUNDS_DIR: &str = "assets/sounds";
+
+/// The pitches sound effects are loaded at, one picked at random each time
+/// they play so repeats don't sound identical. Only 16-bit WAVs can be
+/// repitched; anything else plays as is.
+pub const SFX_PITCHES: [f32; 5] = [1., 0.94, 0.97, 1.03, 1.06];
+
+/// The stereo pans sound effects are loaded at, from -1 for hard left to 1
+/// for hard right. Positioned sounds use the closest.
+pub const SFX_PANS: [f32; 5] = [0., -1., -0.5, 0.5, 1.];
+
+/// Most a sound effect's volume is randomly turned down by per play.
+const VOLUME_JITTER: f32 = 0.15;
+
+/// The same sound starts at most [`MAX_INSTANCES`] times within
+/// [`THROTTLE_WINDOW`] seconds; plays beyond that are dropped.
+const MAX_INSTANCES: usize = 3;
+const THROTTLE_WINDOW: f64 = 0.1;
+
+/// Share of its volume a [`PlaySoundAt`] loses in a corner of the screen,
+/// as far from the center as it gets.
+const DISTANCE_ATTENUATION: f32 = 0.5;
+
+/// Every sound, by file name without the extension, at each pan it was
+/// loaded at and each pitch within that.
+#[derive(Resource, Default)]
+pub struct SoundAssets(HashMap<String, Vec<(f32, Vec<Sound>)>>);
+
+impl SoundAssets {
+    /// Loads each of `names` at each of `pans` and `pitches`, trying `.wav`
+    /// before `.ogg`. Sounds that fail to load are left out, and playing
+    /// them does nothing.
+    pub async fn load(&mut self, names: &[&str], pans: &[f32], pitches: &[f32]) {
+        for &name in names {
+            let wav = format!("{}/{}.wav", SOUNDS_DIR, name);
+            let ogg = format!("{}/{}.ogg", SOUNDS_DIR, name);
+
+            let bytes = match read_file(&wav).await {
+                Ok(bytes) => Ok(bytes),
+                Err(_) => read_file(&ogg).await,
+            };
+
+            let variants = match bytes {
+                Ok(bytes) => Self::load_variants(&bytes, pans, pitches).await,
+                Err(e) => Err(e),
+            };
+
+            match variants {
+                Ok(variants) => {
+                    self.0.insert(name.to_string(), variants);
+                }
+                Err(e) => warn!("Failed to load sound {}: {}", name, e),
+            }
+        }
+    }
+
+    async fn load_variants(
+        bytes: &[u8],
+        pans: &[f32],
+        pitches: &[f32],
+    ) -> Result<Vec<(f32, Vec<Sound>)>, String> {
+        let as_is = pans == [0.] && pitches == [1.];
+
+        let Some(pcm) = Pcm::parse(bytes).filter(|_| !as_is) else {
+            return Ok(vec![(0., vec![backend::load(bytes).await?])]);
+        };
+
+        let mut variants = Vec::with_capacity(pans.len());
+
+        for &pan in pans {
+            let panned = if pan == 0. {
+                pcm.clone()
+            } else {
+                pcm.panned(pan)
+            };
+            let mut sounds = Vec::with_capacity(pitches.len());
+
+            for &pitch in pitches {
+                sounds.push(backend::load(&panned.repitched(pitch).to_wav()).await?);
+            }
+
+            variants.push((pan, sounds));
+        }
+
+        Ok(variants)
+    }
+
+    /// The sound centered, at the first pitch it was loaded at.
+    pub fn get(&self, name: &str) -> Option<&Sound> {
+        self.variants(name, 0.).first()
+    }
+
+    /// The sound at each pitch, at the pan closest to `pan`.
+    pub fn variants(&self, name: &str, pan: f32) -> &[Sound] {
+        let Some(pans) = self.0.get(name) else {
+            return &[];
+        };
+
+        pans.iter()
+            .min_by(|(a, _), (b, _)| (a - pan).abs().total_cmp(&(b - pan).abs()))
+            .map_or(&[], |(_, sounds)| sounds.as_slice())
+    }
+
+    /// Every variant of the sound.
+    pub fn all(&self, name: &str) -> impl Iterator<Item = &Sound> {
+        self.0
+            .get(name)
+            .into_iter()
+            .flatten()
+            .flat_map(|(_, sounds)| sounds)
+    }
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct PlaySound {
+    pub name: String,
+    pub volume: f32,
+    /// From -1 for hard left to 1 for hard right.
+    pub pan: f32,
+    /// Keeps playing until stopped, which happens when the state it was
+    /// played in is exited.
+    pub looped: bool,
+}
+
+impl PlaySound {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            volume: 1.,
+            pan: 0.,
+            looped: false,
+        }
+    }
+}
+
+/// A [`PlaySound`] from somewhere in the world, panned by how far left or
+/// right of the screen's center it is and quieter the further away.
+#[derive(Event, Clone, Debug)]
+pub struct PlaySoundAt {
+    pub sound: PlaySound,
+    pub position: Vec2,
+}
+
+/// Turns each [`PlaySoundAt`] into a [`PlaySound`] heard from the center of
+/// the screen, in the same texels entities are positioned and drawn in.
+pub fn spatialize_sounds(
+    mut ev_at: EventReader<PlaySoundAt>,
+    mut ev_play: EventWriter<PlaySound>,
+    screen: Res<Screen>,
+) {
+    let half = (vec2(screen.width as f32, screen.height as f32) / 2.).max(Vec2::ONE);
+
+    for ev in ev_at.read() {
+        let offset = ev.position - half;
+        let distance = (offset.length() / half.length()).min(1.);
+
+        let mut sound = ev.sound.clone();
+        sound.pan = (offset.x / half.x).clamp(-1., 1.);
+        sound.volume *= 1. - distance * DISTANCE_ATTENUATION;
+        ev_play.send(sound);
+    }
+}
+
+/// The looped sounds played in each state, to stop once it's exited.
+/// Pushing a state on top doesn't stop them, and one-off sounds are left to
+/// finish.
+#[derive(Resource, Default)]
+pub struct ActiveSounds(HashSet<(String, GameState)>);
+
+/// When each sound last started, for [`MAX_INSTANCES`].
+#[derive(Resource, Default)]
+pub struct SoundThrottle {
+    now: f64,
+    started: HashMap<String, VecDeque<f64>>,
+}
+
+impl SoundThrottle {
+    /// Notes a start of `name` if it's under the limit.
+    fn try_start(&mut self, name: &str) -> bool {
+        let now = self.now;
+        let started = self.started.entry(name.to_string()).or_default();
+
+        while started
+            .front()
+            .is_some_and(|&at| now - at >= THROTTLE_WINDOW)
+        {
+            started.pop_front();
+        }
+
+        if started.len() >= MAX_INSTANCES {
+            return false;
+        }
+
+        started.push_back(now);
+        true
+    }
+}
+
+/// Plays each [`PlaySound`] at a random pitch and slightly random volume.
+/// The randomness comes from macroquad rather than the seeded
+/// [`Rng`](crate::engine::Rng), so sound never changes how a run plays out.
+pub fn play_sounds(
+    mut ev_play: EventReader<PlaySound>,
+    sounds: Res<SoundAssets>,
+    settings: Res<AudioSettings>,
+    state: Res<CurrentState>,
+    time: Res<Time>,
+    mut active: ResMut<ActiveSounds>,
+    mut throttle: ResMut<SoundThrottle>,
+) {
+    throttle.now += time.unscaled_dt as f64;
+
+    for ev in ev_play.read() {
+        let volume = ev.volume * settings.sfx_volume();
+        let variants = sounds.variants(&ev.name, ev.pan);
+
+        if variants.is_empty() || volume <= 0. || !throttle.try_start(&ev.name) {
+            continue;
+        }
+
+        let sound = &variants[rand::gen_range(0, variants.len())];
+        let volume = volume * (1. - rand::gen_range(0., VOLUME_JITTER));
+
+        backend::play(sound, volume, ev.looped);
+
+        if ev.looped {
+            active.0.insert((ev.name.clone(), state.get()));
+        }
+    }
+}
+
+pub fn stop_sounds_on_exit(
+    mut ev_exited: EventReader<StateExited>,
+    sounds: Res<SoundAssets>,
+    mut active: ResMut<ActiveSounds>,
+) {
+    for StateExited(exited) in ev_exited.read() {
+        active.0.retain(|(name, state)| {
+            if state != exited {
+                return true;
+            }
+
+            for sound in sounds.all(name) {
+                backend::stop(sound);
+            }
+
+            false
+        });
+    }
+}
@@ -0,0 +1,145 @@
+//! Just enough of the WAV format to decode 16-bit PCM, change it and encode
+//! it again, since macroquad can't change a sound's pitch as it plays.
+
+/// Decoded 16-bit samples, interleaved by channel.
+#[derive(Clone, Debug)]
+pub struct Pcm {
+    pub channels: u16,
+    pub rate: u32,
+    pub samples: Vec<i16>,
+}
+
+fn u16_at(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+impl Pcm {
+    /// `None` unless `bytes` is an uncompressed 16-bit WAV holding whole
+    /// frames of at least one channel.
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        if bytes.get(0..4)? != b"RIFF" || bytes.get(8..12)? != b"WAVE" {
+            return None;
+        }
+
+        let mut fmt = None;
+        let mut data = None;
+        let mut at = 12;
+
+        while at + 8 <= bytes.len() {
+            let len = u32_at(bytes, at + 4)? as usize;
+            let body = bytes.get(at + 8..at + 8 + len)?;
+
+            match &bytes[at..at + 4] {
+                b"fmt " => fmt = Some(body),
+                b"data" => data = Some(body),
+                _ => {}
+            }
+
+            // Chunks are padded to an even length.
+            at += 8 + len + len % 2;
+        }
+
+        let (fmt, data) = (fmt?, data?);
+        let format = u16_at(fmt, 0)?;
+        let channels = u16_at(fmt, 2)?;
+        let bits = u16_at(fmt, 14)?;
+
+        if format != 1 || bits != 16 || channels == 0 {
+            return None;
+        }
+
+        if !data.len().is_multiple_of(channels as usize * 2) {
+            return None;
+        }
+
+        Some(Self {
+            channels,
+            rate: u32_at(fmt, 4)?,
+            samples: data
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]))
+                .collect(),
+        })
+    }
+
+    fn frames(&self) -> usize {
+        self.samples.len() / self.channels as usize
+    }
+
+    /// Sped up by `pitch`, which raises it, or slowed down below 1.
+    pub fn repitched(&self, pitch: f32) -> Self {
+        let channels = self.channels as usize;
+        let frames = self.frames();
+        let length = (frames as f32 / pitch) as usize;
+        let mut samples = Vec::with_capacity(length * channels);
+
+        for i in 0..length {
+            let position = i as f32 * pitch;
+            let frame = (position as usize).min(frames - 1);
+            let next = (frame + 1).min(frames - 1);
+            let t = position - frame as f32;
+
+            for c in 0..channels {
+                let a = self.samples[frame * channels + c] as f32;
+                let b = self.samples[next * channels + c] as f32;
+                samples.push((a + (b - a) * t) as i16);
+            }
+        }
+
+        Self {
+            channels: self.channels,
+            rate: self.rate,
+            samples,
+        }
+    }
+
+    /// In stereo, turned down on the right for a `pan` below 0 and on the
+    /// left above it. At -1 or 1 only one side is heard.
+    pub fn panned(&self, pan: f32) -> Self {
+        let left = (1. - pan).min(1.);
+        let right = (1. + pan).min(1.);
+        let channels = self.channels as usize;
+        let mut samples = Vec::with_capacity(self.frames() * 2);
+
+        for frame in self.samples.chunks_exact(channels) {
+            let (l, r) = (frame[0], frame[channels.min(2) - 1]);
+            samples.push((l as f32 * left) as i16);
+            samples.push((r as f32 * right) as i16);
+        }
+
+        Self {
+            channels: 2,
+            rate: self.rate,
+            samples,
+        }
+    }
+
+    pub fn to_wav(&self) -> Vec<u8> {
+        let data_len = self.samples.len() as u32 * 2;
+        let block_align = self.channels * 2;
+        let mut bytes = Vec::with_capacity(44 + data_len as usize);
+
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data_len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&1u16.to_le_bytes());
+        bytes.extend_from_slice(&self.channels.to_le_bytes());
+        bytes.extend_from_slice(&self.rate.to_le_bytes());
+        bytes.extend_from_slice(&(self.rate * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&16u16.to_le_bytes());
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&data_len.to_le_bytes());
+
+        for sample in &self.samples {
+            bytes.extend_from_slice(&sample.to_le_bytes());
+        }
+
+        bytes
+    }
+}
@@ -8,26 +8,45 @@ varying vec2 uv;
 uniform sampler2D Texture;
 uniform float iTime;
 uniform vec2 iResolution;
+// xy: center in UV, z: radius in UV, w: strength (0 when inactive).
+uniform vec4 iShockwave;
+// Tunable, see rendering/uniforms.rs.
+uniform float curvature;
+uniform float scanlines;
+uniform float vignette;
 
 vec2 CRTCurveUV(vec2 uv) {
     uv = uv * 2.0 - 1.0;
     vec2 offset = abs(uv.yx) / vec2(8.0, 6.0);
-    uv = uv + uv * offset * offset;
+    uv = uv + uv * offset * offset * curvature;
     uv = uv * 0.5 + 0.5;
     return uv;
 }
 
+vec2 ShockwaveUV(vec2 uv) {
+    vec2 delta = uv - iShockwave.xy;
+    float dist = length(delta);
+    float width = 0.08;
+    float ring = abs(dist - iShockwave.z);
+
+    if(iShockwave.w <= 0.0 || ring > width || dist < 0.0001) {
+        return uv;
+    }
+
+    return uv - delta / dist * (1.0 - ring / width) * iShockwave.w;
+}
+
 void DrawVignette(inout vec3 color, vec2 uv) {
-    float vignette = uv.x * uv.y * (1.0 - uv.x) * (1.0 - uv.y);
-    vignette = clamp(pow(16.0 * vignette, 0.3), 0.0, 1.0);
-    color *= vignette;
+    float edge = uv.x * uv.y * (1.0 - uv.x) * (1.0 - uv.y);
+    edge = clamp(pow(16.0 * edge, vignette), 0.0, 1.0);
+    color *= edge;
 }
 
 void DrawScanline(inout vec3 color, vec2 uv) {
     float width = 1.;
     float phase = iTime / 100.;
     float thickness = 1.;
-    float opacity = 0.25;
+    float opacity = scanlines;
     vec3 lineColor = vec3(0.11, 0.23, 0.19);
 
     float v = .5 * (sin((uv.y + phase) * 3.14159 / width * iResolution.y) + 1.);
@@ -36,7 +55,7 @@ void DrawScanline(inout vec3 color, vec2 uv) {
 
 void main() {
     vec2 crtUV = CRTCurveUV(uv);
-    vec3 res = texture2D(Texture, uv).rgb * color.rgb;
+    vec3 res = texture2D(Texture, ShockwaveUV(uv)).rgb * color.rgb;
     if(crtUV.x < 0.0 || crtUV.x > 1.0 || crtUV.y < 0.0 || crtUV.y > 1.0) {
         res = vec3(0.0, 0.0, 0.0);
     }
@@ -0,0 +1,212 @@
+//! Goals met over one run or many, unlocked once and kept in the
+//! [`Profile`]. Each tick the run's [`RunStats`], the lifetime totals and
+//! the wave just cleared are checked against every locked [`Achievement`],
+//! and [`Toasts`] announces the ones that unlock.
+
+use std::collections::VecDeque;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{LifetimeStats, Profile, RunStats, Waves};
+use crate::engine::Time;
+use crate::t;
+
+/// Fallers destroyed across every run for [`Achievement::Exterminator`].
+const EXTERMINATOR_KILLS: u32 = 100;
+
+/// Wave reached in one run for [`Achievement::Veteran`].
+const VETERAN_WAVE: u32 = 10;
+
+/// Fewest shots in a wave for [`Achievement::Sharpshooter`] to count it,
+/// and the fraction of them that must hit.
+const SHARPSHOOTER_SHOTS: u32 = 20;
+const SHARPSHOOTER_ACCURACY: f32 = 0.9;
+
+/// Seconds a toast stays up.
+pub const TOAST_DURATION: f32 = 3.;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Achievement {
+    FirstBlood,
+    Exterminator,
+    Pacifist,
+    Untouchable,
+    Sharpshooter,
+    GiantSlayer,
+    Veteran,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 7] = [
+        Achievement::FirstBlood,
+        Achievement::Exterminator,
+        Achievement::Pacifist,
+        Achievement::Untouchable,
+        Achievement::Sharpshooter,
+        Achievement::GiantSlayer,
+        Achievement::Veteran,
+    ];
+
+    fn key(&self) -> &'static str {
+        match self {
+            Achievement::FirstBlood => "achievement.first_blood",
+            Achievement::Exterminator => "achievement.exterminator",
+            Achievement::Pacifist => "achievement.pacifist",
+            Achievement::Untouchable => "achievement.untouchable",
+            Achievement::Sharpshooter => "achievement.sharpshooter",
+            Achievement::GiantSlayer => "achievement.giant_slayer",
+            Achievement::Veteran => "achievement.veteran",
+        }
+    }
+
+    pub fn name(&self) -> String {
+        t!(self.key())
+    }
+
+    pub fn description(&self) -> String {
+        t!(&format!("{}.description", self.key()))
+    }
+
+    pub fn is_met(&self, progress: &Progress) -> bool {
+        let cleared = progress.cleared;
+
+        match self {
+            Achievement::FirstBlood => progress.run.total_destroyed() > 0,
+            Achievement::Exterminator => {
+                progress.lifetime.totals.total_destroyed() + progress.run.total_destroyed()
+                    >= EXTERMINATOR_KILLS
+            }
+            Achievement::Pacifist => cleared.is_some_and(|wave| wave.shots == 0),
+            Achievement::Untouchable => cleared.is_some_and(|wave| wave.damage_taken == 0),
+            Achievement::Sharpshooter => cleared.is_some_and(|wave| {
+                wave.shots >= SHARPSHOOTER_SHOTS
+                    && wave.hits as f32 >= wave.shots as f32 * SHARPSHOOTER_ACCURACY
+            }),
+            Achievement::GiantSlayer => progress.run.destroyed.contains_key("boss_core"),
+            Achievement::Veteran => progress.wave >= VETERAN_WAVE,
+        }
+    }
+}
+
+/// The run's running totals that wave achievements look at.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WaveTally {
+    pub shots: u32,
+    pub hits: u32,
+    pub damage_taken: u32,
+}
+
+impl WaveTally {
+    fn of(stats: &RunStats) -> Self {
+        Self {
+            shots: stats.shots,
+            hits: stats.hits,
+            damage_taken: stats.damage_taken,
+        }
+    }
+
+    fn since(self, start: WaveTally) -> Self {
+        Self {
+            shots: self.shots - start.shots,
+            hits: self.hits - start.hits,
+            damage_taken: self.damage_taken - start.damage_taken,
+        }
+    }
+}
+
+/// What achievements are checked against.
+pub struct Progress<'a> {
+    pub run: &'a RunStats,
+    pub lifetime: &'a LifetimeStats,
+    /// The current wave.
+    pub wave: u32,
+    /// What happened during the wave just cleared, on the tick it's cleared.
+    pub cleared: Option<WaveTally>,
+}
+
+/// The wave being played and the run's tallies when it started. A `number`
+/// of zero waits for the first tick of a run, which may be resumed
+/// mid-wave.
+#[derive(Resource, Default)]
+pub struct WaveStart {
+    number: u32,
+    tally: WaveTally,
+}
+
+pub fn reset_wave_start(mut start: ResMut<WaveStart>) {
+    *start = WaveStart::default();
+}
+
+/// Popups announcing unlocked achievements, one at a time.
+#[derive(Resource, Default)]
+pub struct Toasts {
+    queue: VecDeque<Achievement>,
+    /// The toast showing and the seconds it's been up.
+    pub current: Option<(Achievement, f32)>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, achievement: Achievement) {
+        self.queue.push_back(achievement);
+    }
+}
+
+/// Runs once the tick's stats are tallied and the wave has advanced.
+pub fn check_achievements(
+    stats: Res<RunStats>,
+    waves: Res<Waves>,
+    mut start: ResMut<WaveStart>,
+    mut profile: ResMut<Profile>,
+    mut toasts: ResMut<Toasts>,
+) {
+    let tally = WaveTally::of(&stats);
+    let mut cleared = None;
+
+    if start.number == 0 {
+        start.number = waves.number;
+        start.tally = tally;
+    } else if waves.number > start.number {
+        cleared = Some(tally.since(start.tally));
+        start.number = waves.number;
+        start.tally = tally;
+    }
+
+    let progress = Progress {
+        run: &stats,
+        lifetime: &profile.lifetime,
+        wave: waves.number,
+        cleared,
+    };
+
+    let unlocked: Vec<_> = Achievement::ALL
+        .into_iter()
+        .filter(|achievement| !profile.achievements.contains(achievement))
+        .filter(|achievement| achievement.is_met(&progress))
+        .collect();
+
+    for achievement in unlocked {
+        info!("Unlocked {:?}", achievement);
+        profile.achievements.push(achievement);
+        toasts.push(achievement);
+    }
+}
+
+/// Counts in real time, so toasts still clear while paused.
+pub fn update_toasts(mut toasts: ResMut<Toasts>, time: Res<Time>) {
+    if let Some((_, shown)) = &mut toasts.current {
+        *shown += time.unscaled_dt;
+
+        if *shown >= TOAST_DURATION {
+            toasts.current = None;
+        }
+    }
+
+    if toasts.current.is_none() {
+        toasts.current = toasts
+            .queue
+            .pop_front()
+            .map(|achievement| (achievement, 0.));
+    }
+}
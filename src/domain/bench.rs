@@ -0,0 +1,81 @@
+//! The scenario `--bench` plays: the screen kept crowded with fallers and
+//! a wall of bullets fired up into them every tick, so collisions, deaths,
+//! particles and drawing all have plenty to do. The player can't be hurt
+//! and anything that would interrupt the run is closed straight away.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Armored, Faller, Player, PrefabSpawner};
+use crate::engine::{
+    App, Bench, Config, CurrentState, FixedUpdate, GameSet, GameState, Plugin, PostUpdate, Screen,
+    Update, benchmarking, finish_bench, in_state,
+};
+
+/// Fallers kept on screen once the scenario has filled up.
+const BENCH_FALLERS: usize = 1_000;
+
+/// Most fallers added in one tick, so the field fills over the warmup.
+const FALLERS_PER_TICK: usize = 25;
+
+/// Player bullets fired up from the bottom of the screen every tick.
+const BULLETS_PER_TICK: usize = 60;
+
+const FALLER_PREFAB: &str = "faller";
+const BULLET_PREFAB: &str = "bullet";
+
+pub struct BenchPlugin;
+
+impl Plugin for BenchPlugin {
+    fn build(&self, app: &mut App) {
+        let launch = &app.world.resource::<Config>().launch;
+        let bench = Bench::new(
+            launch.bench.unwrap_or_default(),
+            launch.seed.unwrap_or_default(),
+        );
+
+        app.insert_resource(bench)
+            .add_systems(
+                FixedUpdate,
+                (armor_player, crowd_screen)
+                    .in_set(GameSet::Spawning)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(Update, keep_playing.run_if(benchmarking))
+            .add_systems(PostUpdate, finish_bench);
+    }
+}
+
+fn armor_player(mut cmds: Commands, q_players: Query<Entity, Added<Player>>) {
+    for player in q_players.iter() {
+        cmds.entity(player).insert(Armored);
+    }
+}
+
+/// Tops up the fallers along the top of the screen and fires the tick's
+/// bullets from random points along the bottom.
+fn crowd_screen(
+    mut spawner: PrefabSpawner,
+    screen: Res<Screen>,
+    q_fallers: Query<(), With<Faller>>,
+) {
+    let width = screen.width as f32;
+    let missing = BENCH_FALLERS.saturating_sub(q_fallers.iter().len());
+
+    for _ in 0..missing.min(FALLERS_PER_TICK) {
+        let x = spawner.rng.gen_range(0., width);
+        spawner.spawn_prefab(FALLER_PREFAB, vec2(x, 0.));
+    }
+
+    for _ in 0..BULLETS_PER_TICK {
+        let x = spawner.rng.gen_range(0., width);
+        spawner.spawn_prefab(BULLET_PREFAB, vec2(x, screen.height as f32));
+    }
+}
+
+/// Closes sector summaries, shops and anything else pushed over the run.
+fn keep_playing(mut state: ResMut<CurrentState>) {
+    if state.get() != GameState::Playing {
+        state.pop();
+    }
+}
@@ -0,0 +1,76 @@
+//! A limited screen-clearing bomb. Everything it destroys is worth a
+//! fraction of the usual points, so it is a panic button rather than a
+//! scoring tool.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Boss, BossSegment, DamageEvent, EnemyBullet, Faller, Player, Position};
+use crate::{
+    engine::{ReleaseExt, Screen},
+    rendering::{ScreenShake, Shockwave},
+};
+
+pub const BOMB_CHARGES: u32 = 3;
+
+/// Share of a faller's points awarded when a bomb destroys it.
+pub const BOMB_SCORE_FACTOR: f32 = 0.25;
+
+/// Bombs left this run.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bombs(pub u32);
+
+impl Default for Bombs {
+    fn default() -> Self {
+        Self(BOMB_CHARGES)
+    }
+}
+
+/// Caught in a bomb blast. Scoring reads this to pay out less.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Bombed;
+
+pub fn reset_bombs(mut bombs: ResMut<Bombs>) {
+    *bombs = Bombs::default();
+}
+
+/// Destroys every faller on screen and clears enemy bullets. Boss parts
+/// shrug it off.
+pub fn detonate_bomb(
+    mut cmds: Commands,
+    mut bombs: ResMut<Bombs>,
+    mut ev_damage: EventWriter<DamageEvent>,
+    mut shake: ResMut<ScreenShake>,
+    mut shockwave: ResMut<Shockwave>,
+    q_player: Single<&Position, With<Player>>,
+    q_fallers: Query<(Entity, &Position), (With<Faller>, Without<Boss>, Without<BossSegment>)>,
+    q_enemy_bullets: Query<Entity, With<EnemyBullet>>,
+    screen: Res<Screen>,
+) {
+    if bombs.0 == 0 {
+        return;
+    }
+
+    bombs.0 -= 1;
+
+    let bounds = Rect::new(0., 0., screen.width as f32, screen.height as f32);
+
+    for (faller, position) in q_fallers.iter() {
+        if !bounds.contains(position.0) {
+            continue;
+        }
+
+        cmds.entity(faller).insert(Bombed);
+        ev_damage.send(DamageEvent {
+            target: faller,
+            amount: u32::MAX,
+        });
+    }
+
+    for bullet in q_enemy_bullets.iter() {
+        cmds.entity(bullet).release();
+    }
+
+    shake.add(0.8);
+    shockwave.start(q_player.0);
+}
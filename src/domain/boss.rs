@@ -0,0 +1,254 @@
+//! A large multi-part enemy that opens each boss wave (see
+//! [`Director::is_boss_wave`]). Its core is [`Armored`] until the segments
+//! around it are destroyed.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Armored, DeathEvent, Director, HierarchyCommands, Player, Position, PrefabSpawner, ScoreEvent,
+    Velocity, Waves,
+};
+use crate::engine::{Screen, Time};
+use crate::t;
+
+/// Points for defeating a boss, on top of its own points.
+const BOSS_BONUS: u32 = 1000;
+
+/// Height the boss hovers at between attacks.
+const HOVER_Y: f32 = 60.;
+
+const VOLLEYS: u32 = 5;
+const VOLLEY_INTERVAL: f32 = 0.8;
+const VOLLEY_SHOTS: usize = 7;
+const VOLLEY_SPREAD: f32 = 1.2;
+
+const CHARGE_SPEED: f32 = 220.;
+const CHARGE_TIME: f32 = 1.5;
+const RETURN_SPEED: f32 = 80.;
+
+const FORTRESS_SEGMENTS: [Vec2; 4] = [
+    vec2(-44., 0.),
+    vec2(44., 0.),
+    vec2(-24., 30.),
+    vec2(24., 30.),
+];
+
+const SWARM_SEGMENTS: [Vec2; 6] = [
+    vec2(-48., 0.),
+    vec2(48., 0.),
+    vec2(-24., 40.),
+    vec2(24., 40.),
+    vec2(-24., -40.),
+    vec2(24., -40.),
+];
+
+const LANCER_SEGMENTS: [Vec2; 2] = [vec2(-56., 8.), vec2(56., 8.)];
+
+/// Layouts of segments around the core.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BossKind {
+    /// Four segments shielding the core from the sides and below.
+    #[default]
+    Fortress,
+    /// A ring of six segments.
+    Swarm,
+    /// A pair of segments out to the sides.
+    Lancer,
+}
+
+impl BossKind {
+    pub const ALL: [BossKind; 3] = [BossKind::Fortress, BossKind::Swarm, BossKind::Lancer];
+
+    pub fn name(&self) -> String {
+        let key = match self {
+            BossKind::Fortress => "boss.fortress",
+            BossKind::Swarm => "boss.swarm",
+            BossKind::Lancer => "boss.lancer",
+        };
+
+        t!(key)
+    }
+
+    pub fn segment_offsets(&self) -> &'static [Vec2] {
+        match self {
+            BossKind::Fortress => &FORTRESS_SEGMENTS,
+            BossKind::Swarm => &SWARM_SEGMENTS,
+            BossKind::Lancer => &LANCER_SEGMENTS,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum BossPhase {
+    /// Descending into view.
+    Enter,
+    /// Swaying side to side firing fans of shots.
+    Spread { volleys_left: u32, cooldown: f32 },
+    /// Rushing at where the player was.
+    Charge { remaining: f32 },
+    /// Drifting back up to hover height.
+    Return,
+}
+
+#[derive(Component)]
+pub struct Boss {
+    pub phase: BossPhase,
+    /// Seconds since the boss spawned, drives its sway.
+    pub elapsed: f32,
+}
+
+/// A destructible part attached to a [`Boss`].
+#[derive(Component)]
+pub struct BossSegment;
+
+pub fn spawn_boss(mut spawner: PrefabSpawner, mut waves: ResMut<Waves>, screen: Res<Screen>) {
+    if !waves.boss_pending {
+        return;
+    }
+
+    waves.boss_pending = false;
+
+    let position = vec2(screen.width as f32 / 2., -40.);
+    let Some(core) = spawner.spawn_prefab("boss_core", position) else {
+        return;
+    };
+
+    spawner.cmds.entity(core).insert((
+        Boss {
+            phase: BossPhase::Enter,
+            elapsed: 0.,
+        },
+        Armored,
+    ));
+
+    for &offset in spawner.director.boss.segment_offsets() {
+        if let Some(segment) = spawner.spawn_prefab("boss_segment", position + offset) {
+            spawner
+                .cmds
+                .entity(segment)
+                .insert(BossSegment)
+                .set_parent(core, offset);
+        }
+    }
+}
+
+pub fn update_boss(
+    mut spawner: PrefabSpawner,
+    q_boss: Option<Single<(&mut Boss, &Position, &mut Velocity)>>,
+    q_player: Option<Single<&Position, (With<Player>, Without<Boss>)>>,
+    time: Res<Time>,
+    screen: Res<Screen>,
+) {
+    let Some(q_boss) = q_boss else {
+        return;
+    };
+
+    let (mut boss, position, mut velocity) = q_boss.into_inner();
+    let dt = time.fixed_dt;
+    let home = vec2(screen.width as f32 / 2., HOVER_Y);
+
+    boss.elapsed += dt;
+
+    boss.phase = match boss.phase {
+        BossPhase::Enter => {
+            velocity.0 = vec2(0., RETURN_SPEED);
+
+            if position.0.y >= HOVER_Y {
+                BossPhase::Spread {
+                    volleys_left: VOLLEYS,
+                    cooldown: VOLLEY_INTERVAL,
+                }
+            } else {
+                BossPhase::Enter
+            }
+        }
+        BossPhase::Spread {
+            volleys_left,
+            cooldown,
+        } => {
+            velocity.0 = vec2((boss.elapsed * 0.8).cos() * 40., 0.);
+
+            if cooldown > dt {
+                BossPhase::Spread {
+                    volleys_left,
+                    cooldown: cooldown - dt,
+                }
+            } else if volleys_left == 0 {
+                let target = q_player.map_or(home + vec2(0., 120.), |player| player.0);
+                velocity.0 = (target - position.0).normalize_or_zero() * CHARGE_SPEED;
+
+                BossPhase::Charge {
+                    remaining: CHARGE_TIME,
+                }
+            } else {
+                for i in 0..VOLLEY_SHOTS {
+                    let t = i as f32 / (VOLLEY_SHOTS - 1) as f32 - 0.5;
+                    let direction =
+                        Vec2::from_angle(std::f32::consts::FRAC_PI_2 + t * VOLLEY_SPREAD);
+
+                    spawner.spawn_prefab_aimed("boss_shot", position.0, direction);
+                }
+
+                BossPhase::Spread {
+                    volleys_left: volleys_left - 1,
+                    cooldown: VOLLEY_INTERVAL,
+                }
+            }
+        }
+        BossPhase::Charge { remaining } => {
+            if remaining > dt {
+                BossPhase::Charge {
+                    remaining: remaining - dt,
+                }
+            } else {
+                BossPhase::Return
+            }
+        }
+        BossPhase::Return => {
+            let to_home = home - position.0;
+
+            if to_home.length() < RETURN_SPEED * dt {
+                velocity.0 = Vec2::ZERO;
+                BossPhase::Spread {
+                    volleys_left: VOLLEYS,
+                    cooldown: VOLLEY_INTERVAL,
+                }
+            } else {
+                velocity.0 = to_home.normalize() * RETURN_SPEED;
+                BossPhase::Return
+            }
+        }
+    };
+}
+
+/// Exposes the core once every segment is gone.
+pub fn unarmor_boss(
+    mut cmds: Commands,
+    q_boss: Query<Entity, (With<Boss>, With<Armored>)>,
+    q_segments: Query<(), With<BossSegment>>,
+) {
+    if !q_segments.is_empty() {
+        return;
+    }
+
+    for boss in q_boss.iter() {
+        cmds.entity(boss).remove::<Armored>();
+    }
+}
+
+pub fn boss_defeated(
+    mut ev_death: EventReader<DeathEvent>,
+    mut ev_score: EventWriter<ScoreEvent>,
+    mut waves: ResMut<Waves>,
+    director: Res<Director>,
+    q_bosses: Query<(), With<Boss>>,
+) {
+    for ev in ev_death.read() {
+        if q_bosses.contains(ev.entity) {
+            ev_score.send(ScoreEvent { points: BOSS_BONUS });
+            waves.advance(&director);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Caps on how many particles, enemy bullets and fallers can exist at once,
+//! set in `assets/director.ron`. Without them a slow frame lets enemies
+//! pile up, more enemies means more bullets and explosions, and those make
+//! the next frame slower still.
+//!
+//! Particles and enemy bullets over the cap are recycled, least important
+//! first: the particles closest to fading and the bullets fired longest
+//! ago. Fallers are never taken away from the player; instead the spawner
+//! holds off until there's room.
+
+use bevy_ecs::prelude::*;
+use serde::Deserialize;
+
+use super::{EnemyBullet, Particle, Ramp};
+use crate::engine::{Lifetime, ReleaseExt};
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct EntityBudget {
+    pub particles: usize,
+    pub enemy_bullets: usize,
+    pub fallers: usize,
+}
+
+impl Default for EntityBudget {
+    fn default() -> Self {
+        Self {
+            particles: 800,
+            enemy_bullets: 300,
+            fallers: 60,
+        }
+    }
+}
+
+/// Seconds into the run an entity was spawned, from the
+/// [`Director`](super::Director).
+#[derive(Component, Clone, Copy, Debug)]
+pub struct SpawnedAt(pub f32);
+
+/// Releases `count` of `entities`, in order of `priority`, lowest first.
+/// Ties go to the lower entity, so the choice never depends on query order.
+fn recycle(cmds: &mut Commands, mut entities: Vec<(f32, Entity)>, count: usize) {
+    entities.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    for (_, entity) in entities.into_iter().take(count) {
+        cmds.entity(entity).release();
+    }
+}
+
+pub fn enforce_budget(
+    mut cmds: Commands,
+    ramp: Res<Ramp>,
+    q_particles: Query<(Entity, &Lifetime), With<Particle>>,
+    q_enemy_bullets: Query<(Entity, Option<&SpawnedAt>), With<EnemyBullet>>,
+) {
+    let budget = ramp.budget;
+
+    let over = q_particles.iter().len().saturating_sub(budget.particles);
+    if over > 0 {
+        let particles = q_particles
+            .iter()
+            .map(|(entity, lifetime)| (lifetime.0.as_secs_f32(), entity))
+            .collect();
+
+        recycle(&mut cmds, particles, over);
+    }
+
+    // Bullets restored from a save don't know when they were fired, so
+    // they count as the oldest.
+    let over = q_enemy_bullets
+        .iter()
+        .len()
+        .saturating_sub(budget.enemy_bullets);
+    if over > 0 {
+        let bullets = q_enemy_bullets
+            .iter()
+            .map(|(entity, spawned)| (spawned.map_or(0., |s| s.0), entity))
+            .collect();
+
+        recycle(&mut cmds, bullets, over);
+    }
+}
@@ -0,0 +1,67 @@
+//! Slow motion on demand. Holding [`Action::BulletTime`] slows the
+//! simulation through [`Time::time_scale`] while a meter drains; rendering
+//! keeps going at full speed.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::engine::{Action, Actions, Time};
+
+/// `time_scale` while slowed.
+const SLOW_SCALE: f32 = 0.3;
+
+/// Meter spent per real second of slow motion.
+const DRAIN_RATE: f32 = 0.5;
+
+/// Meter regained per real second while not slowed.
+const RECHARGE_RATE: f32 = 0.1;
+
+/// Least meter needed to start slowing, so tapping the key on an empty
+/// meter doesn't flicker.
+const MIN_METER: f32 = 0.2;
+
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct BulletTime {
+    /// From 0 (empty) to 1 (full).
+    pub meter: f32,
+    pub active: bool,
+}
+
+impl Default for BulletTime {
+    fn default() -> Self {
+        Self {
+            meter: 1.,
+            active: false,
+        }
+    }
+}
+
+pub fn reset_bullet_time(mut bullet_time: ResMut<BulletTime>) {
+    *bullet_time = BulletTime::default();
+}
+
+/// Puts the simulation back to normal speed, e.g. when leaving play.
+pub fn reset_time_scale(mut time: ResMut<Time>) {
+    time.time_scale = 1.;
+}
+
+/// Runs on real time, so slow motion doesn't also slow its own meter.
+pub fn update_bullet_time(
+    actions: Res<Actions>,
+    mut bullet_time: ResMut<BulletTime>,
+    mut time: ResMut<Time>,
+) {
+    let dt = time.unscaled_dt;
+    let held = actions.is_down(Action::BulletTime);
+
+    bullet_time.active = held && (bullet_time.active || bullet_time.meter >= MIN_METER);
+
+    if bullet_time.active {
+        bullet_time.meter = (bullet_time.meter - DRAIN_RATE * dt).max(0.);
+        bullet_time.active = bullet_time.meter > 0.;
+    } else {
+        bullet_time.meter = (bullet_time.meter + RECHARGE_RATE * dt).min(1.);
+    }
+
+    time.time_scale = if bullet_time.active { SLOW_SCALE } else { 1. };
+}
@@ -0,0 +1,129 @@
+//! The two ways to play. Endless ramps up forever; the campaign is a fixed
+//! run of waves from `assets/campaign.ron` that ends once the last one is
+//! cleared. Both only steer the [`Director`], so waves, bosses and the
+//! spawner behave the same either way.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Director, Waves};
+use crate::engine::{CurrentState, GameState, Time, cycle, read_string};
+use crate::t;
+
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GameMode {
+    #[default]
+    Endless,
+    Campaign,
+}
+
+impl GameMode {
+    pub const ALL: [GameMode; 2] = [GameMode::Endless, GameMode::Campaign];
+
+    pub fn name(&self) -> String {
+        let key = match self {
+            GameMode::Endless => "mode.endless",
+            GameMode::Campaign => "mode.campaign",
+        };
+
+        t!(key)
+    }
+
+    /// The mode `offset` steps away from this one, wrapping around.
+    pub fn cycle(&self, offset: isize) -> Self {
+        cycle(&Self::ALL, self, offset)
+    }
+}
+
+pub fn in_mode(mode: GameMode) -> impl Fn(Res<GameMode>) -> bool + Clone {
+    move |current: Res<GameMode>| *current == mode
+}
+
+fn default_wave_duration() -> f32 {
+    super::WAVE_DURATION
+}
+
+fn one() -> f32 {
+    1.
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct CampaignWave {
+    #[serde(default = "default_wave_duration")]
+    pub duration: f32,
+    #[serde(default = "one")]
+    pub spawn_rate: f32,
+    #[serde(default = "one")]
+    pub enemy_speed: f32,
+    /// Faller prefabs that may spawn, plus `"formation"` for formations.
+    pub roster: Vec<String>,
+    /// Opens with a boss, and only ends once it is defeated.
+    #[serde(default)]
+    pub boss: bool,
+}
+
+pub const CAMPAIGN_PATH: &str = "assets/campaign.ron";
+
+/// The campaign's waves in order, loaded from [`CAMPAIGN_PATH`].
+#[derive(Resource, Deserialize, Clone, Debug)]
+pub struct Campaign {
+    pub waves: Vec<CampaignWave>,
+}
+
+impl Campaign {
+    pub fn from_ron(src: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(src)
+    }
+
+    pub async fn load(path: &str) -> Result<Self, String> {
+        let src = read_string(path).await?;
+        Self::from_ron(&src).map_err(|e| e.to_string())
+    }
+}
+
+/// Set when a run ends by finishing the campaign rather than by dying.
+#[derive(Resource, Clone, Copy, Debug, Default)]
+pub struct Victory(pub bool);
+
+pub fn reset_victory(mut victory: ResMut<Victory>) {
+    victory.0 = false;
+}
+
+/// The campaign's driver: points the [`Director`] at the current wave's
+/// settings, and ends the run once every wave is done.
+pub fn follow_campaign(
+    mut director: ResMut<Director>,
+    mut victory: ResMut<Victory>,
+    mut state: ResMut<CurrentState>,
+    campaign: Res<Campaign>,
+    waves: Res<Waves>,
+    time: Res<Time>,
+) {
+    director.elapsed += time.fixed_dt;
+
+    let Some(wave) = campaign.waves.get(waves.number as usize - 1) else {
+        victory.0 = true;
+        state.set(GameState::GameOver);
+        return;
+    };
+
+    director.spawn_rate = wave.spawn_rate;
+    director.enemy_speed = wave.enemy_speed;
+    director.wave_duration = wave.duration;
+    director.follows_ramp = false;
+
+    if director.roster.as_deref() != Some(wave.roster.as_slice()) {
+        director.roster = Some(wave.roster.clone());
+    }
+
+    if director.boss_waves.is_none() {
+        let boss_waves = (1..)
+            .zip(campaign.waves.iter())
+            .filter(|(_, wave)| wave.boss)
+            .map(|(number, _)| number)
+            .collect();
+
+        director.boss_waves = Some(boss_waves);
+    }
+}
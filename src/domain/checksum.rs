@@ -0,0 +1,57 @@
+//! What deterministic mode hashes into the [`ChecksumLog`] every tick: the
+//! [`Rng`], the run's counters and every positioned entity. Entities are
+//! hashed one at a time and the results sorted, so the checksum doesn't
+//! depend on the order the world happens to store them in.
+
+use std::hash::{Hash, Hasher};
+
+use bevy_ecs::prelude::*;
+
+use super::{Bombs, Director, Health, Lives, Position, Score, Velocity, Waves};
+use crate::engine::{Checksum, ChecksumLog, Rng};
+
+pub fn restart_checksums(mut log: ResMut<ChecksumLog>) {
+    log.restart();
+}
+
+pub fn checksum_state(
+    mut log: ResMut<ChecksumLog>,
+    rng: Res<Rng>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+    bombs: Res<Bombs>,
+    waves: Res<Waves>,
+    director: Res<Director>,
+    q_entities: Query<(&Position, Option<&Velocity>, Option<&Health>)>,
+) {
+    let mut entities: Vec<u64> = q_entities
+        .iter()
+        .map(|(position, velocity, health)| {
+            let mut checksum = Checksum::default();
+            checksum.write_vec2(position.0);
+
+            if let Some(velocity) = velocity {
+                checksum.write_vec2(velocity.0);
+            }
+
+            if let Some(health) = health {
+                checksum.write_u32(health.current);
+            }
+
+            checksum.finish()
+        })
+        .collect();
+    entities.sort_unstable();
+
+    let mut checksum = Checksum::default();
+    rng.hash(&mut checksum);
+    checksum.write_u32(score.0);
+    checksum.write_u32(lives.0);
+    checksum.write_u32(bombs.0);
+    checksum.write_u32(waves.number);
+    checksum.write_f32(waves.elapsed);
+    checksum.write_f32(director.elapsed);
+    entities.hash(&mut checksum);
+
+    log.record(checksum.finish());
+}
@@ -0,0 +1,219 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Bullet, EnemyBullet, Faller, Invulnerable, Piercing, Player, Position, Velocity};
+use crate::engine::{Broadphase, Time};
+
+/// Axis-aligned box centered on the entity's [`Position`].
+#[derive(Component, Clone, Copy)]
+pub struct Collider {
+    pub size: Vec2,
+}
+
+impl Collider {
+    pub fn square(size: f32) -> Self {
+        Self {
+            size: vec2(size, size),
+        }
+    }
+
+    pub fn rect(&self, position: &Position) -> Rect {
+        Rect {
+            x: position.0.x - self.size.x / 2.0,
+            y: position.0.y - self.size.y / 2.0,
+            w: self.size.x,
+            h: self.size.y,
+        }
+    }
+}
+
+fn overlaps(a: (&Position, &Collider), b: (&Position, &Collider)) -> bool {
+    a.1.rect(a.0).overlaps(&b.1.rect(b.0))
+}
+
+/// Collides along the whole path moved each tick rather than only where it
+/// ends up, for projectiles fast enough to skip clean over a small faller
+/// between one tick and the next.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Swept;
+
+/// How far along the way from `from` to `to` a box of `size` first touches
+/// `rect`, from 0 to 1, or `None` if it never does. The box is shrunk to
+/// its center and `rect` grown to match, then the path is clipped against
+/// each axis in turn.
+fn sweep(from: Vec2, to: Vec2, size: Vec2, rect: Rect) -> Option<f32> {
+    let min = rect.point() - size / 2.;
+    let max = rect.point() + rect.size() + size / 2.;
+    let delta = to - from;
+    let (mut enter, mut exit) = (0f32, 1f32);
+
+    for axis in 0..2 {
+        if delta[axis] == 0. {
+            if from[axis] < min[axis] || from[axis] > max[axis] {
+                return None;
+            }
+
+            continue;
+        }
+
+        let a = (min[axis] - from[axis]) / delta[axis];
+        let b = (max[axis] - from[axis]) / delta[axis];
+        enter = enter.max(a.min(b));
+        exit = exit.min(a.max(b));
+
+        if enter > exit {
+            return None;
+        }
+    }
+
+    Some(enter)
+}
+
+/// Fallers indexed by where their colliders are, rebuilt each tick so
+/// collisions only test what's nearby rather than every faller. Built by
+/// whichever [`Broadphase`] the [`Config`](crate::engine::Config) picks.
+#[derive(Resource)]
+pub struct FallerIndex(pub Box<dyn Broadphase>);
+
+pub fn update_faller_index(
+    mut index: ResMut<FallerIndex>,
+    q_fallers: Query<(Entity, &Position, &Collider), With<Faller>>,
+) {
+    index.0.clear();
+
+    for (entity, position, collider) in q_fallers.iter() {
+        index.0.insert(entity, collider.rect(position));
+    }
+}
+
+#[derive(Event, Clone, Copy)]
+pub enum CollisionEvent {
+    BulletHitFaller { bullet: Entity, faller: Entity },
+    FallerHitPlayer { faller: Entity },
+    EnemyBulletHitPlayer { bullet: Entity },
+}
+
+/// Fills `out` with the fallers a [`Swept`] bullet passed through this
+/// tick, in the order it reached them. One that doesn't pierce stops at the
+/// first.
+fn sweep_bullet(
+    index: &dyn Broadphase,
+    q_fallers: &Query<(&Position, &Collider), With<Faller>>,
+    (position, collider, velocity, piercing): (&Position, &Collider, &Velocity, bool),
+    dt: f32,
+    nearby: &mut Vec<Entity>,
+    out: &mut Vec<(f32, Entity)>,
+) {
+    out.clear();
+
+    let from = position.0 - velocity.0 * dt;
+    let path = collider
+        .rect(&Position(from))
+        .combine_with(collider.rect(position));
+
+    index.gather(path, nearby);
+
+    for &e_faller in nearby.iter() {
+        let Ok((p_faller, c_faller)) = q_fallers.get(e_faller) else {
+            continue;
+        };
+
+        if let Some(at) = sweep(from, position.0, collider.size, c_faller.rect(p_faller)) {
+            out.push((at, e_faller));
+        }
+    }
+
+    out.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+
+    if !piercing {
+        out.truncate(1);
+    }
+}
+
+pub fn check_collisions(
+    index: Res<FallerIndex>,
+    time: Res<Time>,
+    q_bullets: Query<
+        (
+            Entity,
+            &Position,
+            &Collider,
+            &Velocity,
+            Has<Swept>,
+            Has<Piercing>,
+        ),
+        With<Bullet>,
+    >,
+    q_fallers: Query<(&Position, &Collider), With<Faller>>,
+    q_enemy_bullets: Query<(Entity, &Position, &Collider), With<EnemyBullet>>,
+    q_player: Option<Single<(&Position, &Collider), (With<Player>, Without<Invulnerable>)>>,
+    mut ev_collision: EventWriter<CollisionEvent>,
+    mut nearby: Local<Vec<Entity>>,
+    mut swept: Local<Vec<(f32, Entity)>>,
+) {
+    for (e_bullet, p_bullet, c_bullet, v_bullet, is_swept, piercing) in q_bullets.iter() {
+        if is_swept {
+            let bullet = (p_bullet, c_bullet, v_bullet, piercing);
+            sweep_bullet(
+                index.0.as_ref(),
+                &q_fallers,
+                bullet,
+                time.fixed_dt,
+                &mut nearby,
+                &mut swept,
+            );
+
+            for &(_, e_faller) in swept.iter() {
+                ev_collision.send(CollisionEvent::BulletHitFaller {
+                    bullet: e_bullet,
+                    faller: e_faller,
+                });
+            }
+
+            continue;
+        }
+
+        index.0.gather(c_bullet.rect(p_bullet), &mut nearby);
+
+        for &e_faller in nearby.iter() {
+            let Ok(faller) = q_fallers.get(e_faller) else {
+                continue;
+            };
+
+            if overlaps((p_bullet, c_bullet), faller) {
+                ev_collision.send(CollisionEvent::BulletHitFaller {
+                    bullet: e_bullet,
+                    faller: e_faller,
+                });
+
+                // A bullet without piercing is used up by its first hit.
+                if !piercing {
+                    break;
+                }
+            }
+        }
+    }
+
+    let Some(player) = q_player else {
+        return;
+    };
+    let player = *player;
+
+    index.0.gather(player.1.rect(player.0), &mut nearby);
+
+    for &e_faller in nearby.iter() {
+        let Ok(faller) = q_fallers.get(e_faller) else {
+            continue;
+        };
+
+        if overlaps(faller, player) {
+            ev_collision.send(CollisionEvent::FallerHitPlayer { faller: e_faller });
+        }
+    }
+
+    for (e_bullet, p_bullet, c_bullet) in q_enemy_bullets.iter() {
+        if overlaps((p_bullet, c_bullet), player) {
+            ev_collision.send(CollisionEvent::EnemyBulletHitPlayer { bullet: e_bullet });
+        }
+    }
+}
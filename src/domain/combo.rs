@@ -0,0 +1,90 @@
+//! Quick successive kills build a multiplier that every [`ScoreEvent`] is
+//! scaled by. Getting hit or letting a faller through resets it.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{DamageEvent, DeathEvent, Faller, Player, Position, Velocity};
+use crate::engine::{Screen, Time};
+
+/// Seconds after a kill in which the next one keeps the combo going.
+const COMBO_WINDOW: f32 = 2.;
+
+/// Kills needed for each step up in multiplier.
+const KILLS_PER_STEP: u32 = 5;
+
+const MAX_MULTIPLIER: u32 = 8;
+
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default)]
+pub struct Combo {
+    pub kills: u32,
+    /// Seconds left before the combo lapses.
+    pub remaining: f32,
+}
+
+impl Combo {
+    pub fn multiplier(&self) -> u32 {
+        (1 + self.kills / KILLS_PER_STEP).min(MAX_MULTIPLIER)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Combo::default();
+    }
+}
+
+pub fn reset_combo(mut combo: ResMut<Combo>) {
+    combo.reset();
+}
+
+pub fn tick_combo(mut combo: ResMut<Combo>, time: Res<Time>) {
+    if combo.kills == 0 {
+        return;
+    }
+
+    combo.remaining -= time.fixed_dt;
+
+    if combo.remaining <= 0. {
+        combo.reset();
+    }
+}
+
+pub fn combo_on_death(
+    mut ev_death: EventReader<DeathEvent>,
+    mut combo: ResMut<Combo>,
+    q_fallers: Query<(), With<Faller>>,
+) {
+    for ev in ev_death.read() {
+        if q_fallers.contains(ev.entity) {
+            combo.kills += 1;
+            combo.remaining = COMBO_WINDOW;
+        }
+    }
+}
+
+pub fn break_combo_on_hit(
+    mut ev_damage: EventReader<DamageEvent>,
+    mut combo: ResMut<Combo>,
+    q_player: Query<(), With<Player>>,
+) {
+    if ev_damage.read().any(|ev| q_player.contains(ev.target)) {
+        combo.reset();
+    }
+}
+
+/// Breaks the combo when a faller slips past the bottom of the screen.
+/// Runs before [`despawn_offscreen`](super::despawn_offscreen) removes it.
+pub fn break_combo_on_escape(
+    mut combo: ResMut<Combo>,
+    q_fallers: Query<(&Position, &Velocity), With<Faller>>,
+    screen: Res<Screen>,
+) {
+    let height = screen.height as f32;
+
+    let escaped = q_fallers
+        .iter()
+        .any(|(position, velocity)| position.0.y > height && velocity.0.y >= 0.);
+
+    if escaped {
+        combo.reset();
+    }
+}
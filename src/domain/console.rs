@@ -0,0 +1,56 @@
+//! Console commands for the gameplay state, registered by the
+//! [`GameplayPlugin`](super::GameplayPlugin).
+
+use bevy_ecs::{prelude::*, system::SystemState};
+use macroquad::prelude::*;
+
+use super::{Lives, PrefabSpawner, Prefabs, Score};
+use crate::engine::{CommandResult, MouseInput, parse_arg};
+
+pub fn score_command(world: &mut World, args: &[&str]) -> CommandResult {
+    let mut score = world.resource_mut::<Score>();
+
+    if !args.is_empty() {
+        score.0 = parse_arg(args, 0, "score")?;
+    }
+
+    Ok(format!("Score {}", score.0))
+}
+
+pub fn lives_command(world: &mut World, args: &[&str]) -> CommandResult {
+    let mut lives = world.resource_mut::<Lives>();
+
+    if !args.is_empty() {
+        lives.0 = parse_arg(args, 0, "number of lives")?;
+    }
+
+    Ok(format!("Lives {}", lives.0))
+}
+
+/// Spawns `args[0]` at the texel `args[1..3]`, or under the pointer.
+pub fn spawn_command(world: &mut World, args: &[&str]) -> CommandResult {
+    let name = args.first().ok_or("Missing prefab")?;
+
+    let Some(prefabs) = world.get_resource::<Prefabs>() else {
+        return Err("Prefabs haven't loaded yet".to_string());
+    };
+
+    if prefabs.get(name).is_none() {
+        return Err(format!("No prefab called {}", name));
+    }
+
+    let position = if args.len() > 1 {
+        vec2(parse_arg(args, 1, "x")?, parse_arg(args, 2, "y")?)
+    } else {
+        world.resource::<MouseInput>().position
+    };
+
+    let mut state = SystemState::<PrefabSpawner>::new(world);
+    let entity = state.get_mut(world).spawn_prefab(name, position);
+    state.apply(world);
+
+    match entity {
+        Some(entity) => Ok(format!("Spawned {} as {}", name, entity)),
+        None => Err(format!("Couldn't spawn {}", name)),
+    }
+}
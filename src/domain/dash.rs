@@ -0,0 +1,173 @@
+//! A short burst of speed that passes through danger. The ability lives in
+//! components and a [`DashEvent`], so anything with a [`Dash`] can be told to
+//! dash, whether by the player's input or by AI.
+
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Invulnerable, Player, Position, Velocity};
+use crate::{
+    engine::{
+        Action, Actions, Cooldown, DespawnOnExit, GameState, GestureEvent, Lifetime,
+        MOVE_DIRECTIONS, Pool, Time,
+    },
+    rendering::Glyph,
+};
+
+/// Invulnerability that outlasts the dash itself, so ending up on top of a
+/// faller isn't an instant hit.
+const DASH_GRACE: Duration = Duration::from_millis(100);
+
+/// How long each trail ghost lingers.
+const GHOST_LIFETIME: Duration = Duration::from_millis(150);
+
+/// Lets the entity dash at `speed` for `duration`. Rate limited by the
+/// `Cooldown<Dash>` that [`Dash::bundle`] pairs with it.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Dash {
+    pub speed: f32,
+    pub duration: Duration,
+}
+
+impl Dash {
+    pub fn bundle(speed: f32, duration: Duration, cooldown: Duration) -> (Dash, Cooldown<Dash>) {
+        (Dash { speed, duration }, Cooldown::new(cooldown))
+    }
+}
+
+/// Mid-dash. Overrides the entity's [`Velocity`] until it runs out.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Dashing {
+    pub direction: Vec2,
+    pub remaining: Duration,
+}
+
+/// Faded copy of a dashing entity's glyph.
+#[derive(Component)]
+pub struct DashGhost;
+
+/// Asks `entity` to dash along `direction`. Ignored while its cooldown is
+/// running.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DashEvent {
+    pub entity: Entity,
+    pub direction: Vec2,
+}
+
+/// Dashes on [`Action::Dash`] along the held direction, or on a double tap
+/// of a movement action. With nothing held, it dashes forward. A dash
+/// pressed shortly before the cooldown is up waits for it.
+pub fn player_dash_input(
+    mut actions: ResMut<Actions>,
+    mut ev_gesture: EventReader<GestureEvent>,
+    q_player: Single<(Entity, &Cooldown<Dash>, Has<Dashing>), With<Player>>,
+    mut ev_dash: EventWriter<DashEvent>,
+) {
+    let mut direction = None;
+
+    for ev in ev_gesture.read() {
+        if let GestureEvent::DoubleTap(tapped) = *ev
+            && let Some((_, tap_direction)) = MOVE_DIRECTIONS
+                .into_iter()
+                .find(|(action, _)| *action == tapped)
+        {
+            direction = Some(tap_direction);
+        }
+    }
+
+    let (player, cooldown, dashing) = *q_player;
+
+    if direction.is_none() && cooldown.is_ready() && !dashing && actions.consume(Action::Dash) {
+        let held = actions.movement();
+
+        direction = Some(if held == Vec2::ZERO {
+            Vec2::NEG_Y
+        } else {
+            held
+        });
+    }
+
+    if let Some(direction) = direction {
+        ev_dash.send(DashEvent {
+            entity: player,
+            direction: direction.normalize_or_zero(),
+        });
+    }
+}
+
+pub fn start_dash(
+    mut cmds: Commands,
+    mut ev_dash: EventReader<DashEvent>,
+    mut q_dashers: Query<(&Dash, &mut Cooldown<Dash>, Option<&Invulnerable>), Without<Dashing>>,
+) {
+    for ev in ev_dash.read() {
+        let Ok((dash, mut cooldown, invulnerable)) = q_dashers.get_mut(ev.entity) else {
+            continue;
+        };
+
+        if ev.direction == Vec2::ZERO || !cooldown.trigger() {
+            continue;
+        }
+
+        // Don't cut short a longer grace period, e.g. from a recent hit.
+        let immunity =
+            (dash.duration + DASH_GRACE).max(invulnerable.map_or(Duration::ZERO, |i| i.0));
+
+        cmds.entity(ev.entity).insert((
+            Dashing {
+                direction: ev.direction,
+                remaining: dash.duration,
+            },
+            Invulnerable(immunity),
+        ));
+    }
+}
+
+pub fn apply_dash(
+    mut cmds: Commands,
+    mut q_dashing: Query<(Entity, &Dash, &mut Dashing, &mut Velocity)>,
+    time: Res<Time>,
+) {
+    let dt = Duration::from_secs_f32(time.fixed_dt);
+
+    for (entity, dash, mut dashing, mut velocity) in q_dashing.iter_mut() {
+        velocity.0 = dashing.direction * dash.speed;
+        dashing.remaining = dashing.remaining.saturating_sub(dt);
+
+        if dashing.remaining.is_zero() {
+            cmds.entity(entity).remove::<Dashing>();
+        }
+    }
+}
+
+/// Leaves a fading copy of each dashing entity behind it.
+pub fn dash_trail(
+    mut cmds: Commands,
+    mut pool: ResMut<Pool>,
+    q_dashing: Query<(&Position, &Glyph), With<Dashing>>,
+    time: Res<Time>,
+) {
+    if !time.tick.is_multiple_of(2) {
+        return;
+    }
+
+    for (position, glyph) in q_dashing.iter() {
+        let mut ghost = *glyph;
+        ghost.fg1.a = 0.35;
+        ghost.fg2.a = 0.35;
+        ghost.outline.a = 0.35;
+
+        pool.spawn(
+            &mut cmds,
+            (
+                DashGhost,
+                ghost,
+                *position,
+                Lifetime(GHOST_LIFETIME),
+                DespawnOnExit(GameState::Playing),
+            ),
+        );
+    }
+}
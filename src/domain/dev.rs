@@ -0,0 +1,203 @@
+//! Cheats for testing, only built with the `dev` feature so they never
+//! reach players: `cargo run --features dev`. Each [`DevFlag`] toggles with
+//! its function key or the `dev` console command.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Armored, Bombs, Director, Player, detonate_bomb, follow_campaign, update_director};
+use crate::{
+    engine::{
+        App, CommandResult, FixedUpdate, GameSet, GameState, KeyInput, OnUpdate, Plugin, PreUpdate,
+        Render, in_state, update_console, update_key_input,
+    },
+    rendering::{render_hitboxes, render_shapes, render_sprites},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DevFlag {
+    /// The player takes no damage.
+    GodMode,
+    /// Bombs never run out.
+    InfiniteBombs,
+    /// Fallers stop spawning, leaving waves and bosses to come as usual.
+    NoSpawns,
+    /// Outlines every collider.
+    Hitboxes,
+}
+
+impl DevFlag {
+    pub const ALL: [DevFlag; 4] = [
+        DevFlag::GodMode,
+        DevFlag::InfiniteBombs,
+        DevFlag::NoSpawns,
+        DevFlag::Hitboxes,
+    ];
+
+    /// Also what the console calls it.
+    pub fn name(self) -> &'static str {
+        match self {
+            DevFlag::GodMode => "god",
+            DevFlag::InfiniteBombs => "bombs",
+            DevFlag::NoSpawns => "nospawns",
+            DevFlag::Hitboxes => "hitboxes",
+        }
+    }
+
+    pub fn key(self) -> KeyCode {
+        match self {
+            DevFlag::GodMode => KeyCode::F5,
+            DevFlag::InfiniteBombs => KeyCode::F6,
+            DevFlag::NoSpawns => KeyCode::F7,
+            DevFlag::Hitboxes => KeyCode::F8,
+        }
+    }
+}
+
+#[derive(Resource, Default, Clone, Copy, Debug)]
+pub struct DevFlags {
+    pub god_mode: bool,
+    pub infinite_bombs: bool,
+    pub no_spawns: bool,
+    pub show_hitboxes: bool,
+}
+
+impl DevFlags {
+    pub fn get(&self, flag: DevFlag) -> bool {
+        match flag {
+            DevFlag::GodMode => self.god_mode,
+            DevFlag::InfiniteBombs => self.infinite_bombs,
+            DevFlag::NoSpawns => self.no_spawns,
+            DevFlag::Hitboxes => self.show_hitboxes,
+        }
+    }
+
+    pub fn get_mut(&mut self, flag: DevFlag) -> &mut bool {
+        match flag {
+            DevFlag::GodMode => &mut self.god_mode,
+            DevFlag::InfiniteBombs => &mut self.infinite_bombs,
+            DevFlag::NoSpawns => &mut self.no_spawns,
+            DevFlag::Hitboxes => &mut self.show_hitboxes,
+        }
+    }
+
+    /// Flips `flag`, returning whether it's now on.
+    pub fn toggle(&mut self, flag: DevFlag) -> bool {
+        let on = self.get_mut(flag);
+        *on = !*on;
+        *on
+    }
+}
+
+fn toggled(flag: DevFlag, on: bool) -> String {
+    format!("{} {}", flag.name(), if on { "on" } else { "off" })
+}
+
+pub fn toggle_dev_flags(keys: Res<KeyInput>, mut flags: ResMut<DevFlags>) {
+    for flag in DevFlag::ALL {
+        if keys.is_pressed(flag.key()) {
+            let on = flags.toggle(flag);
+            info!("{}", toggled(flag, on));
+        }
+    }
+}
+
+pub fn dev_command(world: &mut World, args: &[&str]) -> CommandResult {
+    let Some(name) = args.first() else {
+        let flags = world.resource::<DevFlags>();
+        let lines: Vec<String> = DevFlag::ALL
+            .into_iter()
+            .map(|flag| toggled(flag, flags.get(flag)))
+            .collect();
+
+        return Ok(lines.join("\n"));
+    };
+
+    let flag = DevFlag::ALL
+        .into_iter()
+        .find(|flag| flag.name() == *name)
+        .ok_or_else(|| format!("No flag called {}", name))?;
+
+    let on = world.resource_mut::<DevFlags>().toggle(flag);
+    Ok(toggled(flag, on))
+}
+
+/// Armors the player while in god mode. Fallers still collide, so they
+/// break against the ship as usual.
+pub fn apply_god_mode(
+    mut cmds: Commands,
+    flags: Res<DevFlags>,
+    q_player: Option<Single<(Entity, Has<Armored>), With<Player>>>,
+) {
+    let Some(q_player) = q_player else {
+        return;
+    };
+
+    let (player, armored) = *q_player;
+
+    if flags.god_mode && !armored {
+        cmds.entity(player).insert(Armored);
+    } else if !flags.god_mode && armored {
+        cmds.entity(player).remove::<Armored>();
+    }
+}
+
+pub fn refill_bombs(flags: Res<DevFlags>, mut bombs: ResMut<Bombs>) {
+    if flags.infinite_bombs {
+        *bombs = Bombs::default();
+    }
+}
+
+/// Zeroes the spawn rate the director has just worked out.
+pub fn halt_spawns(flags: Res<DevFlags>, mut director: ResMut<Director>) {
+    if flags.no_spawns {
+        director.spawn_rate = 0.;
+    }
+}
+
+pub fn show_hitboxes(flags: Res<DevFlags>) -> bool {
+    flags.show_hitboxes
+}
+
+/// [`DevFlags`] and the systems that act on them.
+pub struct DevPlugin;
+
+impl Plugin for DevPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DevFlags>()
+            .add_console_command(
+                "dev",
+                "[flag]",
+                "toggles a cheat, or lists them: god, bombs, nospawns, hitboxes",
+                dev_command,
+            )
+            .add_systems(
+                PreUpdate,
+                toggle_dev_flags
+                    .after(update_key_input)
+                    .after(update_console)
+                    .in_set(GameSet::Input),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    apply_god_mode,
+                    halt_spawns.after(update_director).after(follow_campaign),
+                )
+                    .in_set(GameSet::Simulation)
+                    .run_if(in_state(GameState::Playing)),
+            )
+            .add_systems(
+                OnUpdate(GameState::Playing),
+                refill_bombs.after(detonate_bomb),
+            )
+            .add_systems(
+                Render,
+                render_hitboxes
+                    .in_set(GameSet::Render)
+                    .after(render_shapes)
+                    .after(render_sprites)
+                    .run_if(show_hitboxes),
+            );
+    }
+}
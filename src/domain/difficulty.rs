@@ -0,0 +1,88 @@
+//! Difficulty presets picked from the main menu. Each one is a set of
+//! multipliers the spawner, damage and scoring systems apply on top of the
+//! numbers in the prefabs.
+
+use crate::t;
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    pub fn name(&self) -> String {
+        let key = match self {
+            Difficulty::Easy => "difficulty.easy",
+            Difficulty::Normal => "difficulty.normal",
+            Difficulty::Hard => "difficulty.hard",
+        };
+
+        t!(key)
+    }
+
+    /// The preset `offset` steps away from this one, stopping at either end.
+    pub fn step(&self, offset: isize) -> Self {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap() as isize;
+        let last = Self::ALL.len() as isize - 1;
+
+        Self::ALL[(index + offset).clamp(0, last) as usize]
+    }
+
+    /// Multiplier on how often fallers spawn.
+    pub fn spawn_rate(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.6,
+            Difficulty::Normal => 1.,
+            Difficulty::Hard => 1.5,
+        }
+    }
+
+    /// Multiplier on the speed of fallers and enemy bullets.
+    pub fn enemy_speed(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.8,
+            Difficulty::Normal => 1.,
+            Difficulty::Hard => 1.25,
+        }
+    }
+
+    /// Multiplier on faller health, rounded up so nothing drops to zero.
+    pub fn enemy_health(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.,
+            Difficulty::Hard => 1.5,
+        }
+    }
+
+    /// Multiplier on damage the player takes, rounded up.
+    pub fn player_damage(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.,
+            Difficulty::Hard => 1.5,
+        }
+    }
+
+    /// Multiplier on every point scored.
+    pub fn score(&self) -> f32 {
+        match self {
+            Difficulty::Easy => 0.5,
+            Difficulty::Normal => 1.,
+            Difficulty::Hard => 1.5,
+        }
+    }
+
+    /// Scales `amount` by `multiplier`, rounding up so non-zero stays
+    /// non-zero.
+    pub fn scale(amount: u32, multiplier: f32) -> u32 {
+        (amount as f32 * multiplier).ceil() as u32
+    }
+}
@@ -0,0 +1,147 @@
+//! The [`Director`] holds the knobs the spawner and waves follow: how often
+//! fallers come, how fast, which kinds and when bosses appear. Each
+//! [`GameMode`](super::GameMode) drives it differently. Endless ramps it up
+//! the longer a run lasts, following `assets/director.ron`; the campaign
+//! sets it wave by wave.
+
+use std::collections::HashMap;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{BOSS_EVERY, BossKind, EntityBudget, Sector, WAVE_DURATION};
+use crate::engine::{Time, read_string};
+
+/// Eases from `start` toward `cap` without ever quite reaching it, covering
+/// half the remaining distance every `half_life` seconds.
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct SoftCap {
+    pub start: f32,
+    pub cap: f32,
+    pub half_life: f32,
+}
+
+impl SoftCap {
+    pub fn at(&self, seconds: f32) -> f32 {
+        let remaining = 0.5f32.powf(seconds / self.half_life);
+        self.cap - (self.cap - self.start) * remaining
+    }
+}
+
+pub const RAMP_PATH: &str = "assets/director.ron";
+
+/// The curves the [`Director`] follows, loaded from [`RAMP_PATH`].
+#[derive(Resource, Deserialize, Clone, Debug)]
+pub struct Ramp {
+    pub spawn_rate: SoftCap,
+    pub enemy_speed: SoftCap,
+    /// Seconds into a run before each faller prefab may spawn, plus
+    /// `"formation"` for formations. Anything not listed is available from
+    /// the start.
+    #[serde(default)]
+    pub unlocks: HashMap<String, f32>,
+    /// Faller prefabs the spawner picks from, with their relative weights.
+    /// The first is the staple every [`Sector`] includes.
+    pub fallers: Vec<(String, u32)>,
+    #[serde(default)]
+    pub budget: EntityBudget,
+}
+
+impl Ramp {
+    pub fn from_ron(src: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(src)
+    }
+
+    pub async fn load(path: &str) -> Result<Self, String> {
+        let src = read_string(path).await?;
+        Self::from_ron(&src).map_err(|e| e.to_string())
+    }
+}
+
+/// How far into the run we are and what the spawner should be doing about
+/// it. Multipliers are applied on top of [`Difficulty`](super::Difficulty).
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct Director {
+    /// Seconds of play this run.
+    pub elapsed: f32,
+    pub spawn_rate: f32,
+    pub enemy_speed: f32,
+    /// Seconds of regular fallers before the next wave.
+    pub wave_duration: f32,
+    /// Faller prefabs (and `"formation"`) allowed to spawn. `None` allows
+    /// all of them.
+    pub roster: Option<Vec<String>>,
+    /// Waves that open with a boss. `None` means every
+    /// [`BOSS_EVERY`](super::BOSS_EVERY)th wave.
+    pub boss_waves: Option<Vec<u32>>,
+    /// Which boss those waves bring.
+    pub boss: BossKind,
+    /// Whether the [`Ramp`]'s unlock times gate the roster.
+    pub follows_ramp: bool,
+}
+
+impl Default for Director {
+    fn default() -> Self {
+        Self {
+            elapsed: 0.,
+            spawn_rate: 1.,
+            enemy_speed: 1.,
+            wave_duration: WAVE_DURATION,
+            roster: None,
+            boss_waves: None,
+            boss: BossKind::default(),
+            follows_ramp: true,
+        }
+    }
+}
+
+impl Director {
+    pub fn is_unlocked(&self, ramp: &Ramp, prefab: &str) -> bool {
+        let listed = self
+            .roster
+            .as_ref()
+            .is_none_or(|roster| roster.iter().any(|name| name == prefab));
+
+        let ramped = !self.follows_ramp
+            || ramp
+                .unlocks
+                .get(prefab)
+                .is_none_or(|after| self.elapsed >= *after);
+
+        listed && ramped
+    }
+
+    pub fn is_boss_wave(&self, number: u32) -> bool {
+        match &self.boss_waves {
+            Some(waves) => waves.contains(&number),
+            None => number.is_multiple_of(BOSS_EVERY),
+        }
+    }
+
+    fn follow(&mut self, ramp: &Ramp) {
+        self.spawn_rate = ramp.spawn_rate.at(self.elapsed);
+        self.enemy_speed = ramp.enemy_speed.at(self.elapsed);
+    }
+}
+
+pub fn reset_director(mut director: ResMut<Director>, ramp: Res<Ramp>) {
+    *director = Director::default();
+    director.follow(&ramp);
+}
+
+/// Endless mode's driver: ramps the [`Director`] along the [`Ramp`], scaled
+/// by the current [`Sector`]'s hazard density.
+pub fn update_director(
+    mut director: ResMut<Director>,
+    ramp: Res<Ramp>,
+    sector: Option<Res<Sector>>,
+    time: Res<Time>,
+) {
+    director.elapsed += time.fixed_dt;
+    director.follow(&ramp);
+
+    if let Some(sector) = sector {
+        director.spawn_rate *= sector.hazard_density;
+    }
+}
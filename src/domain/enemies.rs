@@ -0,0 +1,65 @@
+//! Movement patterns for fallers. Each behavior only steers the entity's
+//! [`Velocity`]; [`apply_velocity`](super::apply_velocity) still moves it.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Player, Position, Velocity};
+use crate::engine::Time;
+
+/// Sways side to side in a sine wave while falling.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Weave {
+    /// Widest the sway gets from the center line, in texels.
+    pub amplitude: f32,
+    /// Full swings per second.
+    pub frequency: f32,
+    /// Seconds since spawning.
+    #[serde(default)]
+    pub elapsed: f32,
+}
+
+/// Falls faster and faster.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Dive {
+    /// Texels per second, per second.
+    pub acceleration: f32,
+}
+
+/// Slides sideways to line up with the player.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Strafe {
+    /// Top horizontal speed, in texels per second.
+    pub speed: f32,
+}
+
+pub fn weave(mut q_weavers: Query<(&mut Weave, &mut Velocity)>, time: Res<Time>) {
+    let tau = std::f32::consts::TAU;
+
+    for (mut weave, mut velocity) in q_weavers.iter_mut() {
+        weave.elapsed += time.fixed_dt;
+
+        let angular = tau * weave.frequency;
+        velocity.0.x = weave.amplitude * angular * (angular * weave.elapsed).cos();
+    }
+}
+
+pub fn dive(mut q_divers: Query<(&Dive, &mut Velocity)>, time: Res<Time>) {
+    for (dive, mut velocity) in q_divers.iter_mut() {
+        velocity.0.y += dive.acceleration * time.fixed_dt;
+    }
+}
+
+pub fn strafe(
+    mut q_strafers: Query<(&Strafe, &Position, &mut Velocity), Without<Player>>,
+    q_player: Option<Single<&Position, With<Player>>>,
+) {
+    let Some(player) = q_player else {
+        return;
+    };
+
+    for (strafe, position, mut velocity) in q_strafers.iter_mut() {
+        let gap = player.0.x - position.0.x;
+        velocity.0.x = gap.clamp(-strafe.speed, strafe.speed);
+    }
+}
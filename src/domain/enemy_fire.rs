@@ -0,0 +1,56 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Player, Position, PrefabSpawner};
+use crate::engine::Cooldown;
+
+/// A shot fired by an enemy. Hurts the player, but can't be shot down.
+#[derive(Component)]
+pub struct EnemyBullet;
+
+/// Marker for an enemy's [`Cooldown`] between shots.
+pub struct EnemyShoot;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum FirePattern {
+    /// A single shot straight at the player.
+    Aimed,
+    /// Shots evenly spaced in every direction.
+    Ring(u32),
+}
+
+/// Fires [`EnemyBullet`]s whenever its `Cooldown<EnemyShoot>` is ready.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Shooter {
+    pub pattern: FirePattern,
+    /// Seconds between shots.
+    pub interval: f32,
+}
+
+pub fn enemy_fire(
+    mut spawner: PrefabSpawner,
+    mut q_shooters: Query<(&Shooter, &Position, &mut Cooldown<EnemyShoot>)>,
+    q_player: Option<Single<&Position, With<Player>>>,
+) {
+    let target = q_player.map(|player| player.0);
+
+    for (shooter, position, mut cooldown) in q_shooters.iter_mut() {
+        if !cooldown.trigger() {
+            continue;
+        }
+
+        match shooter.pattern {
+            FirePattern::Aimed => {
+                let direction = target.map_or(Vec2::Y, |target| target - position.0);
+                spawner.spawn_prefab_aimed("enemy_bullet", position.0, direction);
+            }
+            FirePattern::Ring(count) => {
+                for i in 0..count {
+                    let angle = std::f32::consts::TAU * i as f32 / count as f32;
+                    spawner.spawn_prefab_aimed("enemy_bullet", position.0, Vec2::from_angle(angle));
+                }
+            }
+        }
+    }
+}
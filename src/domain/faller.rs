@@ -0,0 +1,141 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    DeathEvent, Director, Formation, Position, PrefabSpawner, Ramp, Telegraph, Velocity, telegraph,
+};
+use crate::engine::{Rng, Screen};
+
+#[derive(Component)]
+pub struct Faller;
+
+/// Breaks into smaller fragments spawned from `prefab` when destroyed.
+#[derive(Component, Serialize, Deserialize, Clone, Debug)]
+pub struct SplitsOnDeath {
+    pub prefab: String,
+    /// Inclusive range of fragments to spawn.
+    pub count: (u32, u32),
+}
+
+/// How much of the parent's velocity each fragment keeps.
+const FRAGMENT_INHERIT: f32 = 1.25;
+
+/// Random sideways kick added to each fragment, in texels per second.
+const FRAGMENT_SPREAD: (f32, f32) = (40., 90.);
+
+pub fn split_on_death(
+    mut ev_death: EventReader<DeathEvent>,
+    mut spawner: PrefabSpawner,
+    q_splitting: Query<(&Position, &Velocity, &SplitsOnDeath)>,
+) {
+    for ev in ev_death.read() {
+        let Ok((position, velocity, splits)) = q_splitting.get(ev.entity) else {
+            continue;
+        };
+
+        let (min, max) = splits.count;
+        let count = spawner.rng.gen_range(min, max + 1);
+
+        for i in 0..count {
+            // Fan the fragments out evenly, then jitter each one a little.
+            let slice = std::f32::consts::TAU / count as f32;
+            let angle = slice * i as f32 + spawner.rng.gen_range(0., slice);
+            let kick = spawner.rng.gen_range(FRAGMENT_SPREAD.0, FRAGMENT_SPREAD.1);
+            let fragment_velocity = velocity.0 * FRAGMENT_INHERIT + Vec2::from_angle(angle) * kick;
+
+            if let Some(fragment) = spawner.spawn_prefab(&splits.prefab, position.0) {
+                spawner
+                    .cmds
+                    .entity(fragment)
+                    .insert(Velocity(fragment_velocity));
+            }
+        }
+    }
+}
+
+/// Chance per tick of a faller spawning, before [`Difficulty`](super::Difficulty)
+/// and the [`Director`] are applied.
+const SPAWN_CHANCE: f32 = 0.04;
+
+/// Share of spawns that are a whole [`Formation`] instead of a single faller.
+const FORMATION_CHANCE: f32 = 0.1;
+
+/// What formations are made of.
+const FORMATION_PREFAB: &str = "faller";
+
+/// Key in the [`Ramp`]'s unlocks that gates formations.
+const FORMATION_UNLOCK: &str = "formation";
+
+/// Rolls a prefab from the [`Ramp`]'s faller weights, skipping any the
+/// [`Director`] hasn't unlocked yet. `None` if the table is empty.
+fn pick_faller<'a>(rng: &mut Rng, director: &Director, ramp: &'a Ramp) -> Option<&'a str> {
+    let available = || {
+        ramp.fallers
+            .iter()
+            .filter(|(name, _)| director.is_unlocked(ramp, name))
+    };
+
+    let total = available().map(|(_, weight)| weight).sum();
+    let mut roll = rng.gen_range(0, total);
+
+    for (name, weight) in available() {
+        if roll < *weight {
+            return Some(name);
+        }
+
+        roll -= weight;
+    }
+
+    ramp.fallers.first().map(|(name, _)| name.as_str())
+}
+
+/// Holds off while the fallers on screen and those about to hatch would
+/// go over the [`EntityBudget`](super::EntityBudget).
+pub fn spawn_fallers(
+    mut spawner: PrefabSpawner,
+    ramp: Res<Ramp>,
+    screen: Res<Screen>,
+    q_fallers: Query<(), With<Faller>>,
+    q_telegraphs: Query<&Telegraph>,
+) {
+    let hatching: usize = q_telegraphs
+        .iter()
+        .map(|t| t.formation.map_or(1, |f| f.count() as usize))
+        .sum();
+
+    if q_fallers.iter().len() + hatching >= ramp.budget.fallers {
+        return;
+    }
+
+    let chance = SPAWN_CHANCE * spawner.difficulty.spawn_rate() * spawner.director.spawn_rate;
+
+    if spawner.rng.next_f32() >= chance {
+        return;
+    }
+
+    let formation = spawner.director.is_unlocked(&ramp, FORMATION_UNLOCK)
+        && spawner.rng.next_f32() < FORMATION_CHANCE;
+
+    let (name, formation) = if formation {
+        (FORMATION_PREFAB, Some(Formation::random(&mut spawner.rng)))
+    } else {
+        let Some(name) = pick_faller(&mut spawner.rng, &spawner.director, &ramp) else {
+            return;
+        };
+
+        (name, None)
+    };
+
+    let Some(size) = spawner.prefabs.get(name).map(|p| p.size.max()) else {
+        return;
+    };
+
+    // Keep the whole formation on screen, not just the leader.
+    let reach = size / 2. + formation.map_or(0., |f| f.half_width());
+    let min_x = reach;
+    let max_x = screen.width as f32 - reach;
+    let x = spawner.rng.gen_range(min_x, max_x);
+
+    telegraph(&mut spawner.cmds, name, formation, vec2(x, -size));
+}
@@ -0,0 +1,199 @@
+//! Groups of fallers that fly in together. Followers are children of a
+//! leader in the [hierarchy](super::HierarchyCommands), so they hold their
+//! places for free; shooting the leader breaks them apart.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{
+    Children, DeathEvent, HierarchyCommands, Offset, Parent, PrefabSpawner, SurvivesParent,
+    Velocity,
+};
+use crate::engine::{Rng, Time};
+
+/// Speed followers are flung outward at when their leader dies, in texels
+/// per second.
+const SCATTER_SPEED: f32 = 90.;
+
+#[derive(Clone, Copy, Debug)]
+pub enum Formation {
+    /// Followers trail back from the leader in two diagonal arms.
+    V { count: u32, spacing: f32 },
+    /// Followers side by side with the leader in the middle.
+    Line { count: u32, spacing: f32 },
+    /// Followers queued up behind the leader, swaying side to side with a
+    /// lag that grows down the chain.
+    SineChain {
+        count: u32,
+        spacing: f32,
+        amplitude: f32,
+        frequency: f32,
+    },
+}
+
+impl Formation {
+    pub fn random(rng: &mut Rng) -> Self {
+        match rng.gen_range(0, 3) {
+            0 => Formation::V {
+                count: 4,
+                spacing: 28.,
+            },
+            1 => Formation::Line {
+                count: 4,
+                spacing: 32.,
+            },
+            _ => Formation::SineChain {
+                count: 5,
+                spacing: 24.,
+                amplitude: 32.,
+                frequency: 0.6,
+            },
+        }
+    }
+
+    pub fn count(&self) -> u32 {
+        match *self {
+            Formation::V { count, .. }
+            | Formation::Line { count, .. }
+            | Formation::SineChain { count, .. } => count,
+        }
+    }
+
+    /// Resting offset of the `i`th follower from the leader.
+    pub fn slot(&self, i: u32) -> Vec2 {
+        match *self {
+            Formation::V { spacing, .. } => {
+                let rank = (i / 2 + 1) as f32;
+                let side = if i.is_multiple_of(2) { -1. } else { 1. };
+                vec2(side * rank, -rank) * spacing
+            }
+            Formation::Line { count, spacing } => {
+                // Leave the middle of the line for the leader.
+                let half = count / 2;
+                let column = if i < half {
+                    i as f32 - half as f32
+                } else {
+                    (i - half + 1) as f32
+                };
+                vec2(column * spacing, 0.)
+            }
+            Formation::SineChain { spacing, .. } => vec2(0., -((i + 1) as f32) * spacing),
+        }
+    }
+
+    /// How far the formation reaches to either side of the leader.
+    pub fn half_width(&self) -> f32 {
+        match *self {
+            Formation::SineChain { amplitude, .. } => amplitude,
+            _ => (0..self.count())
+                .map(|i| self.slot(i).x.abs())
+                .fold(0., f32::max),
+        }
+    }
+}
+
+/// Leads a formation. Its death scatters the followers.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FormationLeader;
+
+/// A follower's place in its formation.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct FormationSlot {
+    pub base: Vec2,
+    /// Side to side sway in texels, zero for rigid formations.
+    pub amplitude: f32,
+    pub frequency: f32,
+    /// Seconds this follower lags behind the leader's sway.
+    pub lag: f32,
+    pub elapsed: f32,
+}
+
+impl PrefabSpawner<'_, '_> {
+    /// Spawns `formation` out of copies of `name`, led from `position`. The
+    /// whole group shares the leader's velocity.
+    pub fn spawn_formation(
+        &mut self,
+        name: &str,
+        formation: Formation,
+        position: Vec2,
+    ) -> Option<Entity> {
+        let leader = self.spawn_prefab(name, position)?;
+        let speed = self.prefabs.get(name)?.speed.roll(&mut self.rng)
+            * self.difficulty.enemy_speed()
+            * self.director.enemy_speed;
+        let velocity = Velocity(vec2(0., speed));
+
+        self.cmds.entity(leader).insert((FormationLeader, velocity));
+
+        let (amplitude, frequency) = match formation {
+            Formation::SineChain {
+                amplitude,
+                frequency,
+                ..
+            } => (amplitude, frequency),
+            _ => (0., 0.),
+        };
+
+        for i in 0..formation.count() {
+            let base = formation.slot(i);
+            let Some(follower) = self.spawn_prefab(name, position + base) else {
+                continue;
+            };
+
+            self.cmds
+                .entity(follower)
+                .insert((
+                    velocity,
+                    SurvivesParent,
+                    FormationSlot {
+                        base,
+                        amplitude,
+                        frequency,
+                        lag: i as f32 * 0.15,
+                        elapsed: 0.,
+                    },
+                ))
+                .set_parent(leader, base);
+        }
+
+        Some(leader)
+    }
+}
+
+/// Sways followers of sine-chains around their resting slot.
+pub fn sway_formations(mut q_slots: Query<(&mut FormationSlot, &mut Offset)>, time: Res<Time>) {
+    let tau = std::f32::consts::TAU;
+
+    for (mut slot, mut offset) in q_slots.iter_mut() {
+        slot.elapsed += time.fixed_dt;
+
+        let phase = tau * slot.frequency * (slot.elapsed - slot.lag);
+        offset.0 = slot.base + vec2(slot.amplitude * phase.sin(), 0.);
+    }
+}
+
+/// Breaks up a formation when its leader dies, flinging the followers
+/// outward from where the leader was.
+pub fn scatter_formations(
+    mut cmds: Commands,
+    mut ev_death: EventReader<DeathEvent>,
+    q_leaders: Query<&Children, With<FormationLeader>>,
+    mut q_followers: Query<(&Offset, &mut Velocity), (With<FormationSlot>, With<Parent>)>,
+) {
+    for ev in ev_death.read() {
+        let Ok(children) = q_leaders.get(ev.entity) else {
+            continue;
+        };
+
+        for &follower in children.0.iter() {
+            let Ok((offset, mut velocity)) = q_followers.get_mut(follower) else {
+                continue;
+            };
+
+            velocity.0 += offset.0.normalize_or(Vec2::Y) * SCATTER_SPEED;
+
+            cmds.entity(follower)
+                .remove::<(Parent, Offset, FormationSlot)>();
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Collisions become [`DamageEvent`]s, damage lowers [`Health`], and
+//! anything that runs out sends a [`DeathEvent`]. Score, particles and lives
+//! all react to deaths rather than to raw collisions.
+
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{CollisionEvent, Difficulty, Invulnerable, Player};
+use crate::{engine::ReleaseExt, rendering::Glyph};
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Health {
+    pub current: u32,
+    pub max: u32,
+}
+
+impl Health {
+    pub fn new(max: u32) -> Self {
+        Self { current: max, max }
+    }
+}
+
+/// Takes no damage. Used for boss cores until their segments are destroyed.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Armored;
+
+/// Absorbs whole hits before [`Health`] is touched, one charge per hit.
+/// While charged, the owner's glyph outline is tinted to show it.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Shield {
+    pub charges: u32,
+    pub max: u32,
+    /// The glyph's own outline colour, kept while it is tinted.
+    base_outline: Option<Color>,
+}
+
+impl Shield {
+    pub fn new(charges: u32, max: u32) -> Self {
+        Self {
+            charges: charges.min(max),
+            max,
+            base_outline: None,
+        }
+    }
+
+    /// The outline the glyph had before it was tinted, if it is tinted.
+    pub fn base_outline(&self) -> Option<Color> {
+        self.base_outline
+    }
+
+    pub fn recharge(&mut self) {
+        self.charges = (self.charges + 1).min(self.max);
+    }
+}
+
+const SHIELD_OUTLINE: Color = Color::new(0.3, 0.8, 1., 1.);
+
+/// Grants [`Invulnerable`] for the given time whenever the entity takes
+/// damage and survives.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct InvulnerableOnHit(pub Duration);
+
+/// Survives crashing into the player instead of being destroyed by it.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Massive;
+
+/// A bullet that passes through what it hits instead of being used up,
+/// damaging each entity at most once.
+#[derive(Component, Clone, Debug, Default)]
+pub struct Piercing {
+    pub hit: Vec<Entity>,
+}
+
+/// Damage dealt to whatever the entity collides with. Entities without it
+/// deal 1.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Damage(pub u32);
+
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DamageEvent {
+    pub target: Entity,
+    pub amount: u32,
+}
+
+/// Sent when damage gets through to an entity, whether its [`Shield`] or
+/// its [`Health`] takes it.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct HitEvent {
+    pub entity: Entity,
+    /// Health lost, zero when a shield took the hit.
+    pub damage: u32,
+}
+
+/// Sent once when an entity's [`Health`] reaches zero. The entity is still
+/// alive while this is handled, so readers can look at its components.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct DeathEvent {
+    pub entity: Entity,
+}
+
+fn damage_of(q_damage: &Query<&Damage>, entity: Entity) -> u32 {
+    q_damage.get(entity).map_or(1, |damage| damage.0)
+}
+
+/// Turns collisions into damage. Bullets are used up on impact unless they
+/// are [`Piercing`], and fallers that reach the player crash into it unless
+/// they are [`Massive`].
+pub fn damage_on_collision(
+    mut cmds: Commands,
+    mut ev_collision: EventReader<CollisionEvent>,
+    mut ev_damage: EventWriter<DamageEvent>,
+    q_damage: Query<&Damage>,
+    q_massive: Query<(), With<Massive>>,
+    mut q_piercing: Query<&mut Piercing>,
+    q_player: Single<Entity, With<Player>>,
+    difficulty: Res<Difficulty>,
+) {
+    let to_player = |amount| Difficulty::scale(amount, difficulty.player_damage());
+
+    for ev in ev_collision.read() {
+        match *ev {
+            CollisionEvent::BulletHitFaller { bullet, faller } => {
+                if let Ok(mut piercing) = q_piercing.get_mut(bullet) {
+                    if piercing.hit.contains(&faller) {
+                        continue;
+                    }
+
+                    piercing.hit.push(faller);
+                } else {
+                    cmds.entity(bullet).release();
+                }
+
+                ev_damage.send(DamageEvent {
+                    target: faller,
+                    amount: damage_of(&q_damage, bullet),
+                });
+            }
+            CollisionEvent::FallerHitPlayer { faller } => {
+                ev_damage.send(DamageEvent {
+                    target: *q_player,
+                    amount: to_player(damage_of(&q_damage, faller)),
+                });
+
+                if !q_massive.contains(faller) {
+                    cmds.entity(faller).try_despawn();
+                }
+            }
+            CollisionEvent::EnemyBulletHitPlayer { bullet } => {
+                ev_damage.send(DamageEvent {
+                    target: *q_player,
+                    amount: to_player(damage_of(&q_damage, bullet)),
+                });
+                cmds.entity(bullet).release();
+            }
+        }
+    }
+}
+
+pub fn apply_damage(
+    mut cmds: Commands,
+    mut ev_damage: EventReader<DamageEvent>,
+    mut ev_hit: EventWriter<HitEvent>,
+    mut ev_death: EventWriter<DeathEvent>,
+    mut q_health: Query<
+        (&mut Health, Option<&InvulnerableOnHit>, Option<&mut Shield>),
+        (Without<Invulnerable>, Without<Armored>),
+    >,
+    mut shielded: Local<Vec<Entity>>,
+) {
+    shielded.clear();
+
+    for ev in ev_damage.read() {
+        let Ok((mut health, on_hit, shield)) = q_health.get_mut(ev.target) else {
+            continue;
+        };
+
+        if health.current == 0 || shielded.contains(&ev.target) {
+            continue;
+        }
+
+        let before = health.current;
+
+        match shield {
+            Some(mut shield) if shield.charges > 0 => shield.charges -= 1,
+            _ => health.current = health.current.saturating_sub(ev.amount),
+        }
+
+        ev_hit.send(HitEvent {
+            entity: ev.target,
+            damage: before - health.current,
+        });
+
+        if health.current == 0 {
+            ev_death.send(DeathEvent { entity: ev.target });
+        } else if let Some(on_hit) = on_hit {
+            // Later hits this tick are ignored too, not just later ticks.
+            shielded.push(ev.target);
+            cmds.entity(ev.target).insert(Invulnerable(on_hit.0));
+        }
+    }
+}
+
+/// Tints the outline of shielded glyphs, restoring it once the shield is
+/// spent. Only the colour is touched so blinking alpha is left alone.
+pub fn sync_shield_outline(mut q_shields: Query<(&mut Shield, &mut Glyph), Changed<Shield>>) {
+    for (mut shield, mut glyph) in q_shields.iter_mut() {
+        let alpha = glyph.outline.a;
+
+        if shield.charges > 0 {
+            if shield.base_outline.is_none() {
+                shield.base_outline = Some(glyph.outline);
+            }
+
+            glyph.outline = Color {
+                a: alpha,
+                ..SHIELD_OUTLINE
+            };
+        } else if let Some(base) = shield.base_outline.take() {
+            glyph.outline = Color { a: alpha, ..base };
+        }
+    }
+}
+
+/// Despawns everything that died except the player, whose death is handled
+/// by [`lose_life_on_death`](super::lose_life_on_death).
+pub fn despawn_dead(
+    mut cmds: Commands,
+    mut ev_death: EventReader<DeathEvent>,
+    q_players: Query<(), With<Player>>,
+) {
+    for ev in ev_death.read() {
+        if !q_players.contains(ev.entity) {
+            cmds.entity(ev.entity).try_despawn();
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Parent/child attachments. A child's [`Position`] is derived from its
+//! parent's every tick, and children are despawned along with their parent
+//! unless they are marked [`SurvivesParent`].
+
+use bevy_ecs::{entity::Entities, prelude::*, world::EntityWorldMut};
+use macroquad::prelude::*;
+
+use super::Position;
+
+/// The entity this one is attached to.
+#[derive(Component, Clone, Copy)]
+pub struct Parent(pub Entity);
+
+/// Entities attached to this one. May briefly contain despawned entities.
+#[derive(Component, Default)]
+pub struct Children(pub Vec<Entity>);
+
+/// Position of a child relative to its parent.
+#[derive(Component, Clone, Copy, Default)]
+pub struct Offset(pub Vec2);
+
+/// Detached instead of despawned when its parent goes away, carrying on with
+/// its own [`Velocity`](super::Velocity).
+#[derive(Component, Clone, Copy)]
+pub struct SurvivesParent;
+
+pub trait HierarchyCommands {
+    /// Attaches this entity to `parent` at `offset` from its position.
+    fn set_parent(&mut self, parent: Entity, offset: Vec2) -> &mut Self;
+}
+
+impl HierarchyCommands for EntityCommands<'_> {
+    fn set_parent(&mut self, parent: Entity, offset: Vec2) -> &mut Self {
+        self.queue(move |mut entity: EntityWorldMut| {
+            let child = entity.id();
+
+            entity.insert((Parent(parent), Offset(offset)));
+            entity.world_scope(|world| {
+                let Ok(mut parent) = world.get_entity_mut(parent) else {
+                    return;
+                };
+
+                match parent.get_mut::<Children>() {
+                    Some(mut children) => children.0.push(child),
+                    None => {
+                        parent.insert(Children(vec![child]));
+                    }
+                }
+            });
+        })
+    }
+}
+
+/// Moves every child to its parent's position plus its [`Offset`], walking
+/// down from the roots so nested attachments follow their whole chain.
+pub fn propagate_positions(
+    q_roots: Query<Entity, (With<Children>, Without<Parent>)>,
+    q_children: Query<&Children>,
+    q_offsets: Query<&Offset>,
+    mut q_positions: Query<&mut Position>,
+) {
+    let mut stack = Vec::new();
+
+    for root in q_roots.iter() {
+        stack.push(root);
+
+        while let Some(entity) = stack.pop() {
+            let (Ok(children), Ok(origin)) = (q_children.get(entity), q_positions.get(entity))
+            else {
+                continue;
+            };
+            let origin = origin.0;
+
+            for &child in children.0.iter() {
+                let (Ok(offset), Ok(mut position)) =
+                    (q_offsets.get(child), q_positions.get_mut(child))
+                else {
+                    continue;
+                };
+
+                position.0 = origin + offset.0;
+                stack.push(child);
+            }
+        }
+    }
+}
+
+/// Despawns children whose parent is gone, along with their own children.
+/// Those marked [`SurvivesParent`] are detached and keep theirs.
+pub fn despawn_orphans(
+    mut cmds: Commands,
+    entities: &Entities,
+    q_parents: Query<(Entity, &Parent, Has<SurvivesParent>)>,
+    q_children: Query<&Children>,
+) {
+    let mut stack = Vec::new();
+
+    for (entity, parent, survives) in q_parents.iter() {
+        if entities.contains(parent.0) {
+            continue;
+        }
+
+        if survives {
+            cmds.entity(entity).remove::<(Parent, Offset)>();
+        } else {
+            stack.push(entity);
+        }
+    }
+
+    while let Some(entity) = stack.pop() {
+        if let Ok(children) = q_children.get(entity) {
+            stack.extend(children.0.iter().copied());
+        }
+
+        cmds.entity(entity).try_despawn();
+    }
+}
@@ -0,0 +1,138 @@
+//! The top ten runs, kept in [`HIGH_SCORES_PATH`].
+
+use bevy_ecs::prelude::*;
+use macroquad::{miniquad::date, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::Score;
+use crate::engine::{CurrentState, GameState, KeyInput, Rng, TextInput, Time, write_atomic};
+
+pub const HIGH_SCORES_PATH: &str = "highscore.dat";
+
+pub const MAX_HIGH_SCORES: usize = 10;
+
+pub const NAME_LENGTH: usize = 3;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HighScore {
+    pub score: u32,
+    pub name: String,
+    /// Seconds since the Unix epoch.
+    pub date: u64,
+    pub seed: u64,
+}
+
+/// Sorted from best to worst, never longer than [`MAX_HIGH_SCORES`].
+#[derive(Resource, Serialize, Deserialize, Default, Debug)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>,
+}
+
+impl HighScores {
+    /// Reads the table, falling back to the single bare number older builds
+    /// wrote. A missing or unreadable file is an empty table.
+    pub fn load(path: &str) -> Self {
+        let Ok(src) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        if let Ok(table) = ron::from_str(&src) {
+            return table;
+        }
+
+        match src.trim().parse() {
+            Ok(score) => Self {
+                entries: vec![HighScore {
+                    score,
+                    name: "???".to_string(),
+                    date: 0,
+                    seed: 0,
+                }],
+            },
+            Err(_) => {
+                warn!("Ignoring unreadable {}", path);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: &str) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|src| write_atomic(path, &src).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            warn!("Failed to save {}: {}", path, e);
+        }
+    }
+
+    pub fn qualifies(&self, score: u32) -> bool {
+        score > 0
+            && (self.entries.len() < MAX_HIGH_SCORES
+                || self.entries.iter().any(|entry| score > entry.score))
+    }
+
+    /// Inserts `entry` in rank order, dropping whatever falls off the end.
+    pub fn insert(&mut self, entry: HighScore) {
+        let rank = self
+            .entries
+            .iter()
+            .position(|e| entry.score > e.score)
+            .unwrap_or(self.entries.len());
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_HIGH_SCORES);
+    }
+}
+
+/// The name being typed for a new high score.
+#[derive(Resource)]
+pub struct NameEntry {
+    pub name: TextInput,
+}
+
+impl Default for NameEntry {
+    fn default() -> Self {
+        Self {
+            name: TextInput::new(NAME_LENGTH, |c| {
+                c.is_ascii_alphanumeric().then(|| c.to_ascii_uppercase())
+            }),
+        }
+    }
+}
+
+/// Asks for a name when the run that just ended made the table.
+pub fn check_high_score(
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+    mut name_entry: ResMut<NameEntry>,
+    mut state: ResMut<CurrentState>,
+) {
+    if high_scores.qualifies(score.0) {
+        name_entry.name.clear();
+        state.push(GameState::EnterName);
+    }
+}
+
+pub fn update_name_entry(
+    keys: Res<KeyInput>,
+    time: Res<Time>,
+    score: Res<Score>,
+    rng: Res<Rng>,
+    mut name_entry: ResMut<NameEntry>,
+    mut high_scores: ResMut<HighScores>,
+    mut state: ResMut<CurrentState>,
+) {
+    name_entry.name.update(&keys, time.unscaled_dt);
+
+    if keys.is_pressed(KeyCode::Enter) && name_entry.name.is_full() {
+        high_scores.insert(HighScore {
+            score: score.0,
+            name: name_entry.name.text.clone(),
+            date: date::now() as u64,
+            seed: rng.seed(),
+        });
+        high_scores.save(HIGH_SCORES_PATH);
+        state.pop();
+    }
+}
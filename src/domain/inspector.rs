@@ -0,0 +1,361 @@
+//! A panel for looking inside entities while the game runs and tuning their
+//! numbers on the spot, toggled with [`INSPECTOR_KEY`] or the `inspector`
+//! console command.
+//!
+//! Clicking an entity in play, or its row in the list, selects it.
+//! Scrolling over one of its fields nudges the value, ten times as far with
+//! shift held, and clicking the field types a new one in. Edits go straight
+//! into the component, so they last as long as the entity does.
+//!
+//! While it's open the mouse belongs to the inspector, so clicking around
+//! doesn't also fire.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{
+    Collider, Damage, Health, Particle, Player, Points, Position, PrefabName, Shooter, Velocity,
+};
+use crate::{
+    engine::{CommandResult, KeyInput, MouseInput, Screen, TextInput, Time},
+    rendering::Glyph,
+};
+
+pub const INSPECTOR_KEY: KeyCode = KeyCode::F4;
+
+pub const PANEL_WIDTH: f32 = 150.;
+pub const ROW_HEIGHT: f32 = 10.;
+
+/// Rows of the entity list shown at once.
+pub const LIST_ROWS: usize = 12;
+
+/// Size picked out around entities with nothing else to go by.
+const DEFAULT_BOUNDS: f32 = 8.;
+
+/// Lets a field be read and written as an `f32`, whatever its own type.
+trait Numeric {
+    fn to_f32(self) -> f32;
+    fn from_f32(value: f32) -> Self;
+}
+
+impl Numeric for f32 {
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+}
+
+impl Numeric for u32 {
+    fn to_f32(self) -> f32 {
+        self as f32
+    }
+
+    fn from_f32(value: f32) -> Self {
+        value.round().max(0.) as u32
+    }
+}
+
+/// One number on a component that can be edited.
+pub struct Field {
+    pub component: &'static str,
+    pub name: &'static str,
+    /// How far one notch of the wheel moves it.
+    pub step: f32,
+    get: fn(&World, Entity) -> Option<f32>,
+    set: fn(&mut World, Entity, f32),
+}
+
+macro_rules! field {
+    ($component:ident, $name:literal, $step:expr, |$c:ident| $place:expr) => {
+        Field {
+            component: stringify!($component),
+            name: $name,
+            step: $step,
+            get: |world, entity| {
+                world
+                    .get::<$component>(entity)
+                    .map(|$c| Numeric::to_f32($place))
+            },
+            set: |world, entity, value| {
+                if let Some(mut $c) = world.get_mut::<$component>(entity) {
+                    $place = Numeric::from_f32(value);
+                }
+            },
+        }
+    };
+}
+
+/// Every field the inspector shows, in order, for entities that have them.
+pub const FIELDS: [Field; 12] = [
+    field!(Position, "x", 1., |c| c.0.x),
+    field!(Position, "y", 1., |c| c.0.y),
+    field!(Velocity, "x", 5., |c| c.0.x),
+    field!(Velocity, "y", 5., |c| c.0.y),
+    field!(Health, "current", 1., |c| c.current),
+    field!(Health, "max", 1., |c| c.max),
+    field!(Collider, "width", 1., |c| c.size.x),
+    field!(Collider, "height", 1., |c| c.size.y),
+    field!(Glyph, "size", 1., |c| c.size),
+    field!(Damage, "amount", 1., |c| c.0),
+    field!(Points, "value", 10., |c| c.0),
+    field!(Shooter, "interval", 0.1, |c| c.interval),
+];
+
+#[derive(Resource, Default)]
+pub struct Inspector {
+    open: bool,
+    pub selected: Option<Entity>,
+    /// First row of the list shown.
+    pub scroll: usize,
+    /// Everything that can be picked and what it is, refreshed each frame
+    /// while open. Particles are left out, there are far too many.
+    pub rows: Vec<(Entity, String)>,
+    /// The selected entity's fields, as indices into [`FIELDS`], and their
+    /// values.
+    pub values: Vec<(usize, f32)>,
+    /// Where the selected entity is, to outline it.
+    pub bounds: Option<Rect>,
+    /// The row in `values` being typed into, and what's been typed.
+    pub editing: Option<(usize, TextInput)>,
+}
+
+impl Inspector {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.editing = None;
+    }
+
+    /// What the selected entity is, from its row.
+    pub fn selected_label(&self) -> Option<&str> {
+        let selected = self.selected?;
+        self.rows
+            .iter()
+            .find(|(entity, _)| *entity == selected)
+            .map(|(_, label)| label.as_str())
+    }
+}
+
+pub fn panel_rect(screen: &Screen) -> Rect {
+    Rect::new(
+        screen.width as f32 - PANEL_WIDTH,
+        0.,
+        PANEL_WIDTH,
+        screen.height as f32,
+    )
+}
+
+/// The `row`th entity shown in the list, under the title.
+pub fn list_row_rect(row: usize, screen: &Screen) -> Rect {
+    let panel = panel_rect(screen);
+    Rect::new(
+        panel.x,
+        (row + 1) as f32 * ROW_HEIGHT + 4.,
+        PANEL_WIDTH,
+        ROW_HEIGHT,
+    )
+}
+
+/// The `row`th of the selected entity's fields, under the list and the
+/// entity's own title.
+pub fn field_row_rect(row: usize, screen: &Screen) -> Rect {
+    list_row_rect(LIST_ROWS + 2 + row, screen)
+}
+
+fn label(name: Option<&PrefabName>, is_player: bool) -> String {
+    match name {
+        _ if is_player => "player".to_string(),
+        Some(name) => name.0.clone(),
+        None => "entity".to_string(),
+    }
+}
+
+fn bounds(world: &World, entity: Entity) -> Option<Rect> {
+    let position = world.get::<Position>(entity)?;
+
+    if let Some(collider) = world.get::<Collider>(entity) {
+        return Some(collider.rect(position));
+    }
+
+    let size = world
+        .get::<Glyph>(entity)
+        .map_or(DEFAULT_BOUNDS, |glyph| glyph.size);
+    Some(Collider::square(size).rect(position))
+}
+
+/// The smallest entity under `point`, so a bullet can be picked out of the
+/// faller it's passing.
+fn pick(world: &World, rows: &[(Entity, String)], point: Vec2) -> Option<Entity> {
+    rows.iter()
+        .filter_map(|(entity, _)| Some((*entity, bounds(world, *entity)?)))
+        .filter(|(_, rect)| rect.contains(point))
+        .min_by(|a, b| (a.1.w * a.1.h).total_cmp(&(b.1.w * b.1.h)))
+        .map(|(entity, _)| entity)
+}
+
+fn refresh(world: &mut World, inspector: &mut Inspector) {
+    let mut query = world.query_filtered::<(Entity, Option<&PrefabName>, Has<Player>), (
+        With<Position>,
+        Without<Particle>,
+    )>();
+
+    inspector.rows = query
+        .iter(world)
+        .map(|(entity, name, is_player)| (entity, label(name, is_player)))
+        .collect();
+    inspector.rows.sort_by_key(|(entity, _)| *entity);
+    inspector.scroll = inspector
+        .scroll
+        .min(inspector.rows.len().saturating_sub(LIST_ROWS));
+
+    if let Some(selected) = inspector.selected
+        && !inspector.rows.iter().any(|(entity, _)| *entity == selected)
+    {
+        inspector.selected = None;
+        inspector.editing = None;
+    }
+
+    inspector.values = inspector.selected.map_or_else(Vec::new, |entity| {
+        FIELDS
+            .iter()
+            .enumerate()
+            .filter_map(|(i, field)| Some((i, (field.get)(world, entity)?)))
+            .collect()
+    });
+    inspector.bounds = inspector.selected.and_then(|entity| bounds(world, entity));
+}
+
+/// Applies typing into the field being edited, taking the keyboard from
+/// everything else while it does.
+fn type_value(world: &mut World, inspector: &mut Inspector, dt: f32) {
+    let Some((row, mut input)) = inspector.editing.take() else {
+        return;
+    };
+
+    let mut keys = world.resource_mut::<KeyInput>();
+    input.update(&keys, dt);
+
+    let done = keys.is_pressed(KeyCode::Enter) || keys.is_pressed(KeyCode::KpEnter);
+    let cancelled = keys.is_pressed(KeyCode::Escape);
+    *keys = KeyInput::default();
+
+    if done {
+        let value = input.text.parse::<f32>().ok();
+        let field = inspector.values.get(row).map(|(field, _)| *field);
+
+        if let (Some(value), Some(field), Some(entity)) = (value, field, inspector.selected) {
+            (FIELDS[field].set)(world, entity, value);
+        }
+    }
+
+    if !done && !cancelled {
+        inspector.editing = Some((row, input));
+    }
+}
+
+/// Picks, scrolls and edits with the mouse, and types into fields.
+/// Runs before the frame's input becomes actions, so neither the clicks
+/// nor the typing reach the game.
+pub fn update_inspector(world: &mut World) {
+    let dt = world.resource::<Time>().unscaled_dt;
+
+    world.resource_scope(|world, mut inspector: Mut<Inspector>| {
+        if world.resource::<KeyInput>().is_pressed(INSPECTOR_KEY) {
+            inspector.toggle();
+        }
+
+        if !inspector.open {
+            return;
+        }
+
+        refresh(world, &mut inspector);
+        type_value(world, &mut inspector, dt);
+
+        let keys = world.resource::<KeyInput>();
+        let boost = if keys.is_down(KeyCode::LeftShift) || keys.is_down(KeyCode::RightShift) {
+            10.
+        } else {
+            1.
+        };
+
+        let mut mouse = world.resource_mut::<MouseInput>();
+        let point = mouse.position;
+        let clicked = mouse.is_pressed(MouseButton::Left);
+        let notches = mouse.wheel.y.signum();
+
+        mouse.down.clear();
+        mouse.pressed.clear();
+        mouse.released.clear();
+        mouse.wheel = Vec2::ZERO;
+
+        let screen = world.resource::<Screen>();
+
+        if !panel_rect(screen).contains(point) {
+            if clicked && let Some(entity) = pick(world, &inspector.rows, point) {
+                inspector.selected = Some(entity);
+                inspector.editing = None;
+            }
+
+            return;
+        }
+
+        let list_row = (0..LIST_ROWS).find(|&row| list_row_rect(row, screen).contains(point));
+        let field_row =
+            (0..inspector.values.len()).find(|&row| field_row_rect(row, screen).contains(point));
+
+        if list_row.is_some() && notches != 0. {
+            let last = inspector.rows.len().saturating_sub(LIST_ROWS);
+            inspector.scroll =
+                (inspector.scroll as isize - notches as isize).clamp(0, last as isize) as usize;
+        }
+
+        if clicked
+            && let Some(row) = list_row
+            && let Some((entity, _)) = inspector.rows.get(inspector.scroll + row)
+        {
+            inspector.selected = Some(*entity);
+            inspector.editing = None;
+        }
+
+        let (Some(row), Some(entity)) = (field_row, inspector.selected) else {
+            return;
+        };
+        let (field, value) = inspector.values[row];
+
+        if notches != 0. {
+            (FIELDS[field].set)(world, entity, value + FIELDS[field].step * notches * boost);
+        }
+
+        if clicked {
+            let mut input = TextInput::new(12, |c| {
+                (c.is_ascii_digit() || matches!(c, '.' | '-')).then_some(c)
+            });
+            input.text = format!("{}", value);
+            inspector.editing = Some((row, input));
+        }
+    });
+}
+
+pub fn inspector_open(inspector: Res<Inspector>) -> bool {
+    inspector.is_open()
+}
+
+pub fn inspector_command(world: &mut World, _args: &[&str]) -> CommandResult {
+    let mut inspector = world.resource_mut::<Inspector>();
+    inspector.toggle();
+
+    Ok(format!(
+        "Inspector {}",
+        if inspector.is_open() {
+            "open"
+        } else {
+            "closed"
+        }
+    ))
+}
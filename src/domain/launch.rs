@@ -0,0 +1,34 @@
+//! Acting on `--replay` and `--skip-menu`, which skip the main menu the
+//! first time it would come up.
+
+use std::path::Path;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Replay, play_replay};
+use crate::engine::{Config, CurrentState, GameState};
+
+/// Plays the replay given on the command line, or starts a run, in place
+/// of the first main menu. A replay that won't load leaves the menu up.
+pub fn apply_launch(
+    mut cmds: Commands,
+    config: Res<Config>,
+    mut state: ResMut<CurrentState>,
+    mut launched: Local<bool>,
+) {
+    if std::mem::replace(&mut *launched, true) {
+        return;
+    }
+
+    let launch = &config.launch;
+
+    if let Some(path) = &launch.replay {
+        match Replay::read(Path::new(path)) {
+            Ok(replay) => play_replay(&mut cmds, &mut state, replay),
+            Err(e) => warn!("Failed to load {}: {}", path, e),
+        }
+    } else if launch.skip_menu {
+        state.set(GameState::Playing);
+    }
+}
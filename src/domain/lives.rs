@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{DeathEvent, Faller, Health, Player, Position};
+use crate::{
+    engine::{CurrentState, GameState, Screen, Time},
+    rendering::Glyph,
+};
+
+pub const STARTING_LIVES: u32 = 3;
+
+/// How long the player is immune to fallers after respawning.
+const RESPAWN_INVULNERABILITY: Duration = Duration::from_secs(2);
+
+/// Fallers closer than this to the respawn point are cleared away.
+const RESPAWN_CLEAR_RADIUS: f32 = 64.;
+
+/// Lives left this run, including the current one.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lives(pub u32);
+
+impl Default for Lives {
+    fn default() -> Self {
+        Self(STARTING_LIVES)
+    }
+}
+
+/// Fallers pass through the entity until the time runs out.
+#[derive(Component, Clone, Copy)]
+pub struct Invulnerable(pub Duration);
+
+pub fn reset_lives(mut lives: ResMut<Lives>) {
+    *lives = Lives::default();
+}
+
+/// Takes a life when the player dies. The player respawns at the center of
+/// the screen with full health until the last life is lost. The tutorial
+/// refills them instead of ending, so it never reaches the game over.
+pub fn lose_life_on_death(
+    mut cmds: Commands,
+    mut ev_death: EventReader<DeathEvent>,
+    mut lives: ResMut<Lives>,
+    mut state: ResMut<CurrentState>,
+    q_player: Single<(Entity, &mut Position, &mut Health), With<Player>>,
+    q_fallers: Query<(Entity, &Position), (With<Faller>, Without<Player>)>,
+    screen: Res<Screen>,
+) {
+    let (player, mut position, mut health) = q_player.into_inner();
+
+    if !ev_death.read().any(|ev| ev.entity == player) {
+        return;
+    }
+
+    lives.0 = lives.0.saturating_sub(1);
+
+    if lives.0 == 0 {
+        if !state.contains(GameState::Tutorial) {
+            state.set(GameState::GameOver);
+            return;
+        }
+
+        *lives = Lives::default();
+    }
+
+    let center = vec2(screen.width as f32 / 2.0, screen.height as f32 / 2.0);
+
+    position.0 = center;
+    health.current = health.max;
+    cmds.entity(player)
+        .insert(Invulnerable(RESPAWN_INVULNERABILITY));
+
+    for (faller, faller_position) in q_fallers.iter() {
+        if faller_position.0.distance(center) < RESPAWN_CLEAR_RADIUS {
+            cmds.entity(faller).try_despawn();
+        }
+    }
+}
+
+/// Counts down [`Invulnerable`], flickering the entity's glyph meanwhile.
+pub fn tick_invulnerability(
+    mut cmds: Commands,
+    mut q_invulnerable: Query<(Entity, &mut Invulnerable, Option<&mut Glyph>)>,
+    time: Res<Time>,
+) {
+    let dt = Duration::from_secs_f32(time.fixed_dt);
+
+    for (entity, mut invulnerable, glyph) in q_invulnerable.iter_mut() {
+        invulnerable.0 = invulnerable.0.saturating_sub(dt);
+
+        let visible = invulnerable.0.is_zero() || (time.tick / 6).is_multiple_of(2);
+
+        if let Some(mut glyph) = glyph {
+            let alpha = if visible { 1.0 } else { 0.25 };
+
+            if glyph.fg1.a != alpha {
+                glyph.fg1.a = alpha;
+                glyph.fg2.a = alpha;
+                glyph.outline.a = alpha;
+            }
+        }
+
+        if invulnerable.0.is_zero() {
+            cmds.entity(entity).remove::<Invulnerable>();
+        }
+    }
+}
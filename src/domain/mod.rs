@@ -0,0 +1,93 @@
+mod achievements;
+mod bench;
+mod bomb;
+mod boss;
+mod budget;
+mod bullet_time;
+mod campaign;
+mod checksum;
+mod collision;
+mod combo;
+mod console;
+mod dash;
+#[cfg(feature = "dev")]
+mod dev;
+mod difficulty;
+mod director;
+mod enemies;
+mod enemy_fire;
+mod faller;
+mod formations;
+mod health;
+mod hierarchy;
+mod highscores;
+mod inspector;
+mod launch;
+mod lives;
+mod movement;
+mod particles;
+mod pickups;
+mod player;
+mod plugin;
+mod prefabs;
+mod profile;
+mod replay;
+mod save;
+mod score;
+mod sector;
+mod seed;
+mod settings;
+mod shop;
+mod stats;
+mod targeting;
+mod telegraph;
+mod tutorial;
+mod waves;
+mod weapons;
+
+pub use achievements::*;
+pub use bench::*;
+pub use bomb::*;
+pub use boss::*;
+pub use budget::*;
+pub use bullet_time::*;
+pub use campaign::*;
+pub use checksum::*;
+pub use collision::*;
+pub use combo::*;
+pub use console::*;
+pub use dash::*;
+#[cfg(feature = "dev")]
+pub use dev::*;
+pub use difficulty::*;
+pub use director::*;
+pub use enemies::*;
+pub use enemy_fire::*;
+pub use faller::*;
+pub use formations::*;
+pub use health::*;
+pub use hierarchy::*;
+pub use highscores::*;
+pub use inspector::*;
+pub use launch::*;
+pub use lives::*;
+pub use movement::*;
+pub use particles::*;
+pub use pickups::*;
+pub use player::*;
+pub use plugin::*;
+pub use prefabs::*;
+pub use profile::*;
+pub use replay::*;
+pub use save::*;
+pub use score::*;
+pub use sector::*;
+pub use seed::*;
+pub use settings::*;
+pub use shop::*;
+pub use stats::*;
+pub use targeting::*;
+pub use telegraph::*;
+pub use tutorial::*;
+pub use waves::*;
+pub use weapons::*;
@@ -0,0 +1,45 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::engine::{ReleaseExt, Screen, Time};
+
+/// Center of the entity, in render target texels.
+#[derive(Component, Clone, Copy, Default)]
+pub struct Position(pub Vec2);
+
+/// Texels per second.
+#[derive(Component, Clone, Copy, Default)]
+pub struct Velocity(pub Vec2);
+
+/// Entities that leave the screen while moving away from it are released.
+#[derive(Component)]
+pub struct DespawnOffscreen;
+
+pub fn apply_velocity(mut q_moving: Query<(&mut Position, &Velocity)>, time: Res<Time>) {
+    for (mut position, velocity) in q_moving.iter_mut() {
+        position.0 += velocity.0 * time.fixed_dt;
+    }
+}
+
+pub fn despawn_offscreen(
+    mut cmds: Commands,
+    q_moving: Query<(Entity, &Position, &Velocity), With<DespawnOffscreen>>,
+    screen: Res<Screen>,
+) {
+    let width = screen.width as f32;
+    let height = screen.height as f32;
+
+    for (entity, position, velocity) in q_moving.iter() {
+        let Vec2 { x, y } = position.0;
+        let Vec2 { x: vx, y: vy } = velocity.0;
+
+        let leaving = (x < 0. && vx <= 0.)
+            || (x > width && vx >= 0.)
+            || (y < 0. && vy <= 0.)
+            || (y > height && vy >= 0.);
+
+        if leaving {
+            cmds.entity(entity).release();
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{DeathEvent, Position, Ramp, Velocity};
+use crate::{
+    engine::{DespawnOnExit, GameState, Lifetime, Pool, Rng},
+    rendering::Glyph,
+};
+
+#[derive(Component)]
+pub struct Particle;
+
+/// Bursts whatever died into smaller copies of its glyph.
+pub fn particles_on_death(
+    mut cmds: Commands,
+    mut ev_death: EventReader<DeathEvent>,
+    mut rng: ResMut<Rng>,
+    mut pool: ResMut<Pool>,
+    ramp: Res<Ramp>,
+    q_glyphs: Query<(&Position, &Glyph)>,
+    q_particles: Query<(), With<Particle>>,
+) {
+    // Bursts shrink as the budget fills, rather than spawning particles
+    // only for them to be recycled straight away.
+    let mut room = ramp
+        .budget
+        .particles
+        .saturating_sub(q_particles.iter().len());
+
+    for ev in ev_death.read() {
+        let Ok((position, glyph)) = q_glyphs.get(ev.entity) else {
+            continue;
+        };
+
+        let count = room.min(8);
+        room -= count;
+
+        for _ in 0..count {
+            let angle = rng.gen_range(0.0, std::f32::consts::TAU);
+            let speed = rng.gen_range(20.0, 80.0);
+
+            pool.spawn(
+                &mut cmds,
+                (
+                    Particle,
+                    Lifetime(Duration::from_secs_f32(rng.gen_range(0.2, 0.6))),
+                    Glyph {
+                        size: glyph.size / 4.0,
+                        ..*glyph
+                    },
+                    *position,
+                    Velocity(Vec2::from_angle(angle) * speed),
+                    DespawnOnExit(GameState::Playing),
+                ),
+            );
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! Gems and power-ups dropped by fallers. Anything with a [`Magnet`] pulls
+//! nearby pickups in by steering their [`Velocity`], so individual pickups
+//! need no homing logic of their own.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Bombed, Collider, Credits, DeathEvent, Faller, Player, Position, PrefabSpawner, ScoreEvent,
+    Shield, Velocity,
+};
+use crate::engine::Time;
+
+/// Starting pull radius, in texels.
+pub const MAGNET_RADIUS: f32 = 48.;
+
+/// Radius added by each magnet power-up.
+const MAGNET_RADIUS_STEP: f32 = 24.;
+
+const MAGNET_MAX_RADIUS: f32 = 160.;
+
+/// Fastest a pickup travels while being pulled.
+const PULL_SPEED: f32 = 320.;
+
+/// Power-up prefabs and the chance a destroyed faller drops each one, on top
+/// of its gem.
+const POWER_UP_DROPS: [(&str, f32); 2] = [("magnet", 0.05), ("shield_charge", 0.04)];
+
+/// What the player gets for collecting the entity.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum Pickup {
+    /// Points, scaled by the combo like any other [`ScoreEvent`].
+    Gem(u32),
+    /// Widens the collector's [`Magnet`].
+    Magnet,
+    /// Restores one [`Shield`] charge.
+    Shield,
+}
+
+/// Pulls pickups within `radius` toward the entity.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Magnet {
+    pub radius: f32,
+    /// How quickly pulled pickups turn toward the magnet, in texels per
+    /// second, per second.
+    pub strength: f32,
+}
+
+impl Default for Magnet {
+    fn default() -> Self {
+        Self {
+            radius: MAGNET_RADIUS,
+            strength: 900.,
+        }
+    }
+}
+
+impl Magnet {
+    pub fn upgrade(&mut self) {
+        self.radius = (self.radius + MAGNET_RADIUS_STEP).min(MAGNET_MAX_RADIUS);
+    }
+}
+
+/// Leaves a gem where each faller died, and occasionally a power-up.
+/// Bombed fallers drop nothing.
+pub fn drop_pickups(
+    mut ev_death: EventReader<DeathEvent>,
+    mut spawner: PrefabSpawner,
+    q_fallers: Query<&Position, (With<Faller>, Without<Bombed>)>,
+) {
+    for ev in ev_death.read() {
+        let Ok(position) = q_fallers.get(ev.entity) else {
+            continue;
+        };
+
+        spawner.spawn_prefab("gem", position.0);
+
+        for (prefab, chance) in POWER_UP_DROPS {
+            if spawner.rng.next_f32() < chance {
+                spawner.spawn_prefab(prefab, position.0);
+            }
+        }
+    }
+}
+
+/// Steers every pickup inside a [`Magnet`]'s radius toward the closest one.
+pub fn attract_pickups(
+    q_magnets: Query<(&Position, &Magnet)>,
+    mut q_pickups: Query<(&Position, &mut Velocity), (With<Pickup>, Without<Magnet>)>,
+    time: Res<Time>,
+) {
+    for (position, mut velocity) in q_pickups.iter_mut() {
+        let nearest = q_magnets
+            .iter()
+            .map(|(magnet_position, magnet)| (magnet_position.0 - position.0, magnet))
+            .filter(|(gap, magnet)| gap.length() <= magnet.radius)
+            .min_by(|(a, _), (b, _)| a.length().total_cmp(&b.length()));
+
+        let Some((gap, magnet)) = nearest else {
+            continue;
+        };
+
+        let desired = gap.normalize_or_zero() * PULL_SPEED;
+        let steer = (desired - velocity.0).clamp_length_max(magnet.strength * time.fixed_dt);
+
+        velocity.0 += steer;
+    }
+}
+
+/// Sent when the player collects a pickup.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct PickupEvent {
+    pub pickup: Pickup,
+}
+
+pub fn collect_pickups(
+    mut cmds: Commands,
+    mut ev_score: EventWriter<ScoreEvent>,
+    mut ev_pickup: EventWriter<PickupEvent>,
+    mut credits: ResMut<Credits>,
+    q_player: Option<
+        Single<(&Position, &Collider, &mut Magnet, Option<&mut Shield>), With<Player>>,
+    >,
+    q_pickups: Query<(Entity, &Position, &Collider, &Pickup)>,
+) {
+    let Some(player) = q_player else {
+        return;
+    };
+    let (p_player, c_player, mut magnet, mut shield) = player.into_inner();
+    let player_rect = c_player.rect(p_player);
+
+    for (entity, position, collider, pickup) in q_pickups.iter() {
+        if !collider.rect(position).overlaps(&player_rect) {
+            continue;
+        }
+
+        match *pickup {
+            Pickup::Gem(points) => {
+                ev_score.send(ScoreEvent { points });
+                credits.0 += points;
+            }
+            Pickup::Magnet => magnet.upgrade(),
+            Pickup::Shield => {
+                if let Some(shield) = shield.as_mut() {
+                    shield.recharge();
+                }
+            }
+        }
+
+        ev_pickup.send(PickupEvent { pickup: *pickup });
+        cmds.entity(entity).despawn();
+    }
+}
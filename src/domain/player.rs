@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Collider, Dash, Health, HierarchyCommands, InvulnerableOnHit, Magnet, Position, Profile,
+    Shield, Unlock, Upgrades, Velocity, Weapon, WeaponKind,
+};
+use crate::t;
+use crate::{
+    engine::{Actions, Cooldown, DespawnOnExit, GameState, Screen, cycle},
+    rendering::Glyph,
+};
+
+#[derive(Component)]
+pub struct Player {
+    pub speed: f32,
+}
+
+#[derive(Component)]
+pub struct Bullet;
+
+/// Exhaust flame attached below the player.
+#[derive(Component)]
+pub struct Thruster;
+
+/// Marker for the player's [`Cooldown`] between shots.
+pub struct Shoot;
+
+/// Hits the player can take before losing a life.
+pub const PLAYER_HEALTH: u32 = 3;
+
+/// Shield charges the player starts with, and the most it can hold.
+pub const PLAYER_SHIELD: u32 = 1;
+pub const PLAYER_MAX_SHIELD: u32 = 3;
+
+/// Grace period after being hit, so overlapping hazards can't chain-kill.
+pub const HIT_INVULNERABILITY: Duration = Duration::from_secs(2);
+
+/// Hulls the player can fly, unlocked through the [`Profile`](super::Profile).
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Ship {
+    #[default]
+    Standard,
+    /// Quick but fragile.
+    Interceptor,
+    /// Slow but takes more hits.
+    Bulwark,
+}
+
+impl Ship {
+    pub const ALL: [Ship; 3] = [Ship::Standard, Ship::Interceptor, Ship::Bulwark];
+
+    pub fn name(&self) -> String {
+        let key = match self {
+            Ship::Standard => "ship.standard",
+            Ship::Interceptor => "ship.interceptor",
+            Ship::Bulwark => "ship.bulwark",
+        };
+
+        t!(key)
+    }
+
+    pub fn cycle(&self, offset: isize) -> Self {
+        cycle(&Self::ALL, self, offset)
+    }
+
+    pub fn speed(&self) -> f32 {
+        match self {
+            Ship::Standard => 200.,
+            Ship::Interceptor => 260.,
+            Ship::Bulwark => 160.,
+        }
+    }
+
+    pub fn health(&self) -> u32 {
+        match self {
+            Ship::Standard => PLAYER_HEALTH,
+            Ship::Interceptor => PLAYER_HEALTH - 1,
+            Ship::Bulwark => PLAYER_HEALTH + 2,
+        }
+    }
+
+    pub fn glyph(&self) -> Glyph {
+        let glyph = Glyph::new(4, 32.);
+
+        match self {
+            Ship::Standard => glyph,
+            Ship::Interceptor => Glyph {
+                fg1: Color::from_rgba(255, 60, 40, 255),
+                outline: Color::from_rgba(255, 200, 40, 255),
+                ..glyph
+            },
+            Ship::Bulwark => Glyph {
+                fg1: Color::from_rgba(120, 120, 140, 255),
+                outline: Color::from_rgba(200, 200, 220, 255),
+                ..glyph
+            },
+        }
+    }
+}
+
+/// The player's [`Dash`] and its cooldown.
+pub fn player_dash() -> (Dash, Cooldown<Dash>) {
+    Dash::bundle(600., Duration::from_millis(150), Duration::from_millis(800))
+}
+
+pub fn setup_player(mut cmds: Commands, profile: Res<Profile>, screen: Res<Screen>) {
+    let ship = profile.ship;
+
+    cmds.spawn((
+        Player {
+            speed: ship.speed(),
+        },
+        ship.glyph(),
+        Position(vec2(screen.width as f32 / 2.0, screen.height as f32 / 2.0)),
+        Velocity::default(),
+        Collider::square(32.),
+        Weapon::bundle(WeaponKind::default()),
+        Health::new(ship.health()),
+        InvulnerableOnHit(HIT_INVULNERABILITY),
+        Magnet::default(),
+        player_dash(),
+        Shield::new(
+            PLAYER_SHIELD + u32::from(profile.has(Unlock::StartingShield)),
+            PLAYER_MAX_SHIELD,
+        ),
+        Upgrades::default(),
+        DespawnOnExit(GameState::Playing),
+    ));
+}
+
+/// Gives newly spawned players a [`Thruster`], whether they came from
+/// [`setup_player`] or a restored save.
+pub fn attach_thrusters(mut cmds: Commands, q_players: Query<(Entity, &Position), Added<Player>>) {
+    for (player, position) in q_players.iter() {
+        cmds.spawn((
+            Thruster,
+            Glyph::new(22, 8.),
+            *position,
+            DespawnOnExit(GameState::Playing),
+        ))
+        .set_parent(player, vec2(0., 18.));
+    }
+}
+
+/// Steers the player from the movement [`Actions`]. Movement itself is applied by
+/// [`apply_velocity`](super::apply_velocity).
+pub fn update_player(actions: Res<Actions>, q_player: Single<(&mut Velocity, &Player, &Upgrades)>) {
+    let (mut velocity, player, upgrades) = q_player.into_inner();
+
+    velocity.0 = actions.movement() * player.speed * upgrades.speed_multiplier();
+}
+
+pub fn clamp_player(mut q_player: Single<&mut Position, With<Player>>, screen: Res<Screen>) {
+    let position = &mut q_player.0;
+
+    position.x = clamp(position.x, 0.0, screen.width as f32);
+    position.y = clamp(position.y, 0.0, screen.height as f32);
+}
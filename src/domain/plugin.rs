@@ -0,0 +1,413 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::*;
+use crate::engine::{
+    Action, App, AppExit, Config, Deterministic, Ephemeral, First, FixedUpdate, GameSet, GameState,
+    OnEnter, OnExit, OnPause, OnUpdate, Plugin, PostUpdate, PreUpdate, Render, Rng, SEED_ENV_VAR,
+    Startup, Update, action_pressed, exit_on_quit_request, frame_step_paused, in_stack, in_state,
+    persistent, tick_cooldowns, tick_lifetimes, update_actions, update_console, update_mouse_input,
+};
+
+/// The player, fallers and everything they do to each other.
+pub struct GameplayPlugin;
+
+fn setup_rng(mut cmds: Commands, config: Res<Config>, mut custom_seed: ResMut<CustomSeed>) {
+    let rng = match config.launch.seed {
+        Some(seed) => Rng::new(seed),
+        None => Rng::from_env_or_time(),
+    };
+    info!(
+        "Session seed: {} (set {} or pass --seed to replay)",
+        rng.seed(),
+        SEED_ENV_VAR
+    );
+
+    // The first run plays the seed asked for, if there is one, and later
+    // runs draw theirs from the session.
+    custom_seed.0 = config.launch.seed.or_else(Rng::env_seed);
+
+    cmds.insert_resource(SessionRng(rng.clone()));
+    cmds.insert_resource(rng);
+}
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        let broadphase = app.world.resource::<Config>().broadphase;
+        let ephemeral = app.world.contains_resource::<Ephemeral>();
+        let (settings, high_scores, profile) = if ephemeral {
+            Default::default()
+        } else {
+            (
+                Settings::load(SETTINGS_PATH),
+                HighScores::load(HIGH_SCORES_PATH),
+                Profile::load(PROFILE_PATH),
+            )
+        };
+
+        app.add_event::<CollisionEvent>()
+            .add_event::<DamageEvent>()
+            .add_event::<HitEvent>()
+            .add_event::<DeathEvent>()
+            .add_event::<FireEvent>()
+            .add_event::<PickupEvent>()
+            .add_event::<ScoreEvent>()
+            .add_event::<DashEvent>()
+            .init_resource::<Combo>()
+            .init_resource::<Bombs>()
+            .init_resource::<Director>()
+            .init_resource::<BulletTime>()
+            .init_resource::<GameMode>()
+            .init_resource::<Victory>()
+            .init_resource::<Credits>()
+            .init_resource::<RunStats>()
+            .insert_resource(FallerIndex(broadphase.build()))
+            .init_resource::<WaveStart>()
+            .init_resource::<Toasts>()
+            .init_resource::<Inspector>()
+            .init_resource::<Shop>()
+            .init_resource::<Score>()
+            .init_resource::<Lives>()
+            .init_resource::<Waves>()
+            .init_resource::<NameEntry>()
+            .init_resource::<SeedEntry>()
+            .init_resource::<CustomSeed>()
+            .insert_resource(high_scores)
+            .insert_resource(profile)
+            .init_resource::<Hangar>()
+            .init_resource::<Tutorial>()
+            .insert_resource(settings.bindings)
+            .insert_resource(settings.input_buffer)
+            .insert_resource(settings.aim)
+            .insert_resource(settings.audio)
+            .insert_resource(settings.language)
+            .insert_resource(settings.tileset)
+            .insert_resource(settings.difficulty)
+            .insert_resource(settings.autosave)
+            .insert_resource(settings.frame_cap)
+            .init_resource::<AutosaveTimer>()
+            .init_resource::<SettingsMenu>()
+            .init_resource::<ControlsMenu>()
+            .init_resource::<ReplayBrowser>()
+            .init_resource::<SlotMenu>()
+            .init_resource::<ActiveSlot>()
+            .add_systems(Startup, setup_rng)
+            .load(
+                PREFABS_PATH,
+                Prefabs::load(PREFABS_PATH),
+                |world, prefabs| {
+                    world.insert_resource(prefabs);
+                },
+            )
+            .load(RAMP_PATH, Ramp::load(RAMP_PATH), |world, ramp| {
+                world.insert_resource(ramp);
+            })
+            .load(
+                CAMPAIGN_PATH,
+                Campaign::load(CAMPAIGN_PATH),
+                |world, campaign| {
+                    world.insert_resource(campaign);
+                },
+            )
+            .add_console_command(
+                "score",
+                "[points]",
+                "shows or sets the score",
+                score_command,
+            )
+            .add_console_command(
+                "lives",
+                "[lives]",
+                "shows or sets the lives left",
+                lives_command,
+            )
+            .add_console_command(
+                "spawn",
+                "<prefab> [x y]",
+                "spawns a prefab, under the pointer unless told where",
+                spawn_command,
+            )
+            .add_console_command(
+                "inspector",
+                "",
+                "opens or closes the entity inspector",
+                inspector_command,
+            );
+
+        app.add_systems(
+            PreUpdate,
+            update_inspector
+                .after(update_console)
+                .after(update_mouse_input)
+                .before(update_actions),
+        );
+
+        app.add_systems(
+            OnEnter(GameState::Playing),
+            (
+                seed_run,
+                start_replay.run_if(not(resource_exists::<PendingSnapshot>)),
+                (
+                    reset_score,
+                    reset_lives,
+                    reset_waves,
+                    reset_combo,
+                    reset_bombs,
+                    reset_director,
+                    reset_bullet_time,
+                    reset_victory,
+                    reset_sector,
+                    reset_credits,
+                    reset_stats,
+                    reset_wave_start,
+                    reset_autosave,
+                ),
+                (setup_player, apply_profile, clear_active_slot)
+                    .run_if(not(resource_exists::<PendingSnapshot>)),
+                restore_snapshot.run_if(resource_exists::<PendingSnapshot>),
+                restart_checksums,
+            )
+                .chain(),
+        );
+
+        app.add_systems(OnPause(GameState::Playing), reset_time_scale);
+        app.add_systems(
+            OnExit(GameState::Playing),
+            (reset_time_scale, save_recording.run_if(persistent)),
+        );
+
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            (
+                check_high_score,
+                award_stardust,
+                record_lifetime_stats,
+                remove_finished_save.run_if(persistent),
+            )
+                .run_if(not(is_replaying)),
+        );
+
+        app.add_systems(
+            OnEnter(GameState::MainMenu),
+            (stop_replay, apply_launch.after(stop_replay)),
+        );
+
+        app.add_systems(First, feed_playback.run_if(is_replaying));
+        app.add_systems(
+            Render,
+            replay_controls
+                .in_set(GameSet::Input)
+                .run_if(is_replaying)
+                .run_if(in_stack(GameState::Playing)),
+        );
+
+        app.add_systems(OnEnter(GameState::Replays), scan_replays);
+        app.add_systems(OnEnter(GameState::SaveSlots), scan_slots);
+
+        app.add_systems(OnUpdate(GameState::Replays), update_replay_browser);
+        app.add_systems(
+            PreUpdate,
+            record_input.after(update_actions).run_if(is_recording),
+        );
+        app.add_systems(
+            PostUpdate,
+            (
+                save_recording,
+                // Closing the game mid-run keeps the run in its slot.
+                save_game
+                    .run_if(in_stack(GameState::Playing))
+                    .run_if(not(in_tutorial.or(is_replaying))),
+            )
+                .after(exit_on_quit_request)
+                .run_if(persistent)
+                .run_if(|exit: Res<AppExit>| exit.0),
+        );
+        app.add_systems(
+            PostUpdate,
+            (
+                // A replay swaps in the recorded setup for its length.
+                save_settings.run_if(not(is_replaying)),
+                save_profile.run_if(not(is_replaying)),
+                save_profile_during_replay.run_if(is_replaying),
+            )
+                .after(exit_on_quit_request)
+                .run_if(persistent),
+        );
+
+        app.add_systems(
+            Update,
+            (save_game, reset_autosave)
+                .chain()
+                .run_if(in_state(GameState::Playing))
+                .run_if(autosave_due)
+                .run_if(persistent)
+                .run_if(not(in_tutorial.or(is_replaying))),
+        );
+
+        app.add_systems(OnUpdate(GameState::Hangar), update_hangar);
+
+        app.add_systems(OnEnter(GameState::Tutorial), start_tutorial);
+
+        app.add_systems(OnUpdate(GameState::Settings), update_settings_menu);
+
+        app.add_systems(
+            Update,
+            toggle_mute
+                .run_if(action_pressed(Action::Mute))
+                .run_if(not(in_state(GameState::EnterName)))
+                .run_if(not(in_state(GameState::EnterSeed)))
+                .run_if(not(in_state(GameState::Controls))),
+        );
+
+        app.add_systems(OnUpdate(GameState::Controls), update_controls);
+
+        app.add_systems(Update, update_toasts);
+
+        app.add_systems(OnUpdate(GameState::EnterName), update_name_entry);
+
+        app.add_systems(OnEnter(GameState::EnterSeed), reset_seed_entry);
+
+        app.add_systems(OnUpdate(GameState::EnterSeed), update_seed_entry);
+
+        app.add_systems(OnUpdate(GameState::SectorIntro), update_sector_intro);
+
+        app.add_systems(OnUpdate(GameState::Shop), update_shop.param_warn_once());
+
+        app.add_systems(
+            OnUpdate(GameState::Playing),
+            (
+                // Nothing the player presses may land between ticks while
+                // frame stepping.
+                (
+                    cycle_weapon.param_warn_once().in_set(GameSet::Input),
+                    player_dash_input.param_warn_once().in_set(GameSet::Input),
+                    update_bullet_time.in_set(GameSet::Input),
+                    detonate_bomb
+                        .param_warn_once()
+                        .run_if(action_pressed(Action::Bomb))
+                        .in_set(GameSet::Input),
+                    player_fire.param_warn_once().in_set(GameSet::Spawning),
+                )
+                    .run_if(not(frame_step_paused)),
+                finish_tutorial.run_if(in_tutorial).in_set(GameSet::Input),
+            ),
+        );
+
+        app.add_systems(
+            FixedUpdate,
+            (
+                (
+                    // These all steer through `Velocity` and so can't run side
+                    // by side anyway; chaining fixes the order they take turns
+                    // in, and with it which entity ids the spawners get.
+                    (
+                        attach_thrusters,
+                        update_player.param_warn_once(),
+                        weave,
+                        dive,
+                        strafe,
+                        update_boss,
+                        attract_pickups,
+                        steer_homing,
+                    )
+                        .chain_ignore_deferred()
+                        .before(apply_velocity),
+                    apply_velocity,
+                    clamp_player.param_warn_once().after(apply_velocity),
+                    sway_formations.before(propagate_positions),
+                    propagate_positions.after(clamp_player),
+                    despawn_offscreen.after(propagate_positions),
+                    despawn_orphans.after(despawn_offscreen),
+                    enforce_budget.after(tick_lifetimes),
+                    (
+                        tick_lifetimes,
+                        tick_cooldowns::<Shoot>,
+                        tick_cooldowns::<EnemyShoot>,
+                        tick_cooldowns::<Dash>,
+                        tick_invulnerability,
+                        tick_combo,
+                        tally_play_time,
+                        tick_autosave,
+                        (advance_sector, update_director)
+                            .chain()
+                            .run_if(in_mode(GameMode::Endless))
+                            .run_if(not(in_tutorial)),
+                        follow_campaign
+                            .run_if(in_mode(GameMode::Campaign))
+                            .run_if(not(in_tutorial)),
+                    ),
+                    advance_waves.run_if(boss_absent).run_if(not(in_tutorial)),
+                    break_combo_on_escape
+                        .after(propagate_positions)
+                        .before(despawn_offscreen),
+                    (start_dash, apply_dash)
+                        .chain()
+                        .after(update_player)
+                        .before(apply_velocity),
+                    dash_trail.after(propagate_positions),
+                )
+                    .in_set(GameSet::Simulation),
+                (
+                    update_faller_index,
+                    check_collisions,
+                    damage_on_collision.param_warn_once(),
+                    apply_damage,
+                )
+                    .chain()
+                    .in_set(GameSet::Collision),
+                // Most of these spawn or draw from the `Rng`, so they take
+                // turns in a fixed order rather than whichever comes first.
+                (
+                    despawn_dead,
+                    lose_life_on_death.param_warn_once(),
+                    particles_on_death,
+                    drop_pickups,
+                    split_on_death,
+                    scatter_formations,
+                    (
+                        combo_on_death,
+                        break_combo_on_hit,
+                        score_on_death,
+                        boss_defeated,
+                        open_shop,
+                    )
+                        .chain_ignore_deferred(),
+                    unarmor_boss,
+                    tally_stats,
+                    check_achievements.run_if(not(in_tutorial.or(is_replaying))),
+                    update_tutorial.run_if(in_tutorial),
+                )
+                    .chain_ignore_deferred()
+                    .in_set(GameSet::Collision)
+                    .after(apply_damage),
+                collect_pickups
+                    .in_set(GameSet::Collision)
+                    .after(apply_damage)
+                    .before(despawn_dead),
+                sync_shield_outline
+                    .in_set(GameSet::Collision)
+                    .after(apply_damage)
+                    .after(collect_pickups),
+                apply_score
+                    .in_set(GameSet::Collision)
+                    .after(collect_pickups)
+                    .after(combo_on_death)
+                    .after(break_combo_on_hit)
+                    .after(score_on_death)
+                    .after(boss_defeated),
+                (
+                    spawn_fallers.run_if(boss_absent).run_if(not(in_tutorial)),
+                    hatch_telegraphs,
+                    spawn_boss,
+                    enemy_fire,
+                )
+                    .chain_ignore_deferred()
+                    .in_set(GameSet::Spawning),
+                checksum_state
+                    .after(GameSet::Spawning)
+                    .run_if(resource_exists::<Deterministic>),
+            )
+                .run_if(in_state(GameState::Playing)),
+        );
+    }
+}
@@ -0,0 +1,341 @@
+use std::{collections::HashMap, time::Duration};
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use macroquad::prelude::*;
+use serde::Deserialize;
+
+use super::{
+    Bullet, Collider, Damage, DespawnOffscreen, Difficulty, Director, Dive, EnemyBullet,
+    EnemyShoot, Faller, FirePattern, Health, Homing, Massive, Pickup, Piercing, Points, Position,
+    Shooter, SpawnedAt, SplitsOnDeath, Strafe, Swept, Velocity, Weave,
+};
+use crate::{
+    engine::{Cooldown, DespawnOnExit, GameState, Lifetime, Pool, Rng, read_string},
+    rendering::Glyph,
+};
+
+/// A value that is either fixed or rolled uniformly from `(min, max)` each
+/// time a prefab is spawned.
+#[derive(Deserialize, Clone, Copy, Debug)]
+#[serde(untagged)]
+pub enum Spread {
+    Fixed(f32),
+    Range(f32, f32),
+}
+
+impl Default for Spread {
+    fn default() -> Self {
+        Spread::Fixed(0.)
+    }
+}
+
+impl Spread {
+    pub fn roll(&self, rng: &mut Rng) -> f32 {
+        match *self {
+            Spread::Fixed(value) => value,
+            Spread::Range(min, max) => rng.gen_range(min, max),
+        }
+    }
+
+    pub fn max(&self) -> f32 {
+        match *self {
+            Spread::Fixed(value) => value,
+            Spread::Range(_, max) => max,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, Debug)]
+pub struct PrefabColors {
+    pub fg1: [u8; 4],
+    pub fg2: [u8; 4],
+    pub outline: [u8; 4],
+    pub bg: [u8; 4],
+}
+
+/// Marker components and timers a prefab can opt into.
+#[derive(Deserialize, Clone, Debug)]
+pub enum Behavior {
+    Faller,
+    Bullet,
+    DespawnOffscreen,
+    Lifetime(Spread),
+    Weave {
+        amplitude: Spread,
+        frequency: Spread,
+    },
+    Dive(Spread),
+    Strafe(Spread),
+    /// Survives crashing into the player.
+    Massive,
+    EnemyBullet,
+    Piercing,
+    /// Collides along the path it travels each tick, see [`Swept`].
+    Swept,
+    Shooter {
+        pattern: FirePattern,
+        interval: f32,
+    },
+    Pickup(Pickup),
+    /// Turn rate in radians per second.
+    Homing(Spread),
+    /// Breaks into `count` (inclusive range) copies of `prefab` when
+    /// destroyed.
+    SplitsOnDeath {
+        prefab: String,
+        count: (u32, u32),
+    },
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct Prefab {
+    pub glyph: usize,
+    pub size: Spread,
+    #[serde(default)]
+    pub colors: Option<PrefabColors>,
+    #[serde(default)]
+    pub speed: Spread,
+    /// Direction of travel. `None` picks a random heading per spawn.
+    #[serde(default)]
+    pub direction: Option<(f32, f32)>,
+    /// Adds a square [`Collider`] matching the rolled size.
+    #[serde(default)]
+    pub collider: bool,
+    /// Adds [`Health`]; hits needed to destroy it.
+    #[serde(default)]
+    pub health: Option<u32>,
+    /// Adds [`Damage`] dealt on contact.
+    #[serde(default)]
+    pub damage: Option<u32>,
+    /// Adds [`Points`] awarded when destroyed, instead of scoring by size.
+    #[serde(default)]
+    pub score: Option<u32>,
+    #[serde(default)]
+    pub behaviors: Vec<Behavior>,
+}
+
+impl Prefab {
+    /// Fallers and enemy bullets, which [`Difficulty`] scales.
+    fn is_hostile(&self) -> bool {
+        self.behaviors
+            .iter()
+            .any(|b| matches!(b, Behavior::Faller | Behavior::EnemyBullet))
+    }
+
+    /// Player and enemy bullets, which come and go too often to spawn
+    /// afresh each time.
+    fn is_projectile(&self) -> bool {
+        self.behaviors
+            .iter()
+            .any(|b| matches!(b, Behavior::Bullet | Behavior::EnemyBullet))
+    }
+
+    fn glyph(&self, size: f32) -> Glyph {
+        let mut glyph = Glyph::new(self.glyph, size);
+
+        if let Some(colors) = self.colors {
+            let [fg1, fg2, outline, bg] = [colors.fg1, colors.fg2, colors.outline, colors.bg]
+                .map(|[r, g, b, a]| Color::from_rgba(r, g, b, a));
+
+            glyph.fg1 = fg1;
+            glyph.fg2 = fg2;
+            glyph.outline = outline;
+            glyph.bg = bg;
+        }
+
+        glyph
+    }
+}
+
+pub const PREFABS_PATH: &str = "assets/prefabs.ron";
+
+/// The prefab an entity was spawned from.
+#[derive(Component, Clone, Debug)]
+pub struct PrefabName(pub String);
+
+/// Named prefabs, loaded from [`PREFABS_PATH`].
+#[derive(Resource, Default)]
+pub struct Prefabs(HashMap<String, Prefab>);
+
+impl Prefabs {
+    pub fn from_ron(src: &str) -> Result<Self, ron::error::SpannedError> {
+        Ok(Self(ron::from_str(src)?))
+    }
+
+    pub async fn load(path: &str) -> Result<Self, String> {
+        let src = read_string(path).await?;
+        Self::from_ron(&src).map_err(|e| e.to_string())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Prefab> {
+        self.0.get(name)
+    }
+}
+
+/// Spawns entities from [`Prefabs`], rolling their random stats from the
+/// seeded [`Rng`]. Projectiles are drawn from the [`Pool`].
+#[derive(SystemParam)]
+pub struct PrefabSpawner<'w, 's> {
+    pub cmds: Commands<'w, 's>,
+    pub prefabs: Res<'w, Prefabs>,
+    pub rng: ResMut<'w, Rng>,
+    pub difficulty: Res<'w, Difficulty>,
+    pub director: Res<'w, Director>,
+    pub pool: ResMut<'w, Pool>,
+}
+
+impl PrefabSpawner<'_, '_> {
+    pub fn spawn_prefab(&mut self, name: &str, position: Vec2) -> Option<Entity> {
+        self.spawn(name, position, None)
+    }
+
+    /// Like [`spawn_prefab`](Self::spawn_prefab), but travelling along
+    /// `direction` instead of the prefab's own.
+    pub fn spawn_prefab_aimed(
+        &mut self,
+        name: &str,
+        position: Vec2,
+        direction: Vec2,
+    ) -> Option<Entity> {
+        self.spawn(name, position, Some(direction))
+    }
+
+    /// Aimed along `direction` if there is one, otherwise like
+    /// [`spawn_prefab`](Self::spawn_prefab).
+    pub fn spawn_prefab_toward(
+        &mut self,
+        name: &str,
+        position: Vec2,
+        direction: Option<Vec2>,
+    ) -> Option<Entity> {
+        self.spawn(name, position, direction)
+    }
+
+    fn spawn(&mut self, name: &str, position: Vec2, aim: Option<Vec2>) -> Option<Entity> {
+        let Some(prefab) = self.prefabs.get(name) else {
+            warn!("Unknown prefab {}", name);
+            return None;
+        };
+
+        let size = prefab.size.roll(&mut self.rng);
+        let mut speed = prefab.speed.roll(&mut self.rng);
+
+        if prefab.is_hostile() {
+            speed *= self.difficulty.enemy_speed() * self.director.enemy_speed;
+        }
+
+        let direction = match (aim, prefab.direction) {
+            (Some(aim), _) => aim.normalize_or_zero(),
+            (None, Some((x, y))) => vec2(x, y).normalize_or_zero(),
+            (None, None) => Vec2::from_angle(self.rng.gen_range(0.0, std::f32::consts::TAU)),
+        };
+
+        let bundle = (
+            Position(position),
+            prefab.glyph(size),
+            Velocity(direction * speed),
+            PrefabName(name.to_string()),
+            SpawnedAt(self.director.elapsed),
+            DespawnOnExit(GameState::Playing),
+        );
+        let mut entity = if prefab.is_projectile() {
+            self.pool.spawn(&mut self.cmds, bundle)
+        } else {
+            self.cmds.spawn(bundle)
+        };
+
+        if prefab.collider {
+            entity.insert(Collider::square(size));
+        }
+
+        if let Some(health) = prefab.health {
+            let health = if prefab.is_hostile() {
+                Difficulty::scale(health, self.difficulty.enemy_health())
+            } else {
+                health
+            };
+
+            entity.insert(Health::new(health));
+        }
+
+        if let Some(damage) = prefab.damage {
+            entity.insert(Damage(damage));
+        }
+
+        if let Some(score) = prefab.score {
+            entity.insert(Points(score));
+        }
+
+        for behavior in prefab.behaviors.iter() {
+            match *behavior {
+                Behavior::Faller => {
+                    entity.insert(Faller);
+                }
+                Behavior::Bullet => {
+                    entity.insert(Bullet);
+                }
+                Behavior::DespawnOffscreen => {
+                    entity.insert(DespawnOffscreen);
+                }
+                Behavior::Lifetime(seconds) => {
+                    let seconds = seconds.roll(&mut self.rng);
+                    entity.insert(Lifetime(Duration::from_secs_f32(seconds)));
+                }
+                Behavior::Weave {
+                    amplitude,
+                    frequency,
+                } => {
+                    entity.insert(Weave {
+                        amplitude: amplitude.roll(&mut self.rng),
+                        frequency: frequency.roll(&mut self.rng),
+                        elapsed: 0.,
+                    });
+                }
+                Behavior::Dive(acceleration) => {
+                    entity.insert(Dive {
+                        acceleration: acceleration.roll(&mut self.rng),
+                    });
+                }
+                Behavior::Swept => {
+                    entity.insert(Swept);
+                }
+                Behavior::Piercing => {
+                    entity.insert(Piercing::default());
+                }
+                Behavior::EnemyBullet => {
+                    entity.insert(EnemyBullet);
+                }
+                Behavior::Shooter { pattern, interval } => {
+                    entity.insert((
+                        Shooter { pattern, interval },
+                        Cooldown::<EnemyShoot>::started(Duration::from_secs_f32(interval)),
+                    ));
+                }
+                Behavior::Massive => {
+                    entity.insert(Massive);
+                }
+                Behavior::Strafe(speed) => {
+                    entity.insert(Strafe {
+                        speed: speed.roll(&mut self.rng),
+                    });
+                }
+                Behavior::Pickup(pickup) => {
+                    entity.insert(pickup);
+                }
+                Behavior::Homing(turn_rate) => {
+                    entity.insert(Homing {
+                        turn_rate: turn_rate.roll(&mut self.rng),
+                    });
+                }
+                Behavior::SplitsOnDeath { ref prefab, count } => {
+                    entity.insert(SplitsOnDeath {
+                        prefab: prefab.clone(),
+                        count,
+                    });
+                }
+            }
+        }
+
+        Some(entity.id())
+    }
+}
@@ -0,0 +1,247 @@
+//! Progress kept between runs in [`PROFILE_PATH`]. Every run pays out
+//! [`Profile::stardust`], which buys permanent starting bonuses and new
+//! ships and weapons in the hangar, and every run's stats are added into
+//! [`Profile::lifetime`] alongside the achievements unlocked.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Achievement, Bombs, Credits, LifetimeStats, Lives, Score, Ship, WeaponKind};
+use crate::engine::{Action, Actions, AppExit, CurrentState, write_atomic};
+use crate::t;
+
+pub const PROFILE_PATH: &str = "profile.dat";
+
+/// Score needed for each point of stardust a run pays out.
+const SCORE_PER_STARDUST: u32 = 100;
+
+/// Credits a run starts with once [`Unlock::HeadStart`] is bought.
+const HEAD_START_CREDITS: u32 = 150;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Unlock {
+    ExtraLife,
+    ExtraBomb,
+    StartingShield,
+    HeadStart,
+    Interceptor,
+    Bulwark,
+    SpreadGun,
+    ChargeGun,
+    MissileLauncher,
+}
+
+impl Unlock {
+    pub const ALL: [Unlock; 9] = [
+        Unlock::ExtraLife,
+        Unlock::ExtraBomb,
+        Unlock::StartingShield,
+        Unlock::HeadStart,
+        Unlock::Interceptor,
+        Unlock::Bulwark,
+        Unlock::SpreadGun,
+        Unlock::ChargeGun,
+        Unlock::MissileLauncher,
+    ];
+
+    pub fn name(&self) -> String {
+        let key = match self {
+            Unlock::ExtraLife => "unlock.extra_life",
+            Unlock::ExtraBomb => "unlock.extra_bomb",
+            Unlock::StartingShield => "unlock.starting_shield",
+            Unlock::HeadStart => "unlock.head_start",
+            Unlock::Interceptor => "unlock.interceptor",
+            Unlock::Bulwark => "unlock.bulwark",
+            Unlock::SpreadGun => "unlock.spread_gun",
+            Unlock::ChargeGun => "unlock.charge_gun",
+            Unlock::MissileLauncher => "unlock.missiles",
+        };
+
+        t!(key)
+    }
+
+    pub fn cost(&self) -> u32 {
+        match self {
+            Unlock::ExtraLife => 300,
+            Unlock::ExtraBomb => 150,
+            Unlock::StartingShield => 200,
+            Unlock::HeadStart => 100,
+            Unlock::Interceptor | Unlock::Bulwark => 250,
+            Unlock::SpreadGun => 100,
+            Unlock::ChargeGun => 150,
+            Unlock::MissileLauncher => 200,
+        }
+    }
+
+    /// What must be bought before `ship` can be flown.
+    pub fn for_ship(ship: Ship) -> Option<Unlock> {
+        match ship {
+            Ship::Standard => None,
+            Ship::Interceptor => Some(Unlock::Interceptor),
+            Ship::Bulwark => Some(Unlock::Bulwark),
+        }
+    }
+
+    /// What must be bought before `kind` can be cycled to.
+    pub fn for_weapon(kind: WeaponKind) -> Option<Unlock> {
+        match kind {
+            WeaponKind::Blaster | WeaponKind::Rapid => None,
+            WeaponKind::Spread => Some(Unlock::SpreadGun),
+            WeaponKind::Charge => Some(Unlock::ChargeGun),
+            WeaponKind::Missile => Some(Unlock::MissileLauncher),
+        }
+    }
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Default, Debug)]
+pub struct Profile {
+    pub stardust: u32,
+    pub unlocked: Vec<Unlock>,
+    /// The ship new runs start in.
+    #[serde(default)]
+    pub ship: Ship,
+    #[serde(default)]
+    pub lifetime: LifetimeStats,
+    #[serde(default)]
+    pub achievements: Vec<Achievement>,
+}
+
+impl Profile {
+    /// A missing or unreadable file is a fresh profile.
+    pub fn load(path: &str) -> Self {
+        let Ok(src) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        ron::from_str(&src).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable {}: {}", path, e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &str) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|src| write_atomic(path, &src).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            warn!("Failed to save {}: {}", path, e);
+        }
+    }
+
+    pub fn has(&self, unlock: Unlock) -> bool {
+        self.unlocked.contains(&unlock)
+    }
+
+    /// Spends stardust on `unlock`, returning whether it was bought.
+    pub fn buy(&mut self, unlock: Unlock) -> bool {
+        if self.has(unlock) || self.stardust < unlock.cost() {
+            return false;
+        }
+
+        self.stardust -= unlock.cost();
+        self.unlocked.push(unlock);
+        true
+    }
+
+    pub fn has_ship(&self, ship: Ship) -> bool {
+        Unlock::for_ship(ship).is_none_or(|unlock| self.has(unlock))
+    }
+
+    pub fn has_weapon(&self, kind: WeaponKind) -> bool {
+        Unlock::for_weapon(kind).is_none_or(|unlock| self.has(unlock))
+    }
+}
+
+/// Gives a fresh run the starting bonuses bought so far. The ship itself is
+/// applied by [`setup_player`](super::setup_player).
+pub fn apply_profile(
+    profile: Res<Profile>,
+    mut lives: ResMut<Lives>,
+    mut bombs: ResMut<Bombs>,
+    mut credits: ResMut<Credits>,
+) {
+    if profile.has(Unlock::ExtraLife) {
+        lives.0 += 1;
+    }
+
+    if profile.has(Unlock::ExtraBomb) {
+        bombs.0 += 1;
+    }
+
+    if profile.has(Unlock::HeadStart) {
+        credits.0 += HEAD_START_CREDITS;
+    }
+}
+
+/// Runs at the end of every frame, so a change is on disk before anything
+/// can crash or close the window.
+pub fn save_profile(profile: Res<Profile>, exit: Res<AppExit>) {
+    if (profile.is_changed() && !profile.is_added()) || exit.0 {
+        profile.save(PROFILE_PATH);
+    }
+}
+
+/// Pays out stardust for the run that just ended.
+pub fn award_stardust(score: Res<Score>, mut profile: ResMut<Profile>) {
+    let earned = score.0 / SCORE_PER_STARDUST;
+
+    if earned > 0 {
+        profile.stardust += earned;
+    }
+}
+
+/// The entry under the cursor in the hangar.
+#[derive(Resource, Default)]
+pub struct Hangar {
+    pub selected: usize,
+}
+
+/// Moving up and down picks an unlock and firing buys it. Left and right
+/// switch between owned ships.
+pub fn update_hangar(
+    actions: Res<Actions>,
+    mut hangar: ResMut<Hangar>,
+    mut profile: ResMut<Profile>,
+    mut state: ResMut<CurrentState>,
+) {
+    let count = Unlock::ALL.len();
+
+    if actions.is_pressed(Action::MoveUp) {
+        hangar.selected = (hangar.selected + count - 1) % count;
+    }
+
+    if actions.is_pressed(Action::MoveDown) {
+        hangar.selected = (hangar.selected + 1) % count;
+    }
+
+    if actions.is_pressed(Action::Fire) {
+        profile.buy(Unlock::ALL[hangar.selected]);
+    }
+
+    let offset = match (
+        actions.is_pressed(Action::MoveLeft),
+        actions.is_pressed(Action::MoveRight),
+    ) {
+        (true, false) => -1,
+        (false, true) => 1,
+        _ => 0,
+    };
+
+    if offset != 0 {
+        let mut ship = profile.ship.cycle(offset);
+
+        while !profile.has_ship(ship) {
+            ship = ship.cycle(offset);
+        }
+
+        if ship != profile.ship {
+            profile.ship = ship;
+        }
+    }
+
+    if actions.is_pressed(Action::Back) {
+        state.pop();
+    }
+}
@@ -0,0 +1,597 @@
+//! Recording runs to [`REPLAYS_DIR`] and playing them back. A recording is
+//! everything the run started from plus every frame of input after; since
+//! the game is deterministic given both, playback reproduces the run
+//! exactly, bugs included.
+//!
+//! Every run is recorded except the tutorial and runs continued from a
+//! save, and the newest [`MAX_REPLAYS`] are kept. They're picked from the
+//! replay browser on the main menu and play through the normal simulation,
+//! with [`replay_controls`] to pause, fast forward and jump back.
+
+use std::path::{Path, PathBuf};
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use macroquad::{miniquad::date, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::{AimMode, Difficulty, GameMode, PROFILE_PATH, Profile, Score};
+use crate::engine::{
+    Action, Actions, AppExit, CurrentState, GameState, InputBuffer, InputFrame, InputOverride,
+    KeyInput, MouseInput, Rng, Time, UpdatesPerFrame, write_atomic,
+};
+
+pub const REPLAYS_DIR: &str = "replays";
+
+/// Recordings kept; saving another removes the oldest.
+pub const MAX_REPLAYS: usize = 10;
+
+/// Bumped whenever the recording layout changes in a way older builds
+/// cannot read.
+pub const REPLAY_VERSION: u32 = 2;
+
+/// How far back the seek control jumps, in seconds.
+const SEEK_BACK: f32 = 10.;
+
+/// Updates per frame while fast forwarding.
+const FAST_FORWARD: u32 = 4;
+
+/// The most updates run in one frame while seeking, so the window keeps
+/// drawing on long jumps.
+const MAX_SEEK_UPDATES: u32 = 240;
+
+/// What the replay browser shows about a recording.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReplayInfo {
+    pub version: u32,
+    /// When the run started, in seconds since the Unix epoch.
+    pub date: u64,
+    pub score: u32,
+    /// Seconds of play.
+    pub duration: f32,
+}
+
+/// Everything a run's outcome depends on besides input.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ReplayHeader {
+    pub rng: Rng,
+    pub difficulty: Difficulty,
+    pub mode: GameMode,
+    pub aim: AimMode,
+    pub input_buffer: InputBuffer,
+    /// Unlocks and ship choice.
+    pub profile: Profile,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Replay {
+    pub info: ReplayInfo,
+    pub header: ReplayHeader,
+    #[serde(with = "track")]
+    pub frames: Vec<InputFrame>,
+}
+
+/// Frames are stored as every frame's time plus the input only on the
+/// frames where it changed, since most frames repeat the one before.
+mod track {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::engine::InputFrame;
+
+    #[derive(Serialize, Deserialize)]
+    struct Track {
+        dt: Vec<f32>,
+        /// Frame index and the input from then on, without its time.
+        changes: Vec<(u32, InputFrame)>,
+    }
+
+    pub fn serialize<S: Serializer>(
+        frames: &[InputFrame],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut changes: Vec<(u32, InputFrame)> = Vec::new();
+
+        for (index, frame) in frames.iter().enumerate() {
+            let input = InputFrame {
+                dt: 0.,
+                ..frame.clone()
+            };
+
+            if changes.last().is_none_or(|(_, last)| *last != input) {
+                changes.push((index as u32, input));
+            }
+        }
+
+        Track {
+            dt: frames.iter().map(|frame| frame.dt).collect(),
+            changes,
+        }
+        .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<InputFrame>, D::Error> {
+        let track = Track::deserialize(deserializer)?;
+        let mut changes = track.changes.into_iter().peekable();
+        let mut input = InputFrame::default();
+
+        let frames = (0..)
+            .zip(track.dt)
+            .map(|(index, dt)| {
+                while let Some((_, next)) = changes.next_if(|(at, _)| *at <= index) {
+                    input = next;
+                }
+
+                InputFrame {
+                    dt,
+                    ..input.clone()
+                }
+            })
+            .collect();
+
+        Ok(frames)
+    }
+}
+
+/// Just the parts of a recording the browser lists, without its frames.
+#[derive(Deserialize)]
+struct Listing {
+    info: ReplayInfo,
+    header: ReplayHeader,
+}
+
+impl Replay {
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let src = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let Listing { info, .. } = ron::from_str(&src).map_err(|e| e.to_string())?;
+
+        if info.version > REPLAY_VERSION {
+            return Err(format!(
+                "replay version {} is newer than this build ({})",
+                info.version, REPLAY_VERSION
+            ));
+        }
+
+        ron::from_str(&src).map_err(|e| e.to_string())
+    }
+
+    /// Writes the recording into [`REPLAYS_DIR`], named by its date, then
+    /// removes all but the newest [`MAX_REPLAYS`].
+    pub fn write(&self) -> Result<PathBuf, String> {
+        std::fs::create_dir_all(REPLAYS_DIR).map_err(|e| e.to_string())?;
+
+        let path = Path::new(REPLAYS_DIR).join(format!("{}.ron", self.info.date));
+        let src = ron::to_string(self).map_err(|e| e.to_string())?;
+        write_atomic(&path.to_string_lossy(), &src).map_err(|e| e.to_string())?;
+
+        for old in list_replays().iter().skip(MAX_REPLAYS) {
+            if let Err(e) = std::fs::remove_file(&old.path) {
+                warn!("Failed to remove {}: {}", old.path.display(), e);
+            }
+        }
+
+        Ok(path)
+    }
+}
+
+/// A recording in [`REPLAYS_DIR`], as listed by the browser.
+#[derive(Clone, Debug)]
+pub struct ReplayEntry {
+    pub path: PathBuf,
+    pub info: ReplayInfo,
+    pub mode: GameMode,
+    pub difficulty: Difficulty,
+}
+
+/// Every readable recording, newest first.
+pub fn list_replays() -> Vec<ReplayEntry> {
+    let Ok(dir) = std::fs::read_dir(REPLAYS_DIR) else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<ReplayEntry> = dir
+        .flatten()
+        .map(|file| file.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ron"))
+        .filter_map(|path| {
+            let src = std::fs::read_to_string(&path).ok()?;
+            let listing: Listing = ron::from_str(&src)
+                .inspect_err(|e| warn!("Skipping unreadable {}: {}", path.display(), e))
+                .ok()?;
+
+            Some(ReplayEntry {
+                path,
+                info: listing.info,
+                mode: listing.header.mode,
+                difficulty: listing.header.difficulty,
+            })
+        })
+        .collect();
+
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.info.date));
+    entries
+}
+
+/// The resources a [`ReplayHeader`] is taken from and put back into.
+#[derive(SystemParam)]
+pub struct RunSetup<'w> {
+    rng: ResMut<'w, Rng>,
+    difficulty: ResMut<'w, Difficulty>,
+    mode: ResMut<'w, GameMode>,
+    aim: ResMut<'w, AimMode>,
+    input_buffer: ResMut<'w, InputBuffer>,
+    profile: ResMut<'w, Profile>,
+}
+
+impl RunSetup<'_> {
+    fn capture(&self) -> ReplayHeader {
+        ReplayHeader {
+            rng: self.rng.clone(),
+            difficulty: *self.difficulty,
+            mode: *self.mode,
+            aim: *self.aim,
+            input_buffer: *self.input_buffer,
+            profile: self.profile.clone(),
+        }
+    }
+
+    fn apply(&mut self, header: ReplayHeader) {
+        *self.rng = header.rng;
+        *self.difficulty = header.difficulty;
+        *self.mode = header.mode;
+        *self.aim = header.aim;
+        *self.input_buffer = header.input_buffer;
+        *self.profile = header.profile;
+    }
+}
+
+/// The run in progress is being recorded.
+#[derive(Resource)]
+pub struct Recording(Replay);
+
+/// A recording is being played back.
+#[derive(Resource)]
+pub struct Playback {
+    replay: Replay,
+    /// The next frame to play.
+    frame: usize,
+    /// Seconds of the recording played so far.
+    elapsed: f32,
+    pub paused: bool,
+    pub fast_forward: bool,
+    /// The frame to fast forward to after jumping back, which restarts the
+    /// run.
+    seek_to: Option<usize>,
+    /// The player's own setup, put back once playback ends.
+    saved: Option<ReplayHeader>,
+}
+
+impl Playback {
+    pub fn elapsed(&self) -> f32 {
+        self.elapsed
+    }
+
+    pub fn duration(&self) -> f32 {
+        self.replay.info.duration
+    }
+
+    pub fn is_seeking(&self) -> bool {
+        self.seek_to.is_some()
+    }
+
+    /// The frame `seconds` into the recording.
+    fn frame_at(&self, seconds: f32) -> usize {
+        let mut elapsed = 0.;
+
+        self.replay
+            .frames
+            .iter()
+            .position(|frame| {
+                elapsed += frame.dt;
+                elapsed > seconds
+            })
+            .unwrap_or(self.replay.frames.len())
+    }
+}
+
+pub fn is_replaying(playback: Option<Res<Playback>>) -> bool {
+    playback.is_some()
+}
+
+pub fn is_recording(recording: Option<Res<Recording>>) -> bool {
+    recording.is_some()
+}
+
+/// Starts a run to play `replay` back in.
+pub fn play_replay(cmds: &mut Commands, state: &mut CurrentState, replay: Replay) {
+    info!("Replaying {} frames", replay.frames.len());
+    cmds.insert_resource(Playback {
+        replay,
+        frame: 0,
+        elapsed: 0.,
+        paused: false,
+        fast_forward: false,
+        seek_to: None,
+        saved: None,
+    });
+    state.set(GameState::Playing);
+}
+
+/// Runs as a run starts, before anything uses the [`Rng`]: either swaps in
+/// the recording's setup, rewinding to its first frame, or starts
+/// recording the current one.
+pub fn start_replay(
+    mut cmds: Commands,
+    mut setup: RunSetup,
+    playback: Option<ResMut<Playback>>,
+    state: Res<CurrentState>,
+) {
+    if let Some(mut playback) = playback {
+        if playback.saved.is_none() {
+            playback.saved = Some(setup.capture());
+        }
+
+        setup.apply(playback.replay.header.clone());
+        playback.frame = 0;
+        playback.elapsed = 0.;
+        return;
+    }
+
+    if !state.contains(GameState::Tutorial) {
+        cmds.insert_resource(Recording(Replay {
+            info: ReplayInfo {
+                version: REPLAY_VERSION,
+                date: date::now() as u64,
+                score: 0,
+                duration: 0.,
+            },
+            header: setup.capture(),
+            frames: Vec::new(),
+        }));
+    }
+}
+
+pub fn record_input(
+    mut recording: ResMut<Recording>,
+    actions: Res<Actions>,
+    time: Res<Time>,
+    mouse: Res<MouseInput>,
+) {
+    let frame = InputFrame::capture(&actions, &time, &mouse);
+    recording.0.frames.push(frame);
+}
+
+pub fn save_recording(mut cmds: Commands, recording: Option<ResMut<Recording>>, score: Res<Score>) {
+    let Some(mut recording) = recording else {
+        return;
+    };
+
+    cmds.remove_resource::<Recording>();
+
+    let replay = &mut recording.0;
+
+    if replay.frames.is_empty() {
+        return;
+    }
+
+    replay.info.score = score.0;
+    replay.info.duration = replay.frames.iter().map(|frame| frame.dt).sum();
+
+    match replay.write() {
+        Ok(path) => info!(
+            "Recorded {} frames to {}",
+            replay.frames.len(),
+            path.display()
+        ),
+        Err(e) => warn!("Failed to save replay to {}: {}", REPLAYS_DIR, e),
+    }
+}
+
+/// Hands the next recorded frame to the input systems, returning to the
+/// main menu once they run out. While paused, the last frame's held input
+/// is repeated without any time passing.
+pub fn feed_playback(
+    mut cmds: Commands,
+    mut playback: ResMut<Playback>,
+    mut state: ResMut<CurrentState>,
+) {
+    if playback.paused && !playback.is_seeking() {
+        let held = playback
+            .frame
+            .checked_sub(1)
+            .and_then(|last| playback.replay.frames.get(last))
+            .map(|last| InputFrame {
+                dt: 0.,
+                pressed: Vec::new(),
+                released: Vec::new(),
+                ..last.clone()
+            })
+            .unwrap_or_default();
+
+        cmds.insert_resource(InputOverride(held));
+        return;
+    }
+
+    match playback.replay.frames.get(playback.frame).cloned() {
+        Some(frame) => {
+            playback.frame += 1;
+            playback.elapsed += frame.dt;
+
+            if playback.seek_to.is_some_and(|to| playback.frame >= to) {
+                playback.seek_to = None;
+            }
+
+            cmds.insert_resource(InputOverride(frame));
+        }
+        None => {
+            cmds.remove_resource::<InputOverride>();
+
+            if state.contains(GameState::Playing) {
+                state.set(GameState::MainMenu);
+            }
+        }
+    }
+}
+
+/// Read from the keyboard rather than [`Actions`], which belong to the
+/// recording, and once per drawn frame however many updates it covered:
+/// Space pauses, holding Right fast forwards, Left jumps back
+/// [`SEEK_BACK`] seconds and Escape stops.
+pub fn replay_controls(
+    keys: Res<KeyInput>,
+    mut playback: ResMut<Playback>,
+    mut updates: ResMut<UpdatesPerFrame>,
+    mut state: ResMut<CurrentState>,
+) {
+    if keys.is_pressed(KeyCode::Escape) {
+        state.set(GameState::MainMenu);
+    }
+
+    if keys.is_pressed(KeyCode::Space) {
+        playback.paused = !playback.paused;
+    }
+
+    // The past can't be simulated backwards, so the run restarts and fast
+    // forwards to the target.
+    if keys.is_pressed(KeyCode::Left) && !playback.is_seeking() {
+        let target = playback.frame_at(playback.elapsed - SEEK_BACK);
+        playback.seek_to = Some(target);
+        state.reset(GameState::Playing);
+    }
+
+    playback.fast_forward = keys.is_down(KeyCode::Right);
+
+    updates.0 = match playback.seek_to {
+        Some(to) => (to.saturating_sub(playback.frame) as u32).clamp(1, MAX_SEEK_UPDATES),
+        None if playback.fast_forward && !playback.paused => FAST_FORWARD,
+        None => 1,
+    };
+}
+
+/// Puts the player's own setup back once the replayed run is over.
+pub fn stop_replay(
+    mut cmds: Commands,
+    mut setup: RunSetup,
+    mut updates: ResMut<UpdatesPerFrame>,
+    playback: Option<ResMut<Playback>>,
+) {
+    let Some(mut playback) = playback else {
+        return;
+    };
+
+    if let Some(saved) = playback.saved.take() {
+        setup.apply(saved);
+    }
+
+    updates.0 = 1;
+    cmds.remove_resource::<Playback>();
+    cmds.remove_resource::<InputOverride>();
+}
+
+/// Closing the game mid-replay saves the player's own profile rather than
+/// the recording's, which [`save_profile`](super::save_profile) is kept
+/// from writing.
+pub fn save_profile_during_replay(
+    playback: Res<Playback>,
+    profile: Res<Profile>,
+    exit: Res<AppExit>,
+) {
+    if exit.0 {
+        let saved = playback
+            .saved
+            .as_ref()
+            .map_or(&*profile, |saved| &saved.profile);
+        saved.save(PROFILE_PATH);
+    }
+}
+
+/// The recordings listed by the replay browser.
+#[derive(Resource, Default)]
+pub struct ReplayBrowser {
+    pub entries: Vec<ReplayEntry>,
+    pub selected: usize,
+}
+
+pub fn scan_replays(mut browser: ResMut<ReplayBrowser>) {
+    browser.entries = list_replays();
+    browser.selected = 0;
+}
+
+pub fn update_replay_browser(
+    mut cmds: Commands,
+    mut actions: ResMut<Actions>,
+    mut browser: ResMut<ReplayBrowser>,
+    mut state: ResMut<CurrentState>,
+) {
+    let count = browser.entries.len().max(1);
+
+    if actions.is_pressed(Action::MoveUp) {
+        browser.selected = (browser.selected + count - 1) % count;
+    }
+
+    if actions.is_pressed(Action::MoveDown) {
+        browser.selected = (browser.selected + 1) % count;
+    }
+
+    if actions.consume(Action::Confirm)
+        && let Some(entry) = browser.entries.get(browser.selected)
+    {
+        match Replay::read(&entry.path) {
+            Ok(replay) => play_replay(&mut cmds, &mut state, replay),
+            Err(e) => warn!("Failed to load {}: {}", entry.path.display(), e),
+        }
+    }
+
+    if actions.is_pressed(Action::Back) {
+        state.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(dt: f32, down: &[Action], pointer: (f32, f32)) -> InputFrame {
+        InputFrame {
+            dt,
+            down: down.to_vec(),
+            pointer,
+            ..InputFrame::default()
+        }
+    }
+
+    #[test]
+    fn frames_round_trip_through_the_track() {
+        let replay = Replay {
+            info: ReplayInfo {
+                version: REPLAY_VERSION,
+                date: 1700000000,
+                score: 120,
+                duration: 0.1,
+            },
+            header: ReplayHeader {
+                rng: Rng::new(7),
+                difficulty: Difficulty::default(),
+                mode: GameMode::default(),
+                aim: AimMode::default(),
+                input_buffer: InputBuffer::default(),
+                profile: Profile::default(),
+            },
+            frames: vec![
+                // Changed from the default on frame 0.
+                frame(0.016, &[Action::Fire], (0., 0.)),
+                frame(0.017, &[Action::Fire], (0., 0.)),
+                frame(0.016, &[Action::Fire], (0., 0.)),
+                frame(0.018, &[Action::Fire, Action::MoveLeft], (0., 0.)),
+                frame(0.016, &[], (12., 30.)),
+                frame(0.016, &[], (12., 30.)),
+            ],
+        };
+
+        let src = ron::to_string(&replay).unwrap();
+        let back: Replay = ron::from_str(&src).unwrap();
+
+        assert_eq!(back.frames, replay.frames);
+        assert_eq!(ron::to_string(&back).unwrap(), src);
+    }
+}
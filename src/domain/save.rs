@@ -0,0 +1,1031 @@
+//! Saving a run to disk and resuming it later. Runs are kept in
+//! [`SAVE_SLOTS`] slots under [`SAVES_DIR`]. Save & Quit on the pause screen
+//! asks which slot to save into, Continue on the main menu which to resume,
+//! and closing the game mid-run saves into the run's own slot, as does the
+//! periodic [`Autosave`] so a crash loses little.
+//!
+//! Each save records the [`SAVE_VERSION`] it was written with, and older
+//! saves are brought up to date by [`MIGRATIONS`] as they're read.
+//!
+//! Only gameplay state is captured: the player, fallers and their
+//! formations, bullets, pickups, the [`Score`], [`Lives`], [`Waves`],
+//! [`Bombs`], [`Combo`], the [`Director`], the endless [`Sector`], the
+//! [`RunStats`] and the [`Rng`]. Particles and cooldowns are cosmetic or
+//! short-lived and are rebuilt fresh on load.
+
+use std::{
+    collections::HashMap,
+    fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use bevy_ecs::prelude::*;
+use macroquad::{miniquad::date, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    BOMB_CHARGES, Bombs, Boss, BossSegment, Bullet, Collider, Combo, Credits, Damage,
+    DespawnOffscreen, Difficulty, Director, Dive, EnemyBullet, EnemyShoot, Faller, FormationLeader,
+    FormationSlot, GameMode, HIT_INVULNERABILITY, Health, HierarchyCommands, Homing,
+    InvulnerableOnHit, Lives, MAGNET_RADIUS, Magnet, Massive, Offset, PLAYER_HEALTH, Parent,
+    Pickup, Piercing, Player, Points, Position, PrefabName, Ramp, RunStats, STARTING_LIVES, Score,
+    Sector, Shield, Shooter, SpawnedAt, SplitsOnDeath, Strafe, SurvivesParent, Swept, Upgrades,
+    Velocity, Waves, Weapon, WeaponKind, Weave, player_dash,
+};
+use crate::{
+    engine::{
+        Action, Actions, Cooldown, CurrentState, DespawnOnExit, GameState, Lifetime, Rng, Time,
+        write_atomic,
+    },
+    rendering::{Glyph, Starfield},
+};
+
+pub const SAVES_DIR: &str = "saves";
+
+pub const SAVE_SLOTS: usize = 3;
+
+/// Where the one save lived before there were slots. It's moved into the
+/// first empty slot when the slots are next listed.
+const LEGACY_SAVE_PATH: &str = "save.ron";
+
+/// Bumped whenever the meaning of a saved field changes, with a step added
+/// to [`MIGRATIONS`] to bring saves from the version before up to date.
+/// New optional fields only need `#[serde(default)]`.
+pub const SAVE_VERSION: u32 = 2;
+
+/// Upgrades for saves written by older builds, applied in order after
+/// parsing. Entry `i` takes a version `i + 1` save to version `i + 2`, so
+/// fields an old version lacks still need a serde default to parse at all.
+const MIGRATIONS: [fn(&mut Snapshot); SAVE_VERSION as usize - 1] = [migrate_v1];
+
+/// Version 1 kept only how long the director had ramped for.
+fn migrate_v1(snapshot: &mut Snapshot) {
+    snapshot.director.get_or_insert_with(|| Director {
+        elapsed: snapshot.run_time,
+        ..Director::default()
+    });
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SaveHeader {
+    pub version: u32,
+    pub seed: u64,
+    /// Seconds since the Unix epoch when the run was saved.
+    #[serde(default)]
+    pub date: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub enum EntityKind {
+    Player {
+        speed: f32,
+        #[serde(default)]
+        weapon: WeaponKind,
+        #[serde(default = "default_magnet_radius")]
+        magnet_radius: f32,
+        #[serde(default)]
+        upgrades: Upgrades,
+    },
+    Faller,
+    Bullet,
+    EnemyBullet,
+    Pickup(Pickup),
+}
+
+/// A follower's place in its formation, as its [`Parent`], [`Offset`] and
+/// [`FormationSlot`].
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct SlotSnapshot {
+    /// The leader's index in [`Snapshot::entities`].
+    pub leader: usize,
+    pub offset: (f32, f32),
+    pub base: (f32, f32),
+    pub amplitude: f32,
+    pub frequency: f32,
+    pub lag: f32,
+    pub elapsed: f32,
+}
+
+impl SlotSnapshot {
+    fn slot(&self) -> FormationSlot {
+        FormationSlot {
+            base: self.base.into(),
+            amplitude: self.amplitude,
+            frequency: self.frequency,
+            lag: self.lag,
+            elapsed: self.elapsed,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct GlyphSnapshot {
+    pub idx: usize,
+    pub size: f32,
+    pub colors: [[u8; 4]; 4],
+}
+
+impl From<&Glyph> for GlyphSnapshot {
+    fn from(glyph: &Glyph) -> Self {
+        Self {
+            idx: glyph.idx,
+            size: glyph.size,
+            colors: [glyph.fg1, glyph.fg2, glyph.outline, glyph.bg].map(Into::into),
+        }
+    }
+}
+
+impl From<GlyphSnapshot> for Glyph {
+    fn from(snapshot: GlyphSnapshot) -> Self {
+        let [fg1, fg2, outline, bg] = snapshot
+            .colors
+            .map(|[r, g, b, a]| Color::from_rgba(r, g, b, a));
+
+        Glyph {
+            idx: snapshot.idx,
+            size: snapshot.size,
+            fg1,
+            fg2,
+            outline,
+            bg,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct EntitySnapshot {
+    pub kind: EntityKind,
+    pub position: (f32, f32),
+    pub velocity: (f32, f32),
+    pub glyph: GlyphSnapshot,
+    #[serde(default)]
+    pub collider: Option<(f32, f32)>,
+    /// `(current, max)`.
+    #[serde(default)]
+    pub health: Option<(u32, u32)>,
+    #[serde(default)]
+    pub damage: Option<u32>,
+    #[serde(default)]
+    pub weave: Option<Weave>,
+    #[serde(default)]
+    pub dive: Option<Dive>,
+    #[serde(default)]
+    pub strafe: Option<Strafe>,
+    #[serde(default)]
+    pub shooter: Option<Shooter>,
+    #[serde(default)]
+    pub splits: Option<SplitsOnDeath>,
+    #[serde(default)]
+    pub homing: Option<Homing>,
+    /// `(charges, max)`.
+    #[serde(default)]
+    pub shield: Option<(u32, u32)>,
+    #[serde(default)]
+    pub prefab: Option<String>,
+    #[serde(default)]
+    pub swept: bool,
+    #[serde(default)]
+    pub points: Option<u32>,
+    #[serde(default)]
+    pub massive: bool,
+    /// Indices in [`Snapshot::entities`] of what a [`Piercing`] bullet has
+    /// already passed through.
+    #[serde(default)]
+    pub piercing: Option<Vec<usize>>,
+    /// Seconds left to live.
+    #[serde(default)]
+    pub lifetime: Option<f32>,
+    #[serde(default)]
+    pub spawned_at: Option<f32>,
+    #[serde(default)]
+    pub leader: bool,
+    #[serde(default)]
+    pub slot: Option<SlotSnapshot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Snapshot {
+    pub header: SaveHeader,
+    pub rng: Rng,
+    #[serde(default)]
+    pub score: u32,
+    #[serde(default = "default_lives")]
+    pub lives: u32,
+    #[serde(default)]
+    pub waves: Waves,
+    #[serde(default = "default_bombs")]
+    pub bombs: u32,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub mode: GameMode,
+    /// Seconds the [`Director`] had ramped for. Only in version 1 saves,
+    /// which [`migrate_v1`] turns into a director.
+    #[serde(default, skip_serializing)]
+    pub run_time: f32,
+    #[serde(default)]
+    pub director: Option<Director>,
+    /// The endless [`Sector`] the run was in, regenerated from the seed.
+    #[serde(default)]
+    pub sector: Option<u32>,
+    #[serde(default)]
+    pub combo: Combo,
+    #[serde(default)]
+    pub credits: u32,
+    #[serde(default)]
+    pub stats: RunStats,
+    pub entities: Vec<EntitySnapshot>,
+}
+
+fn default_lives() -> u32 {
+    STARTING_LIVES
+}
+
+fn default_bombs() -> u32 {
+    BOMB_CHARGES
+}
+
+fn default_magnet_radius() -> f32 {
+    MAGNET_RADIUS
+}
+
+/// Just enough of a save file to check its version before parsing the rest.
+#[derive(Deserialize)]
+struct HeaderOnly {
+    header: SaveHeader,
+}
+
+#[derive(Debug)]
+pub enum SaveError {
+    Io(std::io::Error),
+    Serialize(ron::Error),
+    Parse(ron::error::SpannedError),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Io(e) => write!(f, "{e}"),
+            SaveError::Serialize(e) => write!(f, "{e}"),
+            SaveError::Parse(e) => write!(f, "{e}"),
+            SaveError::UnsupportedVersion(v) => write!(
+                f,
+                "save version {v} is newer than this build ({SAVE_VERSION})"
+            ),
+        }
+    }
+}
+
+impl Snapshot {
+    pub fn to_ron(&self) -> Result<String, SaveError> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(SaveError::Serialize)
+    }
+
+    pub fn from_ron(src: &str) -> Result<Self, SaveError> {
+        let HeaderOnly { header } = ron::from_str(src).map_err(SaveError::Parse)?;
+
+        if header.version > SAVE_VERSION {
+            return Err(SaveError::UnsupportedVersion(header.version));
+        }
+
+        let mut snapshot: Self = ron::from_str(src).map_err(SaveError::Parse)?;
+
+        for migrate in &MIGRATIONS[header.version.saturating_sub(1) as usize..] {
+            migrate(&mut snapshot);
+        }
+
+        snapshot.header.version = SAVE_VERSION;
+        Ok(snapshot)
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> Result<(), SaveError> {
+        let path = path.as_ref();
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(SaveError::Io)?;
+        }
+
+        write_atomic(&path.to_string_lossy(), &self.to_ron()?).map_err(SaveError::Io)
+    }
+
+    pub fn read(path: impl AsRef<Path>) -> Result<Self, SaveError> {
+        let src = std::fs::read_to_string(path).map_err(SaveError::Io)?;
+        Self::from_ron(&src)
+    }
+}
+
+/// How often a run in progress is saved into its slot.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug)]
+#[serde(default)]
+pub struct Autosave {
+    /// Closing the window mid-run saves either way.
+    pub enabled: bool,
+    /// Seconds of play between saves.
+    pub interval: f32,
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval: 60.,
+        }
+    }
+}
+
+/// Seconds of play since the run was last autosaved.
+#[derive(Resource, Default)]
+pub struct AutosaveTimer(pub f32);
+
+pub fn reset_autosave(mut timer: ResMut<AutosaveTimer>) {
+    timer.0 = 0.;
+}
+
+/// Counts fixed ticks, so time spent paused or in menus doesn't count.
+pub fn tick_autosave(mut timer: ResMut<AutosaveTimer>, time: Res<Time>) {
+    timer.0 += time.fixed_dt;
+}
+
+pub fn autosave_due(autosave: Res<Autosave>, timer: Res<AutosaveTimer>) -> bool {
+    autosave.enabled && timer.0 >= autosave.interval
+}
+
+/// A snapshot waiting to be restored on the next [`OnEnter`](crate::engine::OnEnter)
+/// of [`GameState::Playing`].
+#[derive(Resource)]
+pub struct PendingSnapshot(pub Snapshot);
+
+pub fn slot_path(slot: usize) -> PathBuf {
+    Path::new(SAVES_DIR).join(format!("slot{}.ron", slot + 1))
+}
+
+/// What the slot screen shows about a saved run.
+#[derive(Clone, Copy, Debug)]
+pub struct SlotInfo {
+    pub date: u64,
+    pub score: u32,
+    pub wave: u32,
+    /// Seconds of play.
+    pub playtime: f32,
+}
+
+impl From<&Snapshot> for SlotInfo {
+    fn from(snapshot: &Snapshot) -> Self {
+        Self {
+            date: snapshot.header.date,
+            score: snapshot.score,
+            wave: snapshot.waves.number,
+            playtime: snapshot.director.as_ref().map_or(0., |d| d.elapsed),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Slot {
+    Empty,
+    Saved(SlotInfo),
+    /// Damaged, or from a newer build. Can be saved over but not loaded.
+    Unreadable,
+}
+
+fn read_slot(slot: usize) -> Slot {
+    let path = slot_path(slot);
+
+    if !path.exists() {
+        return Slot::Empty;
+    }
+
+    match Snapshot::read(&path) {
+        Ok(snapshot) => Slot::Saved(SlotInfo::from(&snapshot)),
+        Err(e) => {
+            warn!("Failed to read {}: {}", path.display(), e);
+            Slot::Unreadable
+        }
+    }
+}
+
+/// Moves a save from before there were slots into the first empty one.
+fn adopt_legacy_save() {
+    if !Path::new(LEGACY_SAVE_PATH).exists() {
+        return;
+    }
+
+    let Some(slot) = (0..SAVE_SLOTS).find(|slot| !slot_path(*slot).exists()) else {
+        return;
+    };
+
+    let path = slot_path(slot);
+    let moved =
+        std::fs::create_dir_all(SAVES_DIR).and_then(|()| std::fs::rename(LEGACY_SAVE_PATH, &path));
+
+    match moved {
+        Ok(()) => info!("Moved {} to {}", LEGACY_SAVE_PATH, path.display()),
+        Err(e) => warn!("Failed to move {}: {}", LEGACY_SAVE_PATH, e),
+    }
+}
+
+/// Where a run without a slot of its own is saved: the first empty slot,
+/// or failing that the one saved longest ago.
+fn free_slot() -> usize {
+    let slots: Vec<Slot> = (0..SAVE_SLOTS).map(read_slot).collect();
+
+    slots
+        .iter()
+        .position(|slot| !matches!(slot, Slot::Saved(_)))
+        .or_else(|| {
+            (0..SAVE_SLOTS).min_by_key(|i| match slots[*i] {
+                Slot::Saved(info) => info.date,
+                _ => 0,
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// The slot the current run was continued from or last saved into.
+#[derive(Resource, Default)]
+pub struct ActiveSlot(pub Option<usize>);
+
+/// A fresh run doesn't have a slot until it's first saved.
+pub fn clear_active_slot(mut active: ResMut<ActiveSlot>) {
+    active.0 = None;
+}
+
+/// A run that's over can't be continued, so its save goes with it.
+pub fn remove_finished_save(mut active: ResMut<ActiveSlot>) {
+    let Some(slot) = active.0.take() else {
+        return;
+    };
+
+    let path = slot_path(slot);
+
+    if path.exists()
+        && let Err(e) = std::fs::remove_file(&path)
+    {
+        warn!("Failed to remove {}: {}", path.display(), e);
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SlotMode {
+    /// Picking a run to continue.
+    #[default]
+    Load,
+    /// Picking where to save the current run.
+    Save,
+}
+
+#[derive(Resource, Default)]
+pub struct SlotMenu {
+    pub mode: SlotMode,
+    pub slots: Vec<Slot>,
+    pub selected: usize,
+    chosen: bool,
+}
+
+/// Opens the slot screen to load or save.
+pub fn open_slots(mode: SlotMode) -> impl FnMut(ResMut<SlotMenu>, ResMut<CurrentState>) {
+    move |mut menu, mut state| {
+        menu.mode = mode;
+        state.push(GameState::SaveSlots);
+    }
+}
+
+pub fn scan_slots(mut menu: ResMut<SlotMenu>, active: Res<ActiveSlot>) {
+    adopt_legacy_save();
+
+    menu.slots = (0..SAVE_SLOTS).map(read_slot).collect();
+    menu.chosen = false;
+    menu.selected = match menu.mode {
+        SlotMode::Load => menu
+            .slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| match slot {
+                Slot::Saved(info) => Some((i, info.date)),
+                _ => None,
+            })
+            .max_by_key(|(_, date)| *date)
+            .map_or(0, |(i, _)| i),
+        SlotMode::Save => active.0.unwrap_or_else(free_slot),
+    };
+}
+
+pub fn update_slot_menu(
+    mut actions: ResMut<Actions>,
+    mut menu: ResMut<SlotMenu>,
+    mut active: ResMut<ActiveSlot>,
+    mut state: ResMut<CurrentState>,
+) {
+    let count = menu.slots.len().max(1);
+
+    if actions.is_pressed(Action::MoveUp) {
+        menu.selected = (menu.selected + count - 1) % count;
+    }
+
+    if actions.is_pressed(Action::MoveDown) {
+        menu.selected = (menu.selected + 1) % count;
+    }
+
+    if actions.consume(Action::Confirm) {
+        let loadable = matches!(menu.slots.get(menu.selected), Some(Slot::Saved(_)));
+
+        if menu.mode == SlotMode::Save || loadable {
+            active.0 = Some(menu.selected);
+            menu.chosen = true;
+        }
+    }
+
+    if actions.is_pressed(Action::Back) {
+        state.pop();
+    }
+}
+
+/// Whether a slot was just picked on the slot screen in `mode`.
+pub fn slot_chosen(mode: SlotMode) -> impl Fn(Res<SlotMenu>) -> bool {
+    move |menu| menu.chosen && menu.mode == mode
+}
+
+type SavedQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static Position,
+        &'static Velocity,
+        &'static Glyph,
+        Option<&'static Collider>,
+        Option<&'static Health>,
+        Option<&'static Damage>,
+        Option<&'static Shield>,
+        (
+            Option<&'static Weave>,
+            Option<&'static Dive>,
+            Option<&'static Strafe>,
+            Option<&'static Shooter>,
+            Option<&'static SplitsOnDeath>,
+            Option<&'static Homing>,
+            Option<&'static PrefabName>,
+            Has<Swept>,
+        ),
+        (
+            Option<(
+                &'static Player,
+                &'static Weapon,
+                &'static Magnet,
+                &'static Upgrades,
+            )>,
+            Has<Faller>,
+            Has<Bullet>,
+            Has<EnemyBullet>,
+            Option<&'static Pickup>,
+        ),
+        (
+            Option<&'static Points>,
+            Has<Massive>,
+            Option<&'static Piercing>,
+            Option<&'static Lifetime>,
+            Option<&'static SpawnedAt>,
+        ),
+        (
+            Has<FormationLeader>,
+            Option<(&'static Parent, &'static Offset, &'static FormationSlot)>,
+        ),
+        Entity,
+    ),
+    (Without<Boss>, Without<BossSegment>),
+>;
+
+/// Bosses are not saved part by part. A run saved mid-fight restarts the
+/// boss from full health when resumed.
+pub fn save_game(
+    q_saved: SavedQuery,
+    q_bosses: Query<(), With<Boss>>,
+    rng: Res<Rng>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+    waves: Res<Waves>,
+    bombs: Res<Bombs>,
+    difficulty: Res<Difficulty>,
+    director: Res<Director>,
+    mode: Res<GameMode>,
+    credits: Res<Credits>,
+    combo: Res<Combo>,
+    stats: Res<RunStats>,
+    sector: Option<Res<Sector>>,
+    mut active: ResMut<ActiveSlot>,
+) {
+    let saved: Vec<_> = q_saved
+        .iter()
+        .filter_map(|item| {
+            let kind = match item.8 {
+                (Some((player, weapon, magnet, upgrades)), ..) => EntityKind::Player {
+                    speed: player.speed,
+                    weapon: weapon.kind,
+                    magnet_radius: magnet.radius,
+                    upgrades: *upgrades,
+                },
+                (_, true, ..) => EntityKind::Faller,
+                (_, _, true, ..) => EntityKind::Bullet,
+                (_, _, _, true, _) => EntityKind::EnemyBullet,
+                (.., Some(pickup)) => EntityKind::Pickup(*pickup),
+                _ => return None,
+            };
+
+            Some((kind, item))
+        })
+        .collect();
+
+    // Entities refer to each other by where they are in the save.
+    let indices: HashMap<Entity, usize> = saved
+        .iter()
+        .enumerate()
+        .map(|(i, (_, item))| (item.11, i))
+        .collect();
+
+    let entities = saved
+        .iter()
+        .map(|&(kind, item)| {
+            let (
+                position,
+                velocity,
+                glyph,
+                collider,
+                health,
+                damage,
+                shield,
+                motion,
+                _,
+                extra,
+                (leader, follower),
+                _,
+            ) = item;
+
+            // Save the glyph untinted; the shield tints it again on load.
+            let mut glyph = GlyphSnapshot::from(glyph);
+
+            if let Some(outline) = shield.and_then(Shield::base_outline) {
+                glyph.colors[2] = outline.into();
+            }
+
+            let slot = follower.and_then(|(parent, offset, slot)| {
+                Some(SlotSnapshot {
+                    leader: *indices.get(&parent.0)?,
+                    offset: offset.0.into(),
+                    base: slot.base.into(),
+                    amplitude: slot.amplitude,
+                    frequency: slot.frequency,
+                    lag: slot.lag,
+                    elapsed: slot.elapsed,
+                })
+            });
+
+            EntitySnapshot {
+                kind,
+                position: position.0.into(),
+                velocity: velocity.0.into(),
+                glyph,
+                collider: collider.map(|c| c.size.into()),
+                health: health.map(|h| (h.current, h.max)),
+                damage: damage.map(|d| d.0),
+                weave: motion.0.copied(),
+                dive: motion.1.copied(),
+                strafe: motion.2.copied(),
+                shooter: motion.3.copied(),
+                splits: motion.4.cloned(),
+                homing: motion.5.copied(),
+                shield: shield.map(|s| (s.charges, s.max)),
+                prefab: motion.6.map(|name| name.0.clone()),
+                swept: motion.7,
+                points: extra.0.map(|points| points.0),
+                massive: extra.1,
+                piercing: extra.2.map(|piercing| {
+                    piercing
+                        .hit
+                        .iter()
+                        .filter_map(|hit| indices.get(hit).copied())
+                        .collect()
+                }),
+                lifetime: extra.3.map(|lifetime| lifetime.0.as_secs_f32()),
+                spawned_at: extra.4.map(|spawned| spawned.0),
+                leader,
+                slot,
+            }
+        })
+        .collect();
+
+    let snapshot = Snapshot {
+        header: SaveHeader {
+            version: SAVE_VERSION,
+            seed: rng.seed(),
+            date: date::now() as u64,
+        },
+        rng: rng.clone(),
+        score: score.0,
+        lives: lives.0,
+        waves: Waves {
+            boss_pending: waves.boss_pending || !q_bosses.is_empty(),
+            ..*waves
+        },
+        bombs: bombs.0,
+        difficulty: *difficulty,
+        mode: *mode,
+        run_time: 0.,
+        director: Some(director.clone()),
+        sector: sector.map(|sector| sector.number),
+        combo: *combo,
+        credits: credits.0,
+        stats: stats.clone(),
+        entities,
+    };
+
+    let slot = *active.0.get_or_insert_with(free_slot);
+    let path = slot_path(slot);
+
+    match snapshot.write(&path) {
+        Ok(()) => info!("Saved to {}", path.display()),
+        Err(e) => warn!("Failed to save {}: {}", path.display(), e),
+    }
+}
+
+/// Whether there's a saved run to continue.
+pub fn has_save() -> bool {
+    Path::new(LEGACY_SAVE_PATH).exists() || (0..SAVE_SLOTS).any(|slot| slot_path(slot).exists())
+}
+
+/// Reads the [`ActiveSlot`] and, if it parses, resumes the run it contains.
+pub fn load_game(mut cmds: Commands, active: Res<ActiveSlot>, mut state: ResMut<CurrentState>) {
+    let Some(slot) = active.0 else {
+        return;
+    };
+
+    let path = slot_path(slot);
+
+    match Snapshot::read(&path) {
+        Ok(snapshot) => {
+            cmds.insert_resource(PendingSnapshot(snapshot));
+            state.set(GameState::Playing);
+        }
+        Err(e) => warn!("Failed to load {}: {}", path.display(), e),
+    }
+}
+
+pub fn restore_snapshot(world: &mut World) {
+    let Some(PendingSnapshot(snapshot)) = world.remove_resource::<PendingSnapshot>() else {
+        return;
+    };
+
+    info!("Resuming run with seed {}", snapshot.header.seed);
+    world.insert_resource(snapshot.rng);
+    world.insert_resource(Score(snapshot.score));
+    world.insert_resource(Lives(snapshot.lives));
+    world.insert_resource(snapshot.waves);
+    world.insert_resource(Bombs(snapshot.bombs));
+    world.insert_resource(snapshot.difficulty);
+    world.insert_resource(snapshot.mode);
+    world.insert_resource(snapshot.combo);
+    world.insert_resource(Credits(snapshot.credits));
+    world.insert_resource(snapshot.stats);
+
+    if let Some(director) = snapshot.director {
+        world.insert_resource(director);
+    }
+
+    // Restored quietly, so the sector's summary isn't shown again.
+    if let Some(number) = snapshot.sector {
+        let sector = Sector::generate(snapshot.header.seed, number, world.resource::<Ramp>());
+        world.resource_mut::<Starfield>().tint = sector.tint;
+        world.insert_resource(sector);
+    }
+
+    let mut spawned = Vec::with_capacity(snapshot.entities.len());
+    let mut links = Vec::new();
+
+    for saved in snapshot.entities {
+        let mut entity = world.spawn((
+            Position(saved.position.into()),
+            Velocity(saved.velocity.into()),
+            Glyph::from(saved.glyph),
+            DespawnOnExit(GameState::Playing),
+        ));
+
+        if let Some(size) = saved.collider {
+            entity.insert(Collider { size: size.into() });
+        }
+
+        if let Some((current, max)) = saved.health {
+            entity.insert(Health { current, max });
+        }
+
+        if let Some(damage) = saved.damage {
+            entity.insert(Damage(damage));
+        }
+
+        if let Some(weave) = saved.weave {
+            entity.insert(weave);
+        }
+
+        if let Some(dive) = saved.dive {
+            entity.insert(dive);
+        }
+
+        if let Some(strafe) = saved.strafe {
+            entity.insert(strafe);
+        }
+
+        if let Some(shooter) = saved.shooter {
+            entity.insert((
+                shooter,
+                Cooldown::<EnemyShoot>::started(Duration::from_secs_f32(shooter.interval)),
+            ));
+        }
+
+        if let Some(splits) = saved.splits {
+            entity.insert(splits);
+        }
+
+        if let Some(homing) = saved.homing {
+            entity.insert(homing);
+        }
+
+        if let Some((charges, max)) = saved.shield {
+            entity.insert(Shield::new(charges, max));
+        }
+
+        if let Some(name) = saved.prefab {
+            entity.insert(PrefabName(name));
+        }
+
+        if saved.swept {
+            entity.insert(Swept);
+        }
+
+        if let Some(points) = saved.points {
+            entity.insert(Points(points));
+        }
+
+        if saved.massive {
+            entity.insert(Massive);
+        }
+
+        if let Some(seconds) = saved.lifetime {
+            entity.insert(Lifetime(Duration::from_secs_f32(seconds)));
+        }
+
+        if let Some(seconds) = saved.spawned_at {
+            entity.insert(SpawnedAt(seconds));
+        }
+
+        if saved.leader {
+            entity.insert(FormationLeader);
+        }
+
+        // Saves from before health existed.
+        if matches!(saved.kind, EntityKind::Player { .. }) && saved.health.is_none() {
+            entity.insert(Health::new(PLAYER_HEALTH));
+        }
+
+        match saved.kind {
+            EntityKind::Player {
+                speed,
+                weapon,
+                magnet_radius,
+                upgrades,
+            } => {
+                entity.insert((
+                    Player { speed },
+                    Weapon::bundle(weapon),
+                    InvulnerableOnHit(HIT_INVULNERABILITY),
+                    player_dash(),
+                    Magnet {
+                        radius: magnet_radius,
+                        ..Magnet::default()
+                    },
+                    upgrades,
+                ));
+            }
+            EntityKind::Faller => {
+                entity.insert((Faller, DespawnOffscreen));
+            }
+            EntityKind::Bullet => {
+                entity.insert((Bullet, DespawnOffscreen));
+            }
+            EntityKind::EnemyBullet => {
+                entity.insert((EnemyBullet, DespawnOffscreen));
+            }
+            EntityKind::Pickup(pickup) => {
+                entity.insert((pickup, DespawnOffscreen));
+            }
+        }
+
+        spawned.push(entity.id());
+
+        if saved.piercing.is_some() || saved.slot.is_some() {
+            links.push((entity.id(), saved.piercing, saved.slot));
+        }
+    }
+
+    // Followers and piercing bullets refer to other entities, so they're
+    // hooked up once everything is back.
+    for (entity, piercing, slot) in links {
+        if let Some(hit) = piercing {
+            let hit = hit
+                .iter()
+                .filter_map(|&i| spawned.get(i).copied())
+                .collect();
+            world.entity_mut(entity).insert(Piercing { hit });
+        }
+
+        if let Some(slot) = slot
+            && let Some(&leader) = spawned.get(slot.leader)
+        {
+            world
+                .entity_mut(entity)
+                .insert((slot.slot(), SurvivesParent));
+            world
+                .commands()
+                .entity(entity)
+                .set_parent(leader, slot.offset.into());
+        }
+    }
+
+    world.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const V1_SAVE: &str = r#"(
+        header: (version: 1, seed: 7),
+        rng: (seed: 7, state: 1234, inc: 15),
+        score: 120,
+        run_time: 42.5,
+        entities: [],
+    )"#;
+
+    const V2_SAVE: &str = r#"(
+        header: (version: 2, seed: 7, date: 1700000000),
+        rng: (seed: 7, state: 1234, inc: 15),
+        score: 120,
+        lives: 2,
+        director: Some((
+            elapsed: 42.5,
+            spawn_rate: 1.5,
+            enemy_speed: 1.2,
+            wave_duration: 30.0,
+            roster: Some(["faller"]),
+            boss_waves: None,
+            boss: Swarm,
+            follows_ramp: true,
+        )),
+        entities: [
+            (
+                kind: Faller,
+                position: (10.0, 20.0),
+                velocity: (0.0, 90.0),
+                glyph: (idx: 25, size: 16.0, colors: ((255, 0, 0, 255), (0, 0, 0, 255), (0, 0, 0, 255), (0, 0, 0, 0))),
+                health: Some((1, 1)),
+                points: Some(10),
+                prefab: Some("faller"),
+            ),
+        ],
+    )"#;
+
+    #[test]
+    fn version_1_saves_ramp_the_director_from_run_time() {
+        let snapshot = Snapshot::from_ron(V1_SAVE).unwrap();
+
+        assert_eq!(snapshot.header.version, SAVE_VERSION);
+        assert_eq!(snapshot.score, 120);
+
+        let director = snapshot.director.expect("migration adds a director");
+        assert_eq!(director.elapsed, snapshot.run_time);
+        assert_eq!(director.elapsed, 42.5);
+    }
+
+    #[test]
+    fn saves_from_newer_builds_are_refused() {
+        let newer = V1_SAVE.replace("version: 1", &format!("version: {}", SAVE_VERSION + 1));
+
+        assert!(matches!(
+            Snapshot::from_ron(&newer),
+            Err(SaveError::UnsupportedVersion(v)) if v == SAVE_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn current_saves_round_trip() {
+        let snapshot = Snapshot::from_ron(V2_SAVE).unwrap();
+        let ron = snapshot.to_ron().unwrap();
+        let again = Snapshot::from_ron(&ron).unwrap();
+
+        assert_eq!(again.to_ron().unwrap(), ron);
+        assert_eq!(again.lives, 2);
+        assert_eq!(again.director.unwrap().elapsed, 42.5);
+        assert_eq!(again.entities.len(), 1);
+        assert_eq!(again.entities[0].prefab.as_deref(), Some("faller"));
+        assert_eq!(again.entities[0].points, Some(10));
+    }
+}
@@ -0,0 +1,59 @@
+use bevy_ecs::prelude::*;
+
+use super::{BOMB_SCORE_FACTOR, Bombed, Collider, Combo, DeathEvent, Difficulty, Faller};
+
+/// Points earned this run.
+#[derive(Resource, Default, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Score(pub u32);
+
+/// Points earned before the [`Combo`] multiplier is applied. Anything that
+/// awards points sends this rather than touching [`Score`] directly.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct ScoreEvent {
+    pub points: u32,
+}
+
+/// Points for destroying a faller one texel wide. Bigger fallers are worth
+/// proportionally more, unless they have their own [`Points`].
+const POINTS_PER_TEXEL: f32 = 0.5;
+
+/// Fixed points for destroying a faller, from its prefab's `score`.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Points(pub u32);
+
+pub fn reset_score(mut score: ResMut<Score>) {
+    score.0 = 0;
+}
+
+pub fn score_on_death(
+    mut ev_death: EventReader<DeathEvent>,
+    mut ev_score: EventWriter<ScoreEvent>,
+    q_fallers: Query<(&Collider, Option<&Points>, Has<Bombed>), With<Faller>>,
+) {
+    for ev in ev_death.read() {
+        let Ok((collider, fixed, bombed)) = q_fallers.get(ev.entity) else {
+            continue;
+        };
+
+        let mut points = fixed.map_or(collider.size.x * POINTS_PER_TEXEL, |p| p.0 as f32);
+
+        if bombed {
+            points *= BOMB_SCORE_FACTOR;
+        }
+
+        ev_score.send(ScoreEvent {
+            points: points.ceil() as u32,
+        });
+    }
+}
+
+pub fn apply_score(
+    mut ev_score: EventReader<ScoreEvent>,
+    mut score: ResMut<Score>,
+    combo: Res<Combo>,
+    difficulty: Res<Difficulty>,
+) {
+    for ev in ev_score.read() {
+        score.0 += Difficulty::scale(ev.points * combo.multiplier(), difficulty.score());
+    }
+}
@@ -0,0 +1,127 @@
+//! Endless runs are split into sectors, one boss cycle each. Every sector
+//! is generated from the run seed and its number, so a seed always plays
+//! the same sequence, and a summary is shown before each one starts.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{BOSS_EVERY, BossKind, Director, Ramp, Waves};
+use crate::t;
+use crate::{
+    engine::{Action, Actions, CurrentState, GameState, Rng},
+    rendering::Starfield,
+};
+
+/// Starfield tints sectors pick from, with the text key of a name for the
+/// summary.
+const PALETTES: [(&str, Vec3); 5] = [
+    ("palette.deep_blue", vec3(0.6, 0.8, 1.4)),
+    ("palette.crimson_nebula", vec3(1.5, 0.6, 0.6)),
+    ("palette.emerald_drift", vec3(0.6, 1.4, 0.8)),
+    ("palette.golden_haze", vec3(1.4, 1.2, 0.6)),
+    ("palette.violet_expanse", vec3(1.2, 0.7, 1.5)),
+];
+
+/// Fallers in a sector's mix on top of the plain faller, which every
+/// sector has.
+const EXTRA_FALLERS: u32 = 3;
+
+#[derive(Resource, Clone, Debug)]
+pub struct Sector {
+    pub number: u32,
+    /// Faller prefabs that may spawn, plus `"formation"` for formations.
+    pub roster: Vec<String>,
+    /// Multiplier on spawn rate.
+    pub hazard_density: f32,
+    /// Text key of the palette's name.
+    pub palette: &'static str,
+    pub tint: Vec3,
+    pub boss: BossKind,
+}
+
+impl Sector {
+    /// The sector with this `number` for a run seeded with `seed`, mixing
+    /// fallers from the [`Ramp`]'s table.
+    pub fn generate(seed: u64, number: u32, ramp: &Ramp) -> Self {
+        // Mixed so neighbouring sectors don't get correlated streams.
+        let mut rng = Rng::new(seed ^ (number as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+        let mut names = ramp.fallers.iter().map(|(name, _)| name.clone());
+        let mut roster: Vec<String> = names.next().into_iter().collect();
+        let mut candidates: Vec<String> = names.collect();
+
+        for _ in 0..EXTRA_FALLERS.min(candidates.len() as u32) {
+            let pick = rng.gen_range(0, candidates.len());
+            roster.push(candidates.swap_remove(pick));
+        }
+
+        if rng.next_f32() < 0.5 {
+            roster.push("formation".to_string());
+        }
+
+        let (palette, tint) = PALETTES[rng.gen_range(0, PALETTES.len())];
+
+        Self {
+            number,
+            roster,
+            hazard_density: rng.gen_range(0.8, 1.4),
+            palette,
+            tint,
+            boss: BossKind::ALL[rng.gen_range(0, BossKind::ALL.len())],
+        }
+    }
+
+    /// The sector a given wave belongs to, counting from 1.
+    pub fn number_for_wave(wave: u32) -> u32 {
+        (wave.max(1) - 1) / BOSS_EVERY + 1
+    }
+
+    pub fn hazard_name(&self) -> String {
+        let key = match self.hazard_density {
+            d if d < 0.95 => "hazard.low",
+            d if d < 1.2 => "hazard.moderate",
+            _ => "hazard.high",
+        };
+
+        t!(key)
+    }
+}
+
+pub fn reset_sector(mut cmds: Commands, mut starfield: ResMut<Starfield>) {
+    cmds.remove_resource::<Sector>();
+    starfield.tint = Vec3::ONE;
+}
+
+/// Generates the next sector when the waves move into it, points the
+/// [`Director`] at it and shows its summary.
+pub fn advance_sector(
+    mut cmds: Commands,
+    mut director: ResMut<Director>,
+    mut starfield: ResMut<Starfield>,
+    mut state: ResMut<CurrentState>,
+    sector: Option<Res<Sector>>,
+    waves: Res<Waves>,
+    ramp: Res<Ramp>,
+    rng: Res<Rng>,
+) {
+    let number = Sector::number_for_wave(waves.number);
+
+    if sector.is_some_and(|sector| sector.number == number) {
+        return;
+    }
+
+    let sector = Sector::generate(rng.seed(), number, &ramp);
+
+    director.roster = Some(sector.roster.clone());
+    director.boss = sector.boss;
+    starfield.tint = sector.tint;
+
+    cmds.insert_resource(sector);
+    state.push(GameState::SectorIntro);
+}
+
+pub fn update_sector_intro(mut actions: ResMut<Actions>, mut state: ResMut<CurrentState>) {
+    if actions.consume(Action::Confirm) {
+        state.pop();
+    }
+}
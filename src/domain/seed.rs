@@ -0,0 +1,92 @@
+//! Typing a seed on the main menu, so a run can be replayed or shared
+//! without setting [`SEED_ENV_VAR`](crate::engine::SEED_ENV_VAR).
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::engine::{CurrentState, Deterministic, GameState, KeyInput, Rng, TextInput, Time};
+
+/// Digits in the largest `u64`.
+const SEED_LENGTH: usize = 20;
+
+/// The seed for the next run, if one was typed in or passed at launch.
+#[derive(Resource, Default)]
+pub struct CustomSeed(pub Option<u64>);
+
+/// Seeded once at startup, and where each run draws its seed from when it
+/// isn't given one, so a session started from the same seed plays the same
+/// runs in the same order.
+#[derive(Resource)]
+pub struct SessionRng(pub Rng);
+
+/// The seed being typed.
+#[derive(Resource)]
+pub struct SeedEntry {
+    pub seed: TextInput,
+}
+
+impl Default for SeedEntry {
+    fn default() -> Self {
+        Self {
+            seed: TextInput::new(SEED_LENGTH, |c| c.is_ascii_digit().then_some(c)),
+        }
+    }
+}
+
+impl SeedEntry {
+    /// The typed seed, `Ok(None)` if nothing was typed, or `Err` if the
+    /// digits don't fit in a `u64`.
+    pub fn parse(&self) -> Result<Option<u64>, std::num::ParseIntError> {
+        if self.seed.text.is_empty() {
+            return Ok(None);
+        }
+
+        self.seed.text.parse().map(Some)
+    }
+}
+
+/// Reseeds the [`Rng`] as a run starts, from the [`CustomSeed`] if there
+/// is one and otherwise the [`SessionRng`], so [`Rng::seed`] reproduces
+/// this run on its own.
+pub fn seed_run(
+    mut rng: ResMut<Rng>,
+    mut session: ResMut<SessionRng>,
+    mut custom_seed: ResMut<CustomSeed>,
+    deterministic: Option<Res<Deterministic>>,
+) {
+    let seed = custom_seed.0.take().unwrap_or_else(|| session.0.next_u64());
+    info!("Seed: {}", seed);
+    *rng = Rng::new(seed);
+
+    // Cosmetic randomness, e.g. sound variation, follows the seed too.
+    if deterministic.is_some() {
+        rand::srand(seed);
+    }
+}
+
+/// Enter starts a run with the typed seed, or a random one if nothing was
+/// typed. Escape goes back.
+pub fn update_seed_entry(
+    keys: Res<KeyInput>,
+    time: Res<Time>,
+    mut entry: ResMut<SeedEntry>,
+    mut custom_seed: ResMut<CustomSeed>,
+    mut state: ResMut<CurrentState>,
+) {
+    entry.seed.update(&keys, time.unscaled_dt);
+
+    if keys.is_pressed(KeyCode::Enter)
+        && let Ok(seed) = entry.parse()
+    {
+        custom_seed.0 = seed;
+        state.set(GameState::Playing);
+    }
+
+    if keys.is_pressed(KeyCode::Escape) {
+        state.pop();
+    }
+}
+
+pub fn reset_seed_entry(mut entry: ResMut<SeedEntry>) {
+    *entry = SeedEntry::default();
+}
@@ -0,0 +1,335 @@
+//! Player preferences kept in [`SETTINGS_PATH`], and the menus that change
+//! them. They're read once on startup into the resources they configure,
+//! and [`save_settings`] writes them back whenever one of those changes and
+//! on exit.
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{AimMode, Autosave, Difficulty};
+use crate::audio::AudioSettings;
+use crate::engine::{
+    Action, Actions, AppExit, Binding, Bindings, CurrentState, FrameCap, GameState, Gamepad,
+    InputBuffer, KEYS, KeyInput, Language, MouseInput, write_atomic,
+};
+use crate::rendering::Tileset;
+use crate::t;
+
+pub const SETTINGS_PATH: &str = "settings.ron";
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct Settings {
+    #[serde(default)]
+    pub bindings: Bindings,
+    #[serde(default)]
+    pub input_buffer: InputBuffer,
+    #[serde(default)]
+    pub aim: AimMode,
+    #[serde(default)]
+    pub audio: AudioSettings,
+    #[serde(default)]
+    pub language: Language,
+    #[serde(default)]
+    pub tileset: Tileset,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    #[serde(default)]
+    pub autosave: Autosave,
+    #[serde(default)]
+    pub frame_cap: FrameCap,
+}
+
+impl Settings {
+    /// A missing or unreadable file gives the defaults, which are written
+    /// out on the first [`save_settings`]. An unreadable file is kept
+    /// beside it with a `.bak` extension so hand edits aren't lost.
+    pub fn load(path: &str) -> Self {
+        let Ok(src) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+
+        let mut settings: Self = ron::from_str(&src).unwrap_or_else(|e| {
+            warn!("Replacing unreadable {}: {}", path, e);
+
+            let backup = format!("{}.bak", path);
+            if let Err(e) = std::fs::rename(path, &backup) {
+                warn!("Failed to back up {} to {}: {}", path, backup, e);
+            }
+
+            Self::default()
+        });
+        settings.bindings.fill_defaults();
+        settings
+    }
+
+    pub fn save(&self, path: &str) {
+        let result = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|src| write_atomic(path, &src).map_err(|e| e.to_string()));
+
+        if let Err(e) = result {
+            warn!("Failed to save {}: {}", path, e);
+        }
+    }
+}
+
+/// The resources each of [`Settings`]' fields is loaded into, for menus
+/// that change them and save the result.
+#[derive(SystemParam)]
+pub struct SettingsMut<'w> {
+    pub bindings: ResMut<'w, Bindings>,
+    pub input_buffer: ResMut<'w, InputBuffer>,
+    pub aim: ResMut<'w, AimMode>,
+    pub audio: ResMut<'w, AudioSettings>,
+    pub language: ResMut<'w, Language>,
+    pub tileset: ResMut<'w, Tileset>,
+    pub difficulty: ResMut<'w, Difficulty>,
+    pub autosave: ResMut<'w, Autosave>,
+    pub frame_cap: ResMut<'w, FrameCap>,
+}
+
+impl SettingsMut<'_> {
+    /// Whether any setting changed since the last [`save_settings`].
+    pub fn is_changed(&self) -> bool {
+        self.bindings.is_changed()
+            || self.input_buffer.is_changed()
+            || self.aim.is_changed()
+            || self.audio.is_changed()
+            || self.language.is_changed()
+            || self.tileset.is_changed()
+            || self.difficulty.is_changed()
+            || self.autosave.is_changed()
+            || self.frame_cap.is_changed()
+    }
+
+    pub fn save(&self) {
+        Settings {
+            bindings: self.bindings.clone(),
+            input_buffer: *self.input_buffer,
+            aim: *self.aim,
+            audio: *self.audio,
+            language: *self.language,
+            tileset: *self.tileset,
+            difficulty: *self.difficulty,
+            autosave: *self.autosave,
+            frame_cap: *self.frame_cap,
+        }
+        .save(SETTINGS_PATH);
+    }
+}
+
+/// Runs at the end of every frame. The first run writes out the defaults
+/// if the file was missing or unreadable.
+pub fn save_settings(settings: SettingsMut, exit: Res<AppExit>) {
+    if settings.is_changed() || exit.0 {
+        settings.save();
+    }
+}
+
+/// Entries of the settings menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SettingsItem {
+    Controls,
+    InputBuffer,
+    Aim,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    Mute,
+    Language,
+    Tileset,
+    Autosave,
+    FrameCap,
+}
+
+impl SettingsItem {
+    pub const ALL: [SettingsItem; 11] = [
+        SettingsItem::Controls,
+        SettingsItem::InputBuffer,
+        SettingsItem::Aim,
+        SettingsItem::MasterVolume,
+        SettingsItem::MusicVolume,
+        SettingsItem::SfxVolume,
+        SettingsItem::Mute,
+        SettingsItem::Language,
+        SettingsItem::Tileset,
+        SettingsItem::Autosave,
+        SettingsItem::FrameCap,
+    ];
+
+    pub fn name(&self) -> String {
+        let key = match self {
+            SettingsItem::Controls => "settings.controls",
+            SettingsItem::InputBuffer => "settings.input_buffer",
+            SettingsItem::Aim => "settings.aim",
+            SettingsItem::MasterVolume => "settings.master_volume",
+            SettingsItem::MusicVolume => "settings.music_volume",
+            SettingsItem::SfxVolume => "settings.sfx_volume",
+            SettingsItem::Mute => "settings.mute",
+            SettingsItem::Language => "settings.language",
+            SettingsItem::Tileset => "settings.tileset",
+            SettingsItem::Autosave => "settings.autosave",
+            SettingsItem::FrameCap => "settings.frame_cap",
+        };
+
+        t!(key)
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct SettingsMenu {
+    pub selected: usize,
+}
+
+/// Confirming opens a submenu; moving left and right adjusts a value.
+pub fn update_settings_menu(
+    mut actions: ResMut<Actions>,
+    mut settings: SettingsMut,
+    mut menu: ResMut<SettingsMenu>,
+    mut state: ResMut<CurrentState>,
+) {
+    let count = SettingsItem::ALL.len();
+
+    if actions.is_pressed(Action::MoveUp) {
+        menu.selected = (menu.selected + count - 1) % count;
+    }
+
+    if actions.is_pressed(Action::MoveDown) {
+        menu.selected = (menu.selected + 1) % count;
+    }
+
+    let item = SettingsItem::ALL[menu.selected];
+
+    if item == SettingsItem::Controls && actions.consume(Action::Confirm) {
+        state.push(GameState::Controls);
+    }
+
+    let step = match (
+        actions.is_pressed(Action::MoveLeft),
+        actions.is_pressed(Action::MoveRight),
+    ) {
+        (true, false) => -1,
+        (false, true) => 1,
+        _ => 0,
+    };
+
+    if step != 0 {
+        match item {
+            SettingsItem::Controls => {}
+            SettingsItem::InputBuffer => {
+                let millis = settings.input_buffer.0;
+                settings.input_buffer.0 = if step < 0 {
+                    millis.saturating_sub(InputBuffer::STEP)
+                } else {
+                    (millis + InputBuffer::STEP).min(InputBuffer::MAX)
+                };
+            }
+            SettingsItem::Aim => *settings.aim = settings.aim.toggle(),
+            SettingsItem::MasterVolume => {
+                settings.audio.master = AudioSettings::adjust(settings.audio.master, step);
+            }
+            SettingsItem::MusicVolume => {
+                settings.audio.music = AudioSettings::adjust(settings.audio.music, step);
+            }
+            SettingsItem::SfxVolume => {
+                settings.audio.sfx = AudioSettings::adjust(settings.audio.sfx, step);
+            }
+            SettingsItem::Mute => settings.audio.muted = !settings.audio.muted,
+            SettingsItem::Language => *settings.language = settings.language.cycle(step as isize),
+            SettingsItem::Tileset => *settings.tileset = settings.tileset.cycle(step as isize),
+            SettingsItem::Autosave => settings.autosave.enabled = !settings.autosave.enabled,
+            SettingsItem::FrameCap => *settings.frame_cap = settings.frame_cap.cycle(step as isize),
+        }
+    }
+
+    if actions.is_pressed(Action::Back) {
+        state.pop();
+    }
+}
+
+/// [`Action::Mute`] works from anywhere but the screens that read raw keys.
+pub fn toggle_mute(mut settings: SettingsMut) {
+    settings.audio.muted = !settings.audio.muted;
+}
+
+/// The controls screen lists every [`Action`], then a reset entry.
+#[derive(Resource, Default)]
+pub struct ControlsMenu {
+    pub selected: usize,
+    /// Waiting for the input to bind to the selected action.
+    pub capturing: bool,
+    /// Feedback from the last rebind, e.g. which action it swapped with.
+    pub message: Option<String>,
+}
+
+impl ControlsMenu {
+    /// The highlighted action, or `None` on the reset entry.
+    pub fn action(&self) -> Option<Action> {
+        Action::ALL.get(self.selected).copied()
+    }
+}
+
+/// The first key or button pressed this frame on any device.
+fn captured_input(keys: &KeyInput, mouse: &MouseInput, gamepad: &Gamepad) -> Option<Binding> {
+    KEYS.into_iter()
+        .find(|&key| keys.is_pressed(key))
+        .map(Binding::Key)
+        .or_else(|| mouse.pressed.iter().next().map(|&b| Binding::Mouse(b)))
+        .or_else(|| gamepad.pressed.iter().next().map(|&b| Binding::Gamepad(b)))
+}
+
+/// Confirming an action waits for the next key or button and binds it.
+/// Taking a binding from another action swaps the two.
+pub fn update_controls(
+    keys: Res<KeyInput>,
+    mouse: Res<MouseInput>,
+    gamepad: Res<Gamepad>,
+    mut actions: ResMut<Actions>,
+    mut settings: SettingsMut,
+    mut menu: ResMut<ControlsMenu>,
+    mut state: ResMut<CurrentState>,
+) {
+    if menu.capturing {
+        let Some(binding) = captured_input(&keys, &mouse, &gamepad) else {
+            return;
+        };
+        let action = menu.action().unwrap();
+
+        menu.capturing = false;
+        menu.message = settings.bindings.rebind(action, binding).map(|other| {
+            t!(
+                "controls.swapped",
+                binding = binding.name(),
+                action = other.name()
+            )
+        });
+        return;
+    }
+
+    let count = Action::ALL.len() + 1;
+
+    if actions.is_pressed(Action::MoveUp) {
+        menu.selected = (menu.selected + count - 1) % count;
+    }
+
+    if actions.is_pressed(Action::MoveDown) {
+        menu.selected = (menu.selected + 1) % count;
+    }
+
+    if actions.consume(Action::Confirm) {
+        menu.message = None;
+
+        if menu.action().is_some() {
+            menu.capturing = true;
+        } else {
+            *settings.bindings = Bindings::default();
+            menu.message = Some(t!("controls.reset"));
+        }
+    }
+
+    if actions.is_pressed(Action::Back) {
+        menu.message = None;
+        state.pop();
+    }
+}
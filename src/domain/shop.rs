@@ -0,0 +1,216 @@
+//! Spending [`Credits`] between sectors. The shop opens over
+//! [`GameState::Playing`] each time a boss goes down, and everything bought
+//! is kept on the player's [`Upgrades`].
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Bombs, Boss, DeathEvent, Lives, Player};
+use crate::engine::{Action, Actions, CurrentState, GameState, MouseInput, Screen};
+use crate::t;
+
+/// Extra shots per second for each fire rate upgrade, as a share of the
+/// weapon's own rate.
+const FIRE_RATE_STEP: f32 = 0.15;
+
+/// Extra movement speed for each speed upgrade, as a share of the base.
+const SPEED_STEP: f32 = 0.1;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Upgrade {
+    FireRate,
+    Speed,
+    ExtraLife,
+    BombCharge,
+}
+
+impl Upgrade {
+    pub const ALL: [Upgrade; 4] = [
+        Upgrade::FireRate,
+        Upgrade::Speed,
+        Upgrade::ExtraLife,
+        Upgrade::BombCharge,
+    ];
+
+    pub fn name(&self) -> String {
+        let key = match self {
+            Upgrade::FireRate => "upgrade.fire_rate",
+            Upgrade::Speed => "upgrade.speed",
+            Upgrade::ExtraLife => "upgrade.extra_life",
+            Upgrade::BombCharge => "upgrade.bomb",
+        };
+
+        t!(key)
+    }
+
+    pub fn description(&self) -> String {
+        let key = match self {
+            Upgrade::FireRate => "upgrade.fire_rate.description",
+            Upgrade::Speed => "upgrade.speed.description",
+            Upgrade::ExtraLife => "upgrade.extra_life.description",
+            Upgrade::BombCharge => "upgrade.bomb.description",
+        };
+
+        t!(key)
+    }
+
+    /// Price of the first purchase. Each one after costs this much more.
+    fn base_cost(&self) -> u32 {
+        match self {
+            Upgrade::FireRate => 100,
+            Upgrade::Speed => 80,
+            Upgrade::ExtraLife => 250,
+            Upgrade::BombCharge => 60,
+        }
+    }
+}
+
+/// How many of each [`Upgrade`] the player has bought this run.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Upgrades {
+    pub fire_rate: u32,
+    pub speed: u32,
+    pub lives: u32,
+    pub bombs: u32,
+}
+
+impl Upgrades {
+    pub fn level(&self, upgrade: Upgrade) -> u32 {
+        match upgrade {
+            Upgrade::FireRate => self.fire_rate,
+            Upgrade::Speed => self.speed,
+            Upgrade::ExtraLife => self.lives,
+            Upgrade::BombCharge => self.bombs,
+        }
+    }
+
+    fn level_mut(&mut self, upgrade: Upgrade) -> &mut u32 {
+        match upgrade {
+            Upgrade::FireRate => &mut self.fire_rate,
+            Upgrade::Speed => &mut self.speed,
+            Upgrade::ExtraLife => &mut self.lives,
+            Upgrade::BombCharge => &mut self.bombs,
+        }
+    }
+
+    pub fn cost(&self, upgrade: Upgrade) -> u32 {
+        upgrade.base_cost() * (1 + self.level(upgrade))
+    }
+
+    /// Multiplier on the weapon's shots per second.
+    pub fn fire_rate_multiplier(&self) -> f32 {
+        1. + self.fire_rate as f32 * FIRE_RATE_STEP
+    }
+
+    /// Multiplier on [`Player::speed`].
+    pub fn speed_multiplier(&self) -> f32 {
+        1. + self.speed as f32 * SPEED_STEP
+    }
+}
+
+const CARD_SIZE: Vec2 = vec2(112., 72.);
+const CARD_GAP: f32 = 12.;
+
+/// Where the card for `Upgrade::ALL[index]` is drawn, in a row across the
+/// middle of the screen.
+pub fn card_rect(index: usize, screen: &Screen) -> Rect {
+    let count = Upgrade::ALL.len() as f32;
+    let row_width = count * CARD_SIZE.x + (count - 1.) * CARD_GAP;
+    let left = screen.width as f32 / 2. - row_width / 2.;
+
+    Rect::new(
+        left + index as f32 * (CARD_SIZE.x + CARD_GAP),
+        screen.height as f32 / 3. + 40.,
+        CARD_SIZE.x,
+        CARD_SIZE.y,
+    )
+}
+
+/// Earned by collecting gems, spent in the shop.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Credits(pub u32);
+
+/// The card under the cursor.
+#[derive(Resource, Default)]
+pub struct Shop {
+    pub selected: usize,
+}
+
+impl Shop {
+    pub fn selected(&self) -> Upgrade {
+        Upgrade::ALL[self.selected]
+    }
+}
+
+pub fn reset_credits(mut credits: ResMut<Credits>) {
+    *credits = Credits::default();
+}
+
+pub fn open_shop(
+    mut ev_death: EventReader<DeathEvent>,
+    q_bosses: Query<(), With<Boss>>,
+    mut shop: ResMut<Shop>,
+    mut state: ResMut<CurrentState>,
+) {
+    if ev_death.read().any(|ev| q_bosses.contains(ev.entity)) {
+        *shop = Shop::default();
+        state.push(GameState::Shop);
+    }
+}
+
+/// Moving left and right picks a card, firing buys it and backing out
+/// leaves. With the mouse, hovering picks a card and clicking buys it.
+pub fn update_shop(
+    actions: Res<Actions>,
+    mouse: Res<MouseInput>,
+    screen: Res<Screen>,
+    mut shop: ResMut<Shop>,
+    mut credits: ResMut<Credits>,
+    mut lives: ResMut<Lives>,
+    mut bombs: ResMut<Bombs>,
+    mut q_player: Single<&mut Upgrades, With<Player>>,
+    mut state: ResMut<CurrentState>,
+    mut last_mouse: Local<Vec2>,
+) {
+    let count = Upgrade::ALL.len();
+
+    if actions.is_pressed(Action::MoveLeft) {
+        shop.selected = (shop.selected + count - 1) % count;
+    }
+
+    if actions.is_pressed(Action::MoveRight) {
+        shop.selected = (shop.selected + 1) % count;
+    }
+
+    let hovered = (0..count).find(|&i| card_rect(i, &screen).contains(mouse.position));
+
+    // A resting cursor shouldn't fight the keyboard for the selection.
+    if let Some(index) = hovered
+        && mouse.position != *last_mouse
+    {
+        shop.selected = index;
+    }
+
+    *last_mouse = mouse.position;
+
+    if actions.is_pressed(Action::Fire) {
+        let upgrade = shop.selected();
+        let cost = q_player.cost(upgrade);
+
+        if credits.0 >= cost {
+            credits.0 -= cost;
+            *q_player.level_mut(upgrade) += 1;
+
+            match upgrade {
+                Upgrade::ExtraLife => lives.0 += 1,
+                Upgrade::BombCharge => bombs.0 += 1,
+                Upgrade::FireRate | Upgrade::Speed => {}
+            }
+        }
+    }
+
+    if actions.is_pressed(Action::Back) {
+        state.pop();
+    }
+}
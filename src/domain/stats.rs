@@ -0,0 +1,145 @@
+//! Tallies of how a run went, shown when it ends and added into the
+//! [`Profile`](super::Profile)'s lifetime totals.
+
+use std::collections::BTreeMap;
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{CollisionEvent, DeathEvent, Faller, FireEvent, HitEvent, Player, PrefabName, Profile};
+use crate::engine::Time;
+
+#[derive(Resource, Serialize, Deserialize, Clone, Default, Debug)]
+pub struct RunStats {
+    /// Player bullets fired, counting each bullet of a spread.
+    pub shots: u32,
+    /// Shots that hit at least one enemy.
+    pub hits: u32,
+    /// Enemies destroyed, by the prefab they were spawned from.
+    pub destroyed: BTreeMap<String, u32>,
+    /// Health the player lost. Hits a shield soaked up don't count.
+    pub damage_taken: u32,
+    /// Seconds of play.
+    pub duration: f32,
+}
+
+impl RunStats {
+    /// The fraction of shots that hit something.
+    pub fn accuracy(&self) -> f32 {
+        if self.shots == 0 {
+            return 0.;
+        }
+
+        self.hits as f32 / self.shots as f32
+    }
+
+    pub fn total_destroyed(&self) -> u32 {
+        self.destroyed.values().sum()
+    }
+
+    /// The `count` enemy types destroyed most, most first.
+    pub fn most_destroyed(&self, count: usize) -> Vec<(&str, u32)> {
+        let mut destroyed: Vec<_> = self
+            .destroyed
+            .iter()
+            .map(|(name, count)| (name.as_str(), *count))
+            .collect();
+
+        destroyed.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        destroyed.truncate(count);
+        destroyed
+    }
+
+    /// Adds another run's tallies into these.
+    pub fn add(&mut self, other: &RunStats) {
+        self.shots += other.shots;
+        self.hits += other.hits;
+        self.damage_taken += other.damage_taken;
+        self.duration += other.duration;
+
+        for (name, count) in &other.destroyed {
+            *self.destroyed.entry(name.clone()).or_default() += count;
+        }
+    }
+}
+
+/// Every finished run's [`RunStats`] added together.
+#[derive(Serialize, Deserialize, Clone, Default, Debug)]
+pub struct LifetimeStats {
+    pub runs: u32,
+    pub totals: RunStats,
+}
+
+/// A prefab name as shown in the stats, e.g. `faller_big` as `FALLER BIG`.
+pub fn display_name(prefab: &str) -> String {
+    prefab.replace('_', " ").to_uppercase()
+}
+
+/// Marks a bullet already counted as a hit, so piercing shots count once.
+#[derive(Component)]
+pub struct Landed;
+
+pub fn reset_stats(mut stats: ResMut<RunStats>) {
+    *stats = RunStats::default();
+}
+
+pub fn tally_play_time(mut stats: ResMut<RunStats>, time: Res<Time>) {
+    stats.duration += time.fixed_dt;
+}
+
+/// Runs after damage is applied, while the dead can still be looked at.
+pub fn tally_stats(
+    mut cmds: Commands,
+    mut stats: ResMut<RunStats>,
+    mut ev_fire: EventReader<FireEvent>,
+    mut ev_collision: EventReader<CollisionEvent>,
+    mut ev_hit: EventReader<HitEvent>,
+    mut ev_death: EventReader<DeathEvent>,
+    q_landed: Query<(), With<Landed>>,
+    q_fallers: Query<Option<&PrefabName>, With<Faller>>,
+    q_player: Option<Single<Entity, With<Player>>>,
+    mut landed: Local<Vec<Entity>>,
+) {
+    landed.clear();
+
+    for ev in ev_fire.read() {
+        stats.shots += ev.shots;
+    }
+
+    for ev in ev_collision.read() {
+        let CollisionEvent::BulletHitFaller { bullet, .. } = *ev else {
+            continue;
+        };
+
+        if q_landed.contains(bullet) || landed.contains(&bullet) {
+            continue;
+        }
+
+        landed.push(bullet);
+        stats.hits += 1;
+        cmds.entity(bullet).try_insert(Landed);
+    }
+
+    let player = q_player.map(|player| *player);
+
+    for ev in ev_hit.read() {
+        if player == Some(ev.entity) {
+            stats.damage_taken += ev.damage;
+        }
+    }
+
+    for ev in ev_death.read() {
+        let Ok(name) = q_fallers.get(ev.entity) else {
+            continue;
+        };
+
+        let name = name.map_or("faller", |name| name.0.as_str());
+        *stats.destroyed.entry(name.to_string()).or_default() += 1;
+    }
+}
+
+/// Adds the run that just ended into the lifetime totals.
+pub fn record_lifetime_stats(stats: Res<RunStats>, mut profile: ResMut<Profile>) {
+    profile.lifetime.runs += 1;
+    profile.lifetime.totals.add(&stats);
+}
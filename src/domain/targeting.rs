@@ -0,0 +1,24 @@
+//! Target acquisition shared by anything that needs to pick something to
+//! chase or aim at.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::Position;
+
+/// The candidate closest to `origin`, along with its position.
+///
+/// Feed it any query over `(Entity, &Position)`, filtered to whatever counts
+/// as a target.
+pub fn nearest<'a>(
+    origin: Vec2,
+    candidates: impl IntoIterator<Item = (Entity, &'a Position)>,
+) -> Option<(Entity, Vec2)> {
+    candidates
+        .into_iter()
+        .map(|(entity, position)| (entity, position.0))
+        .min_by(|(_, a), (_, b)| {
+            a.distance_squared(origin)
+                .total_cmp(&b.distance_squared(origin))
+        })
+}
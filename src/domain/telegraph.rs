@@ -0,0 +1,104 @@
+//! Warnings flashed at the top of the screen shortly before fallers arrive,
+//! so nothing drops in without notice.
+
+use std::time::Duration;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Formation, HierarchyCommands, Position, PrefabSpawner};
+use crate::{
+    engine::{DespawnOnExit, GameState, Time},
+    rendering::Glyph,
+};
+
+/// How long a warning shows before its fallers spawn.
+pub const TELEGRAPH_TIME: Duration = Duration::from_millis(500);
+
+/// Height the warnings are drawn at, in texels from the top.
+const TELEGRAPH_Y: f32 = 10.;
+
+/// Fallers that will spawn above this warning once `remaining` runs out.
+#[derive(Component, Clone, Debug)]
+pub struct Telegraph {
+    pub prefab: String,
+    pub formation: Option<Formation>,
+    /// Where the faller (or formation leader) will appear.
+    pub spawn_at: Vec2,
+    pub remaining: Duration,
+}
+
+fn warning_glyph() -> Glyph {
+    Glyph {
+        fg1: RED,
+        fg2: ORANGE,
+        outline: ORANGE,
+        ..Glyph::new(22, 10.)
+    }
+}
+
+/// Flashes a warning above `spawn_at`, and over every column a formation
+/// will fill, then spawns the fallers once [`TELEGRAPH_TIME`] is up.
+pub fn telegraph(cmds: &mut Commands, prefab: &str, formation: Option<Formation>, spawn_at: Vec2) {
+    let warning = cmds
+        .spawn((
+            Telegraph {
+                prefab: prefab.to_string(),
+                formation,
+                spawn_at,
+                remaining: TELEGRAPH_TIME,
+            },
+            warning_glyph(),
+            Position(vec2(spawn_at.x, TELEGRAPH_Y)),
+            DespawnOnExit(GameState::Playing),
+        ))
+        .id();
+
+    let Some(formation) = formation else {
+        return;
+    };
+
+    let mut columns: Vec<f32> = (0..formation.count())
+        .map(|i| formation.slot(i).x)
+        .filter(|x| *x != 0.)
+        .collect();
+    columns.dedup();
+
+    for x in columns {
+        cmds.spawn((
+            warning_glyph(),
+            Position(vec2(spawn_at.x + x, TELEGRAPH_Y)),
+            DespawnOnExit(GameState::Playing),
+        ))
+        .set_parent(warning, vec2(x, 0.));
+    }
+}
+
+/// Blinks warnings and spawns what they promised when they expire. The
+/// warning's children go with it.
+pub fn hatch_telegraphs(
+    mut spawner: PrefabSpawner,
+    mut q_telegraphs: Query<(Entity, &mut Telegraph, &mut Glyph)>,
+    time: Res<Time>,
+) {
+    let dt = Duration::from_secs_f32(time.fixed_dt);
+    let visible = (time.tick / 4).is_multiple_of(2);
+
+    for (entity, mut telegraph, mut glyph) in q_telegraphs.iter_mut() {
+        telegraph.remaining = telegraph.remaining.saturating_sub(dt);
+        glyph.fg1.a = if visible { 1. } else { 0.2 };
+
+        if !telegraph.remaining.is_zero() {
+            continue;
+        }
+
+        match telegraph.formation {
+            Some(formation) => {
+                spawner.spawn_formation(&telegraph.prefab, formation, telegraph.spawn_at)
+            }
+            None => spawner.spawn_prefab(&telegraph.prefab, telegraph.spawn_at),
+        };
+
+        spawner.cmds.entity(entity).despawn();
+    }
+}
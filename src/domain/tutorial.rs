@@ -0,0 +1,172 @@
+//! A scripted first run. [`GameState::Tutorial`] sits underneath
+//! [`GameState::Playing`] so every gameplay system runs as normal; the
+//! director is switched off and [`update_tutorial`] places targets instead,
+//! only moving on once each prompt has been followed. Nothing in it counts
+//! towards achievements, and losing the last life refills them rather than
+//! ending the run.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Bombed, Bombs, DeathEvent, Player, PrefabSpawner, Velocity};
+use crate::engine::{Action, Actions, CurrentState, GameState, Screen, Time};
+use crate::t;
+
+/// Distance the player has to fly before shooting is introduced.
+const MOVE_DISTANCE: f32 = 400.;
+
+const SHOOT_TARGETS: u32 = 3;
+const BOMB_TARGETS: u32 = 8;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TutorialStep {
+    #[default]
+    Move,
+    Shoot,
+    Bomb,
+    Done,
+}
+
+impl TutorialStep {
+    pub fn prompt(&self) -> String {
+        let key = match self {
+            TutorialStep::Move => "tutorial.move",
+            TutorialStep::Shoot => "tutorial.shoot",
+            TutorialStep::Bomb => "tutorial.bomb",
+            TutorialStep::Done => "tutorial.done",
+        };
+
+        t!(key)
+    }
+}
+
+/// Where the script is up to.
+#[derive(Resource, Default, Debug)]
+pub struct Tutorial {
+    pub step: TutorialStep,
+    /// Distance flown or targets destroyed, depending on the step.
+    pub progress: f32,
+    /// Whether this step's targets have been placed.
+    spawned: bool,
+}
+
+impl Tutorial {
+    /// How far through the current step the player is, e.g. `1/3`.
+    pub fn goal(&self) -> Option<String> {
+        match self.step {
+            TutorialStep::Move => Some(format!(
+                "{:.0}%",
+                (self.progress / MOVE_DISTANCE * 100.).min(100.)
+            )),
+            TutorialStep::Shoot => Some(format!("{}/{}", self.progress, SHOOT_TARGETS)),
+            TutorialStep::Bomb | TutorialStep::Done => None,
+        }
+    }
+
+    fn advance(&mut self, step: TutorialStep) {
+        *self = Tutorial {
+            step,
+            ..Tutorial::default()
+        };
+    }
+}
+
+/// A stationary faller placed by the script.
+#[derive(Component)]
+pub struct TutorialTarget;
+
+pub fn in_tutorial(state: Res<CurrentState>) -> bool {
+    state.contains(GameState::Tutorial)
+}
+
+/// Starts the script and the run it plays out in.
+pub fn start_tutorial(mut tutorial: ResMut<Tutorial>, mut state: ResMut<CurrentState>) {
+    *tutorial = Tutorial::default();
+    state.push(GameState::Playing);
+}
+
+fn place_targets(spawner: &mut PrefabSpawner, positions: impl IntoIterator<Item = Vec2>) {
+    for position in positions {
+        if let Some(target) = spawner.spawn_prefab("faller", position) {
+            spawner
+                .cmds
+                .entity(target)
+                .insert((TutorialTarget, Velocity::default()));
+        }
+    }
+}
+
+pub fn update_tutorial(
+    mut spawner: PrefabSpawner,
+    mut tutorial: ResMut<Tutorial>,
+    mut bombs: ResMut<Bombs>,
+    mut ev_death: EventReader<DeathEvent>,
+    q_targets: Query<Has<Bombed>, With<TutorialTarget>>,
+    q_player: Option<Single<&Velocity, With<Player>>>,
+    time: Res<Time>,
+    screen: Res<Screen>,
+) {
+    let width = screen.width as f32;
+    let deaths: Vec<bool> = ev_death
+        .read()
+        .filter_map(|ev| q_targets.get(ev.entity).ok())
+        .collect();
+    // Targets can also be lost by crashing into them, so rather than count
+    // kills, check whether any are left to place a fresh set.
+    let needs_targets = !tutorial.spawned || q_targets.iter().len() == deaths.len();
+
+    match tutorial.step {
+        TutorialStep::Move => {
+            if let Some(velocity) = q_player {
+                tutorial.progress += velocity.0.length() * time.fixed_dt;
+            }
+
+            if tutorial.progress >= MOVE_DISTANCE {
+                tutorial.advance(TutorialStep::Shoot);
+            }
+        }
+        TutorialStep::Shoot => {
+            tutorial.progress += deaths.len() as f32;
+
+            if tutorial.progress >= SHOOT_TARGETS as f32 {
+                tutorial.advance(TutorialStep::Bomb);
+            } else if needs_targets {
+                tutorial.spawned = true;
+                place_targets(
+                    &mut spawner,
+                    (0..SHOOT_TARGETS)
+                        .map(|i| vec2(width * (i + 1) as f32 / (SHOOT_TARGETS + 1) as f32, 80.)),
+                );
+            }
+        }
+        TutorialStep::Bomb => {
+            if deaths.iter().any(|&bombed| bombed) {
+                tutorial.advance(TutorialStep::Done);
+            } else if needs_targets {
+                // Bombs spent earlier in the tutorial shouldn't leave the
+                // player stuck here.
+                bombs.0 = bombs.0.max(1);
+                tutorial.spawned = true;
+                place_targets(
+                    &mut spawner,
+                    (0..BOMB_TARGETS).map(|i| {
+                        let column = (i % 4) as f32 - 1.5;
+                        let row = (i / 4) as f32;
+                        vec2(width / 2. + column * 48., 60. + row * 48.)
+                    }),
+                );
+            }
+        }
+        TutorialStep::Done => {}
+    }
+}
+
+pub fn finish_tutorial(
+    mut actions: ResMut<Actions>,
+    tutorial: Res<Tutorial>,
+    mut state: ResMut<CurrentState>,
+) {
+    if tutorial.step == TutorialStep::Done && actions.consume(Action::Confirm) {
+        state.set(GameState::MainMenu);
+    }
+}
@@ -0,0 +1,61 @@
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Boss, Director};
+use crate::engine::Time;
+
+/// Seconds of regular fallers in each wave, unless the [`Director`] says
+/// otherwise.
+pub const WAVE_DURATION: f32 = 30.;
+
+/// A boss appears at the start of every wave divisible by this.
+pub const BOSS_EVERY: u32 = 3;
+
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Waves {
+    /// The current wave, starting at 1.
+    pub number: u32,
+    /// Seconds spent in the current wave.
+    pub elapsed: f32,
+    /// A boss should be spawned for the current wave.
+    pub boss_pending: bool,
+}
+
+impl Default for Waves {
+    fn default() -> Self {
+        Self {
+            number: 1,
+            elapsed: 0.,
+            boss_pending: false,
+        }
+    }
+}
+
+impl Waves {
+    /// Moves on to the next wave, queueing a boss if the [`Director`] says
+    /// it is a boss wave.
+    pub fn advance(&mut self, director: &Director) {
+        self.number += 1;
+        self.elapsed = 0.;
+        self.boss_pending = director.is_boss_wave(self.number);
+    }
+}
+
+pub fn reset_waves(mut waves: ResMut<Waves>) {
+    *waves = Waves::default();
+}
+
+/// True while no boss is alive or waiting to spawn.
+pub fn boss_absent(waves: Res<Waves>, q_bosses: Query<(), With<Boss>>) -> bool {
+    !waves.boss_pending && q_bosses.is_empty()
+}
+
+/// Advances to the next wave on a timer. Boss waves instead end when the
+/// boss is defeated.
+pub fn advance_waves(mut waves: ResMut<Waves>, director: Res<Director>, time: Res<Time>) {
+    waves.elapsed += time.fixed_dt;
+
+    if waves.elapsed >= director.wave_duration {
+        waves.advance(&director);
+    }
+}
@@ -0,0 +1,308 @@
+use std::{
+    f32::consts::{PI, TAU},
+    time::Duration,
+};
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Damage, Faller, Player, Position, PrefabSpawner, Profile, Shoot, Upgrades, Velocity, nearest,
+};
+use crate::engine::{Action, Actions, Cooldown, GestureEvent, Gestures, MouseInput, Time, cycle};
+use crate::t;
+
+/// Angle between neighbouring shots of the spread gun, in radians.
+const SPREAD_ANGLE: f32 = 0.26;
+
+/// Seconds the trigger must be held before a charge shot will fire.
+const MIN_CHARGE: f32 = 0.3;
+
+/// Seconds of holding that give the strongest charge shot.
+pub const MAX_CHARGE: f32 = 1.5;
+
+/// Damage of a fully charged shot. A minimal charge deals 2.
+const MAX_CHARGE_DAMAGE: u32 = 8;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WeaponKind {
+    /// Steady single shots.
+    #[default]
+    Blaster,
+    /// Weak shots at a much higher rate.
+    Rapid,
+    /// Three shots fanned out.
+    Spread,
+    /// Hold to charge, release to fire a large piercing shot.
+    Charge,
+    /// Slow shots that steer toward the nearest faller.
+    Missile,
+}
+
+impl WeaponKind {
+    /// In the order Q and E cycle through them.
+    pub const ALL: [WeaponKind; 5] = [
+        WeaponKind::Blaster,
+        WeaponKind::Rapid,
+        WeaponKind::Spread,
+        WeaponKind::Charge,
+        WeaponKind::Missile,
+    ];
+
+    pub fn name(&self) -> String {
+        let key = match self {
+            WeaponKind::Blaster => "weapon.blaster",
+            WeaponKind::Rapid => "weapon.rapid",
+            WeaponKind::Spread => "weapon.spread",
+            WeaponKind::Charge => "weapon.charge",
+            WeaponKind::Missile => "weapon.missile",
+        };
+
+        t!(key)
+    }
+
+    /// Shots per second while the trigger is held.
+    pub fn fire_rate(&self) -> f32 {
+        match self {
+            WeaponKind::Blaster => 6.,
+            WeaponKind::Rapid => 14.,
+            WeaponKind::Spread => 4.,
+            WeaponKind::Charge => 3.,
+            WeaponKind::Missile => 2.5,
+        }
+    }
+
+    /// The weapon `offset` steps away from this one, wrapping around.
+    pub fn cycle(&self, offset: isize) -> Self {
+        cycle(&Self::ALL, self, offset)
+    }
+
+    /// Prefab spawned for each shot.
+    pub fn projectile(&self) -> &'static str {
+        match self {
+            WeaponKind::Blaster | WeaponKind::Spread => "bullet",
+            WeaponKind::Rapid => "bullet_rapid",
+            WeaponKind::Charge => "bullet_charged",
+            WeaponKind::Missile => "missile",
+        }
+    }
+}
+
+/// Which way the player's shots go.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AimMode {
+    /// Straight up the screen.
+    #[default]
+    Forward,
+    /// Toward the mouse cursor.
+    Mouse,
+}
+
+impl AimMode {
+    pub fn name(&self) -> String {
+        let key = match self {
+            AimMode::Forward => "aim.forward",
+            AimMode::Mouse => "aim.mouse",
+        };
+
+        t!(key)
+    }
+
+    pub fn toggle(&self) -> Self {
+        match self {
+            AimMode::Forward => AimMode::Mouse,
+            AimMode::Mouse => AimMode::Forward,
+        }
+    }
+
+    /// Direction to fire in from `position`, or `None` to leave it to the
+    /// projectile's prefab.
+    fn aim(&self, position: Vec2, mouse: &MouseInput) -> Option<Vec2> {
+        match self {
+            AimMode::Forward => None,
+            AimMode::Mouse => Some((mouse.position - position).normalize_or(Vec2::NEG_Y)),
+        }
+    }
+}
+
+/// The player's gun. Firing is rate limited by the `Cooldown<Shoot>` that
+/// [`Weapon::bundle`] pairs with it.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+}
+
+/// Steers toward the nearest [`Faller`], turning at most `turn_rate` radians
+/// per second. Speed is left unchanged.
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug)]
+pub struct Homing {
+    pub turn_rate: f32,
+}
+
+/// Seconds the trigger has been held for the charge gun.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct ChargeShot(pub f32);
+
+impl Weapon {
+    pub fn bundle(kind: WeaponKind) -> (Weapon, Cooldown<Shoot>, ChargeShot) {
+        (
+            Weapon { kind },
+            Cooldown::new(Self::interval(kind)),
+            ChargeShot::default(),
+        )
+    }
+
+    /// Time between shots.
+    fn interval(kind: WeaponKind) -> Duration {
+        Duration::from_secs_f32(1. / kind.fire_rate())
+    }
+}
+
+/// Switches to the next weapon the [`Profile`] has unlocked. Whatever is
+/// left of the cooldown carries over, so switching doesn't fire faster.
+pub fn cycle_weapon(
+    mut cmds: Commands,
+    actions: Res<Actions>,
+    profile: Res<Profile>,
+    mut q_player: Single<(Entity, &Weapon, &mut Cooldown<Shoot>), With<Player>>,
+) {
+    let previous = actions.is_pressed(Action::PrevWeapon);
+    let next = actions.is_pressed(Action::NextWeapon);
+
+    let offset = match (previous, next) {
+        (true, false) => -1,
+        (false, true) => 1,
+        _ => return,
+    };
+
+    let (player, weapon, cooldown) = &mut *q_player;
+    let mut kind = weapon.kind.cycle(offset);
+
+    while !profile.has_weapon(kind) {
+        kind = kind.cycle(offset);
+    }
+
+    cooldown.duration = Weapon::interval(kind);
+    cmds.entity(*player)
+        .insert((Weapon { kind }, ChargeShot::default()));
+}
+
+/// Sent each time the player fires, once however many shots it spreads
+/// into.
+#[derive(Event, Clone, Copy, Debug)]
+pub struct FireEvent {
+    /// Bullets fired.
+    pub shots: u32,
+}
+
+pub fn player_fire(
+    mut spawner: PrefabSpawner,
+    mut actions: ResMut<Actions>,
+    aim_mode: Res<AimMode>,
+    mouse: Res<MouseInput>,
+    gestures: Res<Gestures>,
+    mut ev_gesture: EventReader<GestureEvent>,
+    mut ev_fire: EventWriter<FireEvent>,
+    q_player: Single<
+        (
+            &Position,
+            &Weapon,
+            &Upgrades,
+            &mut Cooldown<Shoot>,
+            &mut ChargeShot,
+        ),
+        With<Player>,
+    >,
+) {
+    let (position, weapon, upgrades, mut cooldown, mut charge) = q_player.into_inner();
+    let position = position.0;
+    let aim = aim_mode.aim(position, &mouse);
+
+    cooldown.duration =
+        Duration::from_secs_f32(1. / (weapon.kind.fire_rate() * upgrades.fire_rate_multiplier()));
+
+    // Read whatever the weapon, so a release from before switching to the
+    // charge gun isn't picked up later.
+    let released = ev_gesture
+        .read()
+        .filter_map(|ev| match *ev {
+            GestureEvent::HoldReleased {
+                action: Action::Fire,
+                seconds,
+            } => Some(seconds),
+            _ => None,
+        })
+        .last();
+
+    if weapon.kind == WeaponKind::Charge {
+        charge.0 = gestures.held_for(Action::Fire).min(MAX_CHARGE);
+
+        let Some(held) = released.map(|seconds| seconds.min(MAX_CHARGE)) else {
+            return;
+        };
+
+        if held >= MIN_CHARGE
+            && cooldown.trigger()
+            && let Some(shot) = spawner.spawn_prefab_toward(weapon.kind.projectile(), position, aim)
+        {
+            let t = (held - MIN_CHARGE) / (MAX_CHARGE - MIN_CHARGE);
+            let damage = 2 + (t * (MAX_CHARGE_DAMAGE - 2) as f32).round() as u32;
+            spawner.cmds.entity(shot).insert(Damage(damage));
+            ev_fire.send(FireEvent { shots: 1 });
+        }
+
+        return;
+    }
+
+    // A tap that lands during the cooldown still gets its shot once the
+    // cooldown is up.
+    let wants_fire = actions.is_down(Action::Fire) || actions.is_buffered(Action::Fire);
+
+    if !wants_fire || !cooldown.trigger() {
+        return;
+    }
+
+    actions.consume(Action::Fire);
+
+    match weapon.kind {
+        WeaponKind::Spread => {
+            let angles = [-SPREAD_ANGLE, 0., SPREAD_ANGLE];
+
+            for angle in angles {
+                let direction = Vec2::from_angle(angle).rotate(aim.unwrap_or(Vec2::NEG_Y));
+                spawner.spawn_prefab_aimed(weapon.kind.projectile(), position, direction);
+            }
+
+            ev_fire.send(FireEvent {
+                shots: angles.len() as u32,
+            });
+        }
+        _ => {
+            spawner.spawn_prefab_toward(weapon.kind.projectile(), position, aim);
+            ev_fire.send(FireEvent { shots: 1 });
+        }
+    }
+}
+
+pub fn steer_homing(
+    mut q_homing: Query<(&Homing, &Position, &mut Velocity)>,
+    q_fallers: Query<(Entity, &Position), With<Faller>>,
+    time: Res<Time>,
+) {
+    for (homing, position, mut velocity) in q_homing.iter_mut() {
+        let Some((_, target)) = nearest(position.0, q_fallers.iter()) else {
+            continue;
+        };
+
+        let wanted = (target - position.0).to_angle();
+        let current = velocity.0.to_angle();
+        let max_turn = homing.turn_rate * time.fixed_dt;
+
+        // Shortest signed turn from the current heading to the target.
+        let delta = (wanted - current + PI).rem_euclid(TAU) - PI;
+        let turn = delta.clamp(-max_turn, max_turn);
+
+        velocity.0 = Vec2::from_angle(turn).rotate(velocity.0);
+    }
+}
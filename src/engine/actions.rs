@@ -0,0 +1,428 @@
+//! What the player wants to do, independent of the device. Every frame the
+//! [`Bindings`] are checked against the keyboard, mouse and gamepad to fill
+//! in [`Actions`], and gameplay systems only ever look at actions.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    Gamepad, GamepadButton, InputOverride, KeyInput, MouseInput, Time, TouchInput, key_code,
+    key_name,
+};
+use crate::t;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Action {
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    Fire,
+    Dash,
+    Bomb,
+    BulletTime,
+    PrevWeapon,
+    NextWeapon,
+    Pause,
+    /// Mutes or unmutes all sound, from anywhere.
+    Mute,
+    /// Accepts the highlighted option in a menu.
+    Confirm,
+    /// Leaves a menu.
+    Back,
+}
+
+impl Action {
+    pub const ALL: [Action; 14] = [
+        Action::MoveUp,
+        Action::MoveDown,
+        Action::MoveLeft,
+        Action::MoveRight,
+        Action::Fire,
+        Action::Dash,
+        Action::Bomb,
+        Action::BulletTime,
+        Action::PrevWeapon,
+        Action::NextWeapon,
+        Action::Pause,
+        Action::Mute,
+        Action::Confirm,
+        Action::Back,
+    ];
+
+    pub fn name(&self) -> String {
+        let key = match self {
+            Action::MoveUp => "action.move_up",
+            Action::MoveDown => "action.move_down",
+            Action::MoveLeft => "action.move_left",
+            Action::MoveRight => "action.move_right",
+            Action::Fire => "action.fire",
+            Action::Dash => "action.dash",
+            Action::Bomb => "action.bomb",
+            Action::BulletTime => "action.bullet_time",
+            Action::PrevWeapon => "action.prev_weapon",
+            Action::NextWeapon => "action.next_weapon",
+            Action::Pause => "action.pause",
+            Action::Mute => "action.mute",
+            Action::Confirm => "action.confirm",
+            Action::Back => "action.back",
+        };
+
+        t!(key)
+    }
+
+    /// Menu actions are never checked during play, so they may share inputs
+    /// with gameplay actions without conflicting.
+    pub fn is_menu(&self) -> bool {
+        matches!(self, Action::Confirm | Action::Back)
+    }
+
+    fn default_bindings(&self) -> Vec<Binding> {
+        use Binding::*;
+
+        match self {
+            Action::MoveUp => vec![Key(KeyCode::W), Key(KeyCode::Up)],
+            Action::MoveDown => vec![Key(KeyCode::S), Key(KeyCode::Down)],
+            Action::MoveLeft => vec![Key(KeyCode::A), Key(KeyCode::Left)],
+            Action::MoveRight => vec![Key(KeyCode::D), Key(KeyCode::Right)],
+            Action::Fire => vec![
+                Key(KeyCode::Space),
+                Mouse(MouseButton::Left),
+                Gamepad(GamepadButton::South),
+            ],
+            Action::Dash => vec![
+                Key(KeyCode::LeftShift),
+                Key(KeyCode::RightShift),
+                Gamepad(GamepadButton::West),
+            ],
+            Action::Bomb => vec![Key(KeyCode::B), Gamepad(GamepadButton::East)],
+            Action::BulletTime => vec![Key(KeyCode::X), Gamepad(GamepadButton::North)],
+            Action::PrevWeapon => vec![Key(KeyCode::Q), Gamepad(GamepadButton::LeftShoulder)],
+            Action::NextWeapon => vec![Key(KeyCode::E), Gamepad(GamepadButton::RightShoulder)],
+            Action::Pause => vec![Key(KeyCode::Escape), Gamepad(GamepadButton::Start)],
+            Action::Mute => vec![Key(KeyCode::M)],
+            Action::Confirm => vec![
+                Key(KeyCode::Enter),
+                Key(KeyCode::Space),
+                Mouse(MouseButton::Left),
+                Gamepad(GamepadButton::South),
+                Gamepad(GamepadButton::Start),
+            ],
+            Action::Back => vec![Key(KeyCode::Escape), Gamepad(GamepadButton::East)],
+        }
+    }
+}
+
+/// Each movement action and the direction it moves in.
+pub const MOVE_DIRECTIONS: [(Action, Vec2); 4] = [
+    (Action::MoveUp, Vec2::NEG_Y),
+    (Action::MoveLeft, Vec2::NEG_X),
+    (Action::MoveDown, Vec2::Y),
+    (Action::MoveRight, Vec2::X),
+];
+
+/// How far a stick has to be pushed in a direction to hold that movement
+/// action, so sticks can also step through menus.
+const STICK_PRESS: f32 = 0.5;
+
+/// A single key or button that can trigger an [`Action`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Binding {
+    Key(#[serde(with = "key_code")] KeyCode),
+    Mouse(#[serde(with = "mouse_button")] MouseButton),
+    Gamepad(GamepadButton),
+}
+
+impl Binding {
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => key_name(*key).to_uppercase(),
+            Binding::Mouse(button) => format!("MOUSE {}", mouse_button::name(*button)),
+            Binding::Gamepad(button) => format!("PAD {button:?}").to_uppercase(),
+        }
+    }
+
+    fn same_device(&self, other: &Binding) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+/// `MouseButton` has no serde support either, see [`key_code`].
+mod mouse_button {
+    use macroquad::prelude::MouseButton;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de::Error};
+
+    pub fn name(button: MouseButton) -> &'static str {
+        match button {
+            MouseButton::Left => "LEFT",
+            MouseButton::Middle => "MIDDLE",
+            MouseButton::Right => "RIGHT",
+            MouseButton::Unknown => "UNKNOWN",
+        }
+    }
+
+    pub fn serialize<S: Serializer>(
+        button: &MouseButton,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        name(*button).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<MouseButton, D::Error> {
+        let name = String::deserialize(deserializer)?;
+
+        [MouseButton::Left, MouseButton::Middle, MouseButton::Right]
+            .into_iter()
+            .find(|&button| self::name(button) == name)
+            .ok_or_else(|| D::Error::custom(format!("unknown mouse button {name}")))
+    }
+}
+
+/// The inputs that trigger each action. An action can have any number of
+/// bindings, and is down while any of them is.
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+pub struct Bindings(pub BTreeMap<Action, Vec<Binding>>);
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self(
+            Action::ALL
+                .into_iter()
+                .map(|action| (action, action.default_bindings()))
+                .collect(),
+        )
+    }
+}
+
+impl Bindings {
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Gives actions missing from e.g. an older settings file their default
+    /// bindings.
+    pub fn fill_defaults(&mut self) {
+        for action in Action::ALL {
+            self.0
+                .entry(action)
+                .or_insert_with(|| action.default_bindings());
+        }
+    }
+
+    /// The other action `binding` would clash with if bound to `action`.
+    pub fn conflict(&self, action: Action, binding: Binding) -> Option<Action> {
+        Action::ALL.into_iter().find(|&other| {
+            other != action
+                && other.is_menu() == action.is_menu()
+                && self.get(other).contains(&binding)
+        })
+    }
+
+    /// Makes `binding` the only binding of its device for `action`. If
+    /// another action already used it, the two swap so that one is not left
+    /// without a binding; that action is returned.
+    pub fn rebind(&mut self, action: Action, binding: Binding) -> Option<Action> {
+        let conflict = self.conflict(action, binding);
+        let previous: Vec<Binding> = self
+            .get(action)
+            .iter()
+            .filter(|b| b.same_device(&binding))
+            .copied()
+            .collect();
+
+        let bound = self.0.entry(action).or_default();
+        bound.retain(|b| !b.same_device(&binding));
+        bound.insert(0, binding);
+
+        if let Some(other) = conflict {
+            let bound = self.0.entry(other).or_default();
+            bound.retain(|b| *b != binding);
+
+            for b in previous {
+                if !bound.contains(&b) {
+                    bound.push(b);
+                }
+            }
+        }
+
+        conflict
+    }
+}
+
+/// How long, in milliseconds, a press is remembered for
+/// [`Actions::consume`]. Saved in the settings file.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputBuffer(pub u32);
+
+impl Default for InputBuffer {
+    fn default() -> Self {
+        Self(100)
+    }
+}
+
+impl InputBuffer {
+    pub const MAX: u32 = 250;
+    pub const STEP: u32 = 25;
+
+    fn seconds(&self) -> f32 {
+        self.0 as f32 / 1000.
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Actions {
+    pub down: HashSet<Action>,
+    pub pressed: HashSet<Action>,
+    pub released: HashSet<Action>,
+    /// Analog movement, e.g. from a gamepad stick. Zero when centered.
+    pub stick: Vec2,
+    /// Seconds since each recent press that hasn't been consumed yet.
+    buffered: HashMap<Action, f32>,
+}
+
+impl Actions {
+    pub fn is_down(&self, action: Action) -> bool {
+        self.down.contains(&action)
+    }
+
+    pub fn is_pressed(&self, action: Action) -> bool {
+        self.pressed.contains(&action)
+    }
+
+    pub fn is_released(&self, action: Action) -> bool {
+        self.released.contains(&action)
+    }
+
+    /// Whether `action` was pressed within the [`InputBuffer`] window and
+    /// not consumed since. A press that lands on a frame where it can't be
+    /// acted on, e.g. during a cooldown or a state change, is kept this way.
+    pub fn is_buffered(&self, action: Action) -> bool {
+        self.buffered.contains_key(&action)
+    }
+
+    /// Takes a buffered press of `action`, so it only ever triggers once.
+    pub fn consume(&mut self, action: Action) -> bool {
+        self.buffered.remove(&action).is_some()
+    }
+
+    /// Direction to move in: the stick if it is pushed, otherwise the sum of
+    /// the held movement actions.
+    pub fn movement(&self) -> Vec2 {
+        if self.stick != Vec2::ZERO {
+            return self.stick;
+        }
+
+        MOVE_DIRECTIONS
+            .into_iter()
+            .filter(|(action, _)| self.is_down(*action))
+            .map(|(_, direction)| direction)
+            .sum()
+    }
+}
+
+pub fn update_actions(
+    keys: Res<KeyInput>,
+    mouse: Res<MouseInput>,
+    gamepad: Res<Gamepad>,
+    touch: Res<TouchInput>,
+    bindings: Res<Bindings>,
+    buffer: Res<InputBuffer>,
+    time: Res<Time>,
+    input_override: Option<Res<InputOverride>>,
+    mut actions: ResMut<Actions>,
+) {
+    match input_override {
+        Some(replayed) => replayed.0.apply(&mut actions),
+        None => read_devices(&mut actions, &keys, &mouse, &gamepad, &touch, &bindings),
+    }
+
+    // Real time, so slow motion doesn't stretch the window.
+    let window = buffer.seconds();
+    actions.buffered.retain(|_, age| {
+        *age += time.unscaled_dt;
+        *age <= window
+    });
+
+    let actions = &mut *actions;
+    for &action in &actions.pressed {
+        actions.buffered.insert(action, 0.);
+    }
+}
+
+fn read_devices(
+    actions: &mut Actions,
+    keys: &KeyInput,
+    mouse: &MouseInput,
+    gamepad: &Gamepad,
+    touch: &TouchInput,
+    bindings: &Bindings,
+) {
+    let down = |binding: &Binding| match *binding {
+        Binding::Key(key) => keys.is_down(key),
+        Binding::Mouse(button) => mouse.is_down(button),
+        Binding::Gamepad(button) => gamepad.down.contains(&button),
+    };
+    let pressed = |binding: &Binding| match *binding {
+        Binding::Key(key) => keys.is_pressed(key),
+        Binding::Mouse(button) => mouse.is_pressed(button),
+        Binding::Gamepad(button) => gamepad.pressed.contains(&button),
+    };
+    let released = |binding: &Binding| match *binding {
+        Binding::Key(key) => keys.is_released(key),
+        Binding::Mouse(button) => mouse.is_released(button),
+        Binding::Gamepad(button) => gamepad.released.contains(&button),
+    };
+
+    let previous = std::mem::take(&mut actions.down);
+    actions.pressed.clear();
+    actions.released.clear();
+    actions.stick = if gamepad.stick != Vec2::ZERO {
+        gamepad.stick
+    } else {
+        touch.stick
+    };
+
+    // Held without a binding: by a touch button, or by pushing a stick far
+    // enough. These only have a down state, so presses and releases come
+    // from comparing with last frame.
+    let mut held: HashSet<Action> = touch.down.iter().flat_map(|b| b.actions()).collect();
+
+    for (action, direction) in MOVE_DIRECTIONS {
+        if actions.stick.dot(direction) > STICK_PRESS {
+            held.insert(action);
+        }
+    }
+
+    for action in Action::ALL {
+        let bound = bindings.get(action);
+        let was_down = previous.contains(&action);
+
+        if held.contains(&action) || bound.iter().any(down) {
+            actions.down.insert(action);
+        }
+
+        if bound.iter().any(pressed) || (actions.is_down(action) && !was_down) {
+            actions.pressed.insert(action);
+        }
+
+        // Letting go of one binding while another is still held isn't a
+        // release of the action.
+        if (bound.iter().any(released) || was_down) && !actions.is_down(action) {
+            actions.released.insert(action);
+        }
+    }
+
+    // The wheel has no held state, so it can't be bound like a button.
+    if mouse.wheel.y > 0. {
+        actions.pressed.insert(Action::PrevWeapon);
+    }
+
+    if mouse.wheel.y < 0. {
+        actions.pressed.insert(Action::NextWeapon);
+    }
+}
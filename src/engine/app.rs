@@ -0,0 +1,213 @@
+use std::future::Future;
+
+use super::{
+    AppExit, Assets, CommandResult, ConsoleCommand, ConsoleCommands, CurrentState, Deterministic,
+    First, FrameCap, LoadState, Plugin, PostUpdate, PreUpdate, Render, Startup, StateExited,
+    StateTransition, Time, Update, apply_state_transitions, despawn_on_exit, enter_initial_state,
+    install_profiler, new_schedule, record_frame, run_fixed_update, run_single_threaded,
+    run_state_update, scoped,
+};
+use bevy_ecs::{
+    event::{EventRegistry, ShouldUpdateEvents, event_update_condition, event_update_system},
+    prelude::*,
+    schedule::{ScheduleLabel, Schedules},
+};
+
+/// Checks on one background load, applying its result and returning `true`
+/// once it has finished.
+type Load = Box<dyn FnMut(&mut World) -> bool>;
+
+/// How many times [`App::update`] runs for each render, normally one.
+/// Raised to fast forward through a replay.
+#[derive(Resource)]
+pub struct UpdatesPerFrame(pub u32);
+
+impl Default for UpdatesPerFrame {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+/// Owns the ECS world and the schedules registered against it.
+pub struct App {
+    pub world: World,
+    loads: Vec<Load>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl App {
+    pub fn new() -> Self {
+        install_profiler();
+
+        let mut world = World::new();
+
+        world.init_resource::<Schedules>();
+        world.init_resource::<CurrentState>();
+        world.init_resource::<Time>();
+        world.init_resource::<Assets>();
+        world.init_resource::<UpdatesPerFrame>();
+        world.init_resource::<ConsoleCommands>();
+
+        // Events are only swapped once a fixed tick has had a chance to read
+        // them, so nothing sent between ticks is dropped at high frame rates.
+        world.init_resource::<EventRegistry>();
+        world.resource_mut::<EventRegistry>().should_update = ShouldUpdateEvents::Waiting;
+
+        let mut app = Self {
+            world,
+            loads: Vec::new(),
+        };
+        app.add_event::<StateExited>()
+            .add_systems(First, event_update_system.run_if(event_update_condition))
+            .add_systems(StateTransition, despawn_on_exit);
+        app
+    }
+
+    pub fn init_resource<R: Resource + FromWorld>(&mut self) -> &mut Self {
+        self.world.init_resource::<R>();
+        self
+    }
+
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> &mut Self {
+        self.world.insert_resource(resource);
+        self
+    }
+
+    pub fn add_event<E: Event>(&mut self) -> &mut Self {
+        EventRegistry::register_event::<E>(&mut self.world);
+        self
+    }
+
+    pub fn add_plugins(&mut self, plugin: impl Plugin) -> &mut Self {
+        plugin.build(self);
+        self
+    }
+
+    /// Adds `name` to the [`Console`](super::Console)'s commands. `usage`
+    /// lists its arguments for `help`, and `run` gets the rest of the line
+    /// split into words.
+    pub fn add_console_command(
+        &mut self,
+        name: &'static str,
+        usage: &'static str,
+        help: &'static str,
+        run: fn(&mut World, &[&str]) -> CommandResult,
+    ) -> &mut Self {
+        self.world
+            .resource_mut::<ConsoleCommands>()
+            .insert(name, ConsoleCommand { usage, help, run });
+        self
+    }
+
+    /// Loads `future` into [`Assets`] under `name` and hands its output to
+    /// `apply` on the first update after it resolves, e.g. to insert it as a
+    /// resource. A failed load is never applied.
+    pub fn load<T, F>(
+        &mut self,
+        name: &str,
+        future: F,
+        apply: impl FnOnce(&mut World, T) + 'static,
+    ) -> &mut Self
+    where
+        T: Send + Sync + 'static,
+        F: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let handle = self.world.resource_mut::<Assets>().load(name, future);
+        let mut apply = Some(apply);
+
+        self.loads.push(Box::new(move |world| {
+            match world.resource::<Assets>().state(handle) {
+                LoadState::Loading => return false,
+                LoadState::Failed(_) => return true,
+                LoadState::Loaded => {}
+            }
+
+            let output = world.resource_mut::<Assets>().take(handle);
+
+            if let Some(output) = output
+                && let Some(apply) = apply.take()
+            {
+                apply(world, output);
+            }
+
+            true
+        }));
+        self
+    }
+
+    pub fn add_systems<M>(
+        &mut self,
+        schedule: impl ScheduleLabel,
+        systems: impl IntoSystemConfigs<M>,
+    ) -> &mut Self {
+        let label = schedule.intern();
+        let mut schedules = self.world.resource_mut::<Schedules>();
+
+        if !schedules.contains(label) {
+            schedules.insert(new_schedule(label));
+        }
+
+        schedules.add_systems(label, systems);
+        self
+    }
+
+    /// Runs [`Startup`] and enters the initial state. Call once before the
+    /// first [`App::update`].
+    pub fn startup(&mut self) {
+        if self.world.contains_resource::<Deterministic>() {
+            run_single_threaded(&mut self.world);
+        }
+
+        let _ = self.world.try_run_schedule(Startup);
+        enter_initial_state(&mut self.world);
+    }
+
+    fn poll_loads(&mut self) {
+        let world = &mut self.world;
+        world.resource_mut::<Assets>().poll();
+        self.loads.retain_mut(|load| !load(world));
+    }
+
+    pub fn update(&mut self) {
+        self.poll_loads();
+        let _ = scoped("First", || self.world.try_run_schedule(First));
+        let _ = scoped("PreUpdate", || self.world.try_run_schedule(PreUpdate));
+        scoped("FixedUpdate", || run_fixed_update(&mut self.world));
+        let _ = scoped("Update", || self.world.try_run_schedule(Update));
+        scoped("OnUpdate", || run_state_update(&mut self.world));
+        let _ = scoped("PostUpdate", || self.world.try_run_schedule(PostUpdate));
+        apply_state_transitions(&mut self.world);
+    }
+
+    pub fn frame_cap(&self) -> FrameCap {
+        self.world
+            .get_resource::<FrameCap>()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn updates_per_frame(&self) -> u32 {
+        self.world.resource::<UpdatesPerFrame>().0
+    }
+
+    /// True once something has set [`AppExit`], e.g. after a
+    /// [`QuitRequested`](super::QuitRequested).
+    pub fn should_exit(&self) -> bool {
+        self.world
+            .get_resource::<AppExit>()
+            .is_some_and(|exit| exit.0)
+    }
+
+    /// Runs the [`Render`] schedule and ends the frame. The caller is
+    /// responsible for binding the camera and render target first.
+    pub fn render(&mut self) {
+        let _ = scoped("Render", || self.world.try_run_schedule(Render));
+        record_frame(&mut self.world);
+        self.world.clear_trackers();
+    }
+}
@@ -0,0 +1,241 @@
+//! Everything read from disk, loaded in the background: textures, sounds,
+//! shader sources and data files. Each load gets a typed [`Handle`] and a
+//! [`LoadState`], and finished assets can be looked up by handle or by the
+//! name they were loaded under, usually their path.
+
+use std::{
+    any::Any,
+    collections::HashMap,
+    future::Future,
+    marker::PhantomData,
+    pin::pin,
+    sync::Mutex,
+    task::{Context, Waker},
+};
+
+use bevy_ecs::prelude::*;
+use macroquad::{experimental::coroutines::start_coroutine, prelude::*};
+
+use super::{read_file, read_string, read_texture};
+
+/// Refers to an asset of type `T` in [`Assets`], whether or not it has
+/// finished loading.
+pub struct Handle<T> {
+    id: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    fn new(id: usize) -> Self {
+        Self {
+            id,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for Handle<T> {}
+
+impl<T> std::fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Handle({})", self.id)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadState {
+    Loading,
+    Loaded,
+    Failed(String),
+}
+
+type Value = Box<dyn Any + Send + Sync>;
+
+/// Checks on a load in flight, returning its result once it's done.
+type Poll = Box<dyn Fn() -> Option<Result<Value, String>> + Send + Sync>;
+
+struct Entry {
+    name: String,
+    state: LoadState,
+    value: Option<Value>,
+    pending: Option<Poll>,
+}
+
+#[derive(Resource, Default)]
+pub struct Assets {
+    entries: Vec<Entry>,
+    names: HashMap<String, usize>,
+    /// Finish every load on the spot rather than in a coroutine, for
+    /// running without a window.
+    blocking: bool,
+}
+
+/// Runs `future` to completion right away. Reading from disk never has to
+/// wait, so only a load that needs a window, e.g. over the network, fails.
+fn block_on<T>(future: impl Future<Output = Result<T, String>>) -> Result<T, String> {
+    let mut context = Context::from_waker(Waker::noop());
+
+    match pin!(future).poll(&mut context) {
+        std::task::Poll::Ready(result) => result,
+        std::task::Poll::Pending => Err("can't finish loading without a window".to_string()),
+    }
+}
+
+impl Assets {
+    /// Assets that load as soon as they're asked for, for running without
+    /// the window macroquad's coroutines need.
+    pub fn blocking() -> Self {
+        Self {
+            blocking: true,
+            ..Default::default()
+        }
+    }
+
+    /// Runs `future` as a macroquad coroutine and keeps its output under
+    /// `name`. Loading a name again keeps the old asset, and its handle,
+    /// until the new one has loaded, then swaps it in.
+    pub fn load<T, F>(&mut self, name: &str, future: F) -> Handle<T>
+    where
+        T: Send + Sync + 'static,
+        F: Future<Output = Result<T, String>> + Send + 'static,
+    {
+        let pending: Poll = if self.blocking {
+            let result = Mutex::new(Some(block_on(future).map(|value| Box::new(value) as Value)));
+            Box::new(move || result.lock().unwrap().take())
+        } else {
+            let coroutine = start_coroutine(future);
+            Box::new(move || {
+                coroutine
+                    .retrieve()
+                    .map(|result| result.map(|value| Box::new(value) as Value))
+            })
+        };
+
+        let id = *self.names.entry(name.to_string()).or_insert_with(|| {
+            self.entries.push(Entry {
+                name: name.to_string(),
+                state: LoadState::Loading,
+                value: None,
+                pending: None,
+            });
+            self.entries.len() - 1
+        });
+
+        let entry = &mut self.entries[id];
+        entry.state = LoadState::Loading;
+        entry.pending = Some(pending);
+
+        Handle::new(id)
+    }
+
+    pub fn load_texture(&mut self, path: &str) -> Handle<Texture2D> {
+        let path = path.to_string();
+
+        self.load(&path.clone(), async move { read_texture(&path).await })
+    }
+
+    pub fn load_string(&mut self, path: &str) -> Handle<String> {
+        let path = path.to_string();
+
+        self.load(&path.clone(), async move { read_string(&path).await })
+    }
+
+    pub fn load_bytes(&mut self, path: &str) -> Handle<Vec<u8>> {
+        let path = path.to_string();
+
+        self.load(&path.clone(), async move { read_file(&path).await })
+    }
+
+    /// Collects every load that has finished since the last call. Run once
+    /// a frame by [`App::update`](super::App::update).
+    pub fn poll(&mut self) {
+        for entry in self.entries.iter_mut() {
+            let Some(result) = entry.pending.as_ref().and_then(|poll| poll()) else {
+                continue;
+            };
+
+            entry.pending = None;
+
+            match result {
+                Ok(value) => {
+                    entry.value = Some(value);
+                    entry.state = LoadState::Loaded;
+                }
+                Err(e) => {
+                    error!("Failed to load {}: {}", entry.name, e);
+                    entry.state = LoadState::Failed(e);
+                }
+            }
+        }
+    }
+
+    pub fn state<T>(&self, handle: Handle<T>) -> &LoadState {
+        &self.entries[handle.id].state
+    }
+
+    /// The asset, once loaded. A reload in progress still gives the old one.
+    pub fn get<T: 'static>(&self, handle: Handle<T>) -> Option<&T> {
+        self.entries[handle.id].value.as_ref()?.downcast_ref()
+    }
+
+    /// Moves the asset out, for loads that hand it on to a resource. It
+    /// still counts as loaded.
+    pub fn take<T: 'static>(&mut self, handle: Handle<T>) -> Option<T> {
+        let value = self.entries[handle.id].value.take()?;
+        value.downcast().ok().map(|value| *value)
+    }
+
+    /// The handle for whatever was loaded under `name`. Getting it with the
+    /// wrong type gives `None`.
+    pub fn handle<T>(&self, name: &str) -> Option<Handle<T>> {
+        self.names.get(name).map(|&id| Handle::new(id))
+    }
+
+    pub fn get_named<T: 'static>(&self, name: &str) -> Option<&T> {
+        self.get(self.handle(name)?)
+    }
+
+    /// The name of every loaded asset of type `T`.
+    pub fn names<T: 'static>(&self) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.value.as_ref().is_some_and(|value| value.is::<T>()))
+            .map(|entry| entry.name.as_str())
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.entries.iter().any(|entry| entry.pending.is_some())
+    }
+
+    /// Share of loads that have finished, failed ones included, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        if self.entries.is_empty() {
+            return 1.;
+        }
+
+        let done = self.entries.iter().filter(|entry| entry.pending.is_none());
+        done.count() as f32 / self.entries.len() as f32
+    }
+
+    /// The name and error of each load that failed.
+    pub fn failures(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().filter_map(|entry| match &entry.state {
+            LoadState::Failed(e) => Some((entry.name.as_str(), e.as_str())),
+            _ => None,
+        })
+    }
+}
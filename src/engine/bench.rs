@@ -0,0 +1,249 @@
+//! Measuring a run rather than playing it. With `--bench` the game plays a
+//! scripted scenario for a fixed number of ticks, one per frame and without
+//! vsync, timing every frame and every system through the profiler's
+//! spans. At the end it prints the slowest systems, writes everything to
+//! [`BENCH_REPORT_PATH`] and exits.
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::Serialize;
+
+use super::{AppExit, CurrentState, GameState};
+
+/// Ticks benchmarked when `--bench-ticks` doesn't say.
+pub const BENCH_TICKS: u32 = 1_200;
+
+/// Ticks run before timing starts, while the scenario fills up.
+pub const BENCH_WARMUP_TICKS: u32 = 120;
+
+/// Seed the scenario runs on when `--seed` doesn't say, so one report can
+/// be compared with the next.
+pub const BENCH_SEED: u64 = 1;
+
+pub const BENCH_REPORT_PATH: &str = "bench.ron";
+
+/// Systems listed when printing the report; the file has them all.
+const PRINTED_SYSTEMS: usize = 20;
+
+/// Milliseconds across the timed frames.
+#[derive(Serialize, Clone, Copy, Debug, Default)]
+pub struct Spread {
+    pub mean: f64,
+    pub median: f64,
+    pub p95: f64,
+    pub max: f64,
+}
+
+impl Spread {
+    fn of(mut samples: Vec<f64>) -> Self {
+        if samples.is_empty() {
+            return Self::default();
+        }
+
+        samples.sort_by(f64::total_cmp);
+
+        let percentile = |p: f64| samples[((samples.len() - 1) as f64 * p).round() as usize];
+
+        Self {
+            mean: samples.iter().sum::<f64>() / samples.len() as f64,
+            median: percentile(0.5),
+            p95: percentile(0.95),
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+/// How long one system or schedule took per frame, in milliseconds.
+#[derive(Serialize, Clone, Debug)]
+pub struct Timing {
+    pub name: String,
+    /// Summed over every timed frame.
+    pub total: f64,
+    /// Per frame, counting frames it didn't run in as zero.
+    pub per_frame: Spread,
+}
+
+/// What `--bench` writes out, slowest systems first.
+#[derive(Serialize, Clone, Debug)]
+pub struct BenchReport {
+    pub ticks: u32,
+    pub seed: u64,
+    /// Wall clock seconds spent on the timed ticks.
+    pub seconds: f64,
+    pub frame: Spread,
+    pub entities: Spread,
+    pub schedules: Vec<Timing>,
+    pub systems: Vec<Timing>,
+}
+
+/// Present for a `--bench` run, gathering each frame's timings from
+/// [`record_frame`](super::record_frame).
+#[derive(Resource)]
+pub struct Bench {
+    pub ticks: u32,
+    pub seed: u64,
+    /// Frames seen since the run started, warmup included.
+    frame: u32,
+    frames: Vec<f64>,
+    entities: Vec<f64>,
+    /// Milliseconds per timed frame, indexed as `frames` is.
+    schedules: BTreeMap<&'static str, Vec<f64>>,
+    systems: BTreeMap<Arc<str>, Vec<f64>>,
+}
+
+impl Bench {
+    pub fn new(ticks: u32, seed: u64) -> Self {
+        Self {
+            ticks,
+            seed,
+            frame: 0,
+            frames: Vec::new(),
+            entities: Vec::new(),
+            schedules: BTreeMap::new(),
+            systems: BTreeMap::new(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.frames.len() as u32 >= self.ticks
+    }
+
+    /// Takes one frame's timings, in seconds, unless it's still warming up.
+    pub(super) fn record(
+        &mut self,
+        frame_time: f32,
+        entities: u32,
+        schedules: &[(&'static str, f64)],
+        systems: &BTreeMap<Arc<str>, f64>,
+    ) {
+        self.frame += 1;
+
+        if self.frame <= BENCH_WARMUP_TICKS || self.is_finished() {
+            return;
+        }
+
+        let index = self.frames.len();
+        self.frames.push(frame_time as f64 * 1000.);
+        self.entities.push(entities as f64);
+
+        fn push<K: Ord>(samples: &mut BTreeMap<K, Vec<f64>>, key: K, index: usize, ms: f64) {
+            let samples = samples.entry(key).or_default();
+            samples.resize(index, 0.);
+            samples.push(ms);
+        }
+
+        for &(name, seconds) in schedules {
+            push(&mut self.schedules, name, index, seconds * 1000.);
+        }
+
+        for (name, seconds) in systems {
+            push(&mut self.systems, name.clone(), index, seconds * 1000.);
+        }
+    }
+
+    pub fn report(&self) -> BenchReport {
+        let frames = self.frames.len();
+        let timing = |name: String, samples: &Vec<f64>| {
+            let mut samples = samples.clone();
+            samples.resize(frames, 0.);
+
+            Timing {
+                name,
+                total: samples.iter().sum(),
+                per_frame: Spread::of(samples),
+            }
+        };
+
+        let schedules = self
+            .schedules
+            .iter()
+            .map(|(name, samples)| timing(name.to_string(), samples))
+            .collect();
+        let mut systems: Vec<_> = self
+            .systems
+            .iter()
+            .map(|(name, samples)| timing(name.to_string(), samples))
+            .collect();
+        systems.sort_by(|a, b| b.total.total_cmp(&a.total));
+
+        BenchReport {
+            ticks: frames as u32,
+            seed: self.seed,
+            seconds: self.frames.iter().sum::<f64>() / 1000.,
+            frame: Spread::of(self.frames.clone()),
+            entities: Spread::of(self.entities.clone()),
+            schedules,
+            systems,
+        }
+    }
+}
+
+impl BenchReport {
+    /// A table of the frame, each schedule and the slowest systems.
+    pub fn summary(&self) -> String {
+        let row = |name: &str, spread: &Spread| {
+            format!(
+                "{:<40} {:>9.3} {:>9.3} {:>9.3} {:>9.3}\n",
+                name, spread.mean, spread.median, spread.p95, spread.max
+            )
+        };
+
+        let mut summary = format!(
+            "{} ticks on seed {} in {:.2}s, {:.0} entities on average\n\n{:<40} {:>9} {:>9} {:>9} {:>9}\n",
+            self.ticks,
+            self.seed,
+            self.seconds,
+            self.entities.mean,
+            "ms per frame",
+            "mean",
+            "median",
+            "p95",
+            "max"
+        );
+
+        summary += &row("frame", &self.frame);
+
+        for timing in &self.schedules {
+            summary += &row(&timing.name, &timing.per_frame);
+        }
+
+        summary += "\n";
+
+        for timing in self.systems.iter().take(PRINTED_SYSTEMS) {
+            summary += &row(&timing.name, &timing.per_frame);
+        }
+
+        summary
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let src = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())?;
+
+        std::fs::write(path, src).map_err(|e| e.to_string())
+    }
+}
+
+pub fn benchmarking(state: Res<CurrentState>, bench: Option<Res<Bench>>) -> bool {
+    bench.is_some() && state.contains(GameState::Playing)
+}
+
+/// Prints and saves the report once every tick has been timed, then
+/// quits.
+pub fn finish_bench(bench: Res<Bench>, mut exit: ResMut<AppExit>) {
+    if !bench.is_finished() {
+        return;
+    }
+
+    let report = bench.report();
+    println!("{}", report.summary());
+
+    match report.save(BENCH_REPORT_PATH) {
+        Ok(()) => info!("Wrote the benchmark report to {}", BENCH_REPORT_PATH),
+        Err(e) => error!("Failed to write {}: {}", BENCH_REPORT_PATH, e),
+    }
+
+    exit.0 = true;
+}
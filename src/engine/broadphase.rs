@@ -0,0 +1,324 @@
+//! Structures that narrow down what a box might overlap before the exact
+//! test, so collision checks don't compare everything with everything.
+//! Which one the game uses is [`Config::broadphase`](super::Config).
+//!
+//! Kept free of anything else in the crate so `benches/broadphase.rs` can
+//! include it as is.
+
+use std::{collections::HashMap, sync::Mutex};
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Rebuilt from scratch every tick: cleared, then every collider inserted.
+pub trait Broadphase: Send + Sync {
+    fn clear(&mut self);
+
+    fn insert(&mut self, entity: Entity, rect: Rect);
+
+    /// Fills `out` with everything that might overlap `rect`, each once and
+    /// in a stable order. They still need an exact overlap test.
+    fn gather(&self, rect: Rect, out: &mut Vec<Entity>);
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BroadphaseKind {
+    #[default]
+    Grid,
+    Quadtree,
+}
+
+impl BroadphaseKind {
+    pub fn build(self) -> Box<dyn Broadphase> {
+        match self {
+            BroadphaseKind::Grid => Box::new(SpatialHash::default()),
+            BroadphaseKind::Quadtree => Box::new(Quadtree::default()),
+        }
+    }
+}
+
+/// Texels per side of a [`SpatialHash`] cell, about the size of a big
+/// faller so most colliders only touch a few cells.
+pub const CELL_SIZE: f32 = 32.;
+
+/// A uniform grid, with each entity listed in every cell its box overlaps.
+/// Cheap to build and query while things are spread evenly.
+#[derive(Default)]
+pub struct SpatialHash {
+    cells: HashMap<IVec2, Vec<Entity>>,
+}
+
+impl SpatialHash {
+    fn cells(rect: Rect) -> impl Iterator<Item = IVec2> {
+        let min = (rect.point() / CELL_SIZE).floor().as_ivec2();
+        let max = ((rect.point() + rect.size()) / CELL_SIZE)
+            .floor()
+            .as_ivec2();
+
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| ivec2(x, y)))
+    }
+}
+
+impl Broadphase for SpatialHash {
+    /// Empties every cell, keeping the ones used last tick allocated.
+    fn clear(&mut self) {
+        self.cells.retain(|_, entities| {
+            let used = !entities.is_empty();
+            entities.clear();
+            used
+        });
+    }
+
+    fn insert(&mut self, entity: Entity, rect: Rect) {
+        for cell in Self::cells(rect) {
+            self.cells.entry(cell).or_default().push(entity);
+        }
+    }
+
+    fn gather(&self, rect: Rect, out: &mut Vec<Entity>) {
+        out.clear();
+
+        for cell in Self::cells(rect) {
+            if let Some(entities) = self.cells.get(&cell) {
+                out.extend_from_slice(entities);
+            }
+        }
+
+        out.sort_unstable();
+        out.dedup();
+    }
+}
+
+/// Area the [`Quadtree`] divides, well past the screen on every side.
+/// Anything reaching outside it is kept at the root.
+const QUADTREE_BOUNDS: Rect = Rect {
+    x: -512.,
+    y: -512.,
+    w: 2048.,
+    h: 2048.,
+};
+
+/// Entries a node holds before it splits.
+const QUADTREE_CAPACITY: usize = 8;
+const QUADTREE_MAX_DEPTH: u32 = 6;
+
+struct Node {
+    bounds: Rect,
+    depth: u32,
+    entries: Vec<(Entity, Rect)>,
+    /// Index of the first of four children, which are stored together.
+    children: Option<usize>,
+}
+
+impl Node {
+    fn new(bounds: Rect, depth: u32) -> Self {
+        Self {
+            bounds,
+            depth,
+            entries: Vec::new(),
+            children: None,
+        }
+    }
+}
+
+/// Divides space into quarters wherever entities crowd together, each
+/// entity kept in the smallest one that holds it whole. Copes better than
+/// the grid with a few dense clusters in a lot of empty space.
+pub struct Quadtree {
+    nodes: Vec<Node>,
+    /// Nodes left to visit during a query, kept between queries so they
+    /// don't allocate. Behind a lock only because queries take `&self`.
+    stack: Mutex<Vec<usize>>,
+}
+
+impl Default for Quadtree {
+    fn default() -> Self {
+        Self {
+            nodes: vec![Node::new(QUADTREE_BOUNDS, 0)],
+            stack: Mutex::default(),
+        }
+    }
+}
+
+fn contains(outer: Rect, inner: Rect) -> bool {
+    inner.x >= outer.x
+        && inner.y >= outer.y
+        && inner.right() <= outer.right()
+        && inner.bottom() <= outer.bottom()
+}
+
+impl Quadtree {
+    /// The child of `node` that holds `rect` whole, if any.
+    fn child_holding(&self, node: usize, rect: Rect) -> Option<usize> {
+        let first = self.nodes[node].children?;
+        (first..first + 4).find(|&child| contains(self.nodes[child].bounds, rect))
+    }
+
+    fn split(&mut self, node: usize) {
+        let Node { bounds, depth, .. } = self.nodes[node];
+        let half = bounds.size() / 2.;
+        let first = self.nodes.len();
+
+        for (x, y) in [(0., 0.), (1., 0.), (0., 1.), (1., 1.)] {
+            let corner = bounds.point() + half * vec2(x, y);
+            let quarter = Rect::new(corner.x, corner.y, half.x, half.y);
+            self.nodes.push(Node::new(quarter, depth + 1));
+        }
+
+        self.nodes[node].children = Some(first);
+
+        let entries = std::mem::take(&mut self.nodes[node].entries);
+
+        for (entity, rect) in entries {
+            let holder = self.child_holding(node, rect).unwrap_or(node);
+            self.nodes[holder].entries.push((entity, rect));
+        }
+    }
+}
+
+impl Broadphase for Quadtree {
+    fn clear(&mut self) {
+        self.nodes.truncate(1);
+        self.nodes[0].entries.clear();
+        self.nodes[0].children = None;
+    }
+
+    fn insert(&mut self, entity: Entity, rect: Rect) {
+        let mut node = 0;
+
+        while let Some(child) = self.child_holding(node, rect) {
+            node = child;
+        }
+
+        let leaf = &self.nodes[node];
+
+        if leaf.children.is_none()
+            && leaf.entries.len() >= QUADTREE_CAPACITY
+            && leaf.depth < QUADTREE_MAX_DEPTH
+        {
+            self.split(node);
+
+            if let Some(child) = self.child_holding(node, rect) {
+                node = child;
+            }
+        }
+
+        self.nodes[node].entries.push((entity, rect));
+    }
+
+    fn gather(&self, rect: Rect, out: &mut Vec<Entity>) {
+        out.clear();
+
+        let mut stack = self.stack.lock().unwrap();
+        stack.clear();
+        stack.push(0);
+
+        while let Some(node) = stack.pop() {
+            let node = &self.nodes[node];
+
+            out.extend(
+                node.entries
+                    .iter()
+                    .filter(|(_, other)| other.overlaps(&rect))
+                    .map(|(entity, _)| *entity),
+            );
+
+            if let Some(first) = node.children {
+                stack.extend((first..first + 4).filter(|&c| self.nodes[c].bounds.overlaps(&rect)));
+            }
+        }
+
+        out.sort_unstable();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    // Everything, imports included, lives inside the one test:
+    // `benches/broadphase.rs` builds this file without the test harness,
+    // where anything outside it would go unused.
+    #[test]
+    fn broadphases_gather_every_overlap_once() {
+        use super::*;
+
+        // A spread of boxes, a cluster dense enough to split the quadtree
+        // all the way down, boxes across the quarter lines and boxes
+        // reaching past its bounds.
+        let mut rects = Vec::new();
+
+        for y in 0..12 {
+            for x in 0..16 {
+                rects.push(Rect::new(x as f32 * 37., y as f32 * 41., 12., 20.));
+            }
+        }
+
+        for i in 0..120 {
+            let offset = (i % 11) as f32 * 0.7;
+            rects.push(Rect::new(
+                100. + offset,
+                100. + (i / 11) as f32 * 0.5,
+                2.,
+                2.,
+            ));
+        }
+
+        rects.extend([
+            Rect::new(508., 508., 8., 8.),
+            Rect::new(0., 508., 1024., 8.),
+            Rect::new(-600., 100., 40., 40.),
+            Rect::new(1500., 1500., 100., 100.),
+            Rect::new(-1000., -1000., 3000., 3000.),
+            Rect::new(-520., -520., 16., 16.),
+        ]);
+
+        let queries = [
+            QUADTREE_BOUNDS,
+            Rect::new(99., 99., 4., 4.),
+            Rect::new(104., 101., 1., 1.),
+            Rect::new(500., 500., 20., 20.),
+            Rect::new(-700., 0., 200., 200.),
+            Rect::new(1400., 1400., 300., 300.),
+            Rect::new(-530., -530., 10., 10.),
+            Rect::new(30., 30., 60., 60.),
+            Rect::new(300., 0., 1., 600.),
+        ];
+
+        let mut tree = Quadtree::default();
+        let mut grid = SpatialHash::default();
+
+        for (i, &rect) in rects.iter().enumerate() {
+            tree.insert(Entity::from_raw(i as u32), rect);
+            grid.insert(Entity::from_raw(i as u32), rect);
+        }
+
+        let deepest = tree.nodes.iter().map(|node| node.depth).max();
+        assert_eq!(deepest, Some(QUADTREE_MAX_DEPTH));
+        assert!(
+            tree.nodes[0].entries.len() >= 3,
+            "boxes past the bounds stay at the root"
+        );
+
+        let mut out = Vec::new();
+        let broadphases: [&dyn Broadphase; 2] = [&tree, &grid];
+
+        for broadphase in broadphases {
+            for query in queries {
+                broadphase.gather(query, &mut out);
+
+                let mut once = out.clone();
+                once.dedup();
+                assert_eq!(once.len(), out.len(), "duplicates gathering {query:?}");
+
+                for (i, rect) in rects.iter().enumerate() {
+                    if rect.overlaps(&query) {
+                        assert!(
+                            out.contains(&Entity::from_raw(i as u32)),
+                            "{rect:?} missed gathering {query:?}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
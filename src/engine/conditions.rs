@@ -0,0 +1,187 @@
+//! Run conditions for gating systems on game state and resources.
+//!
+//! State conditions read [`CurrentState`] directly, so a system gated on
+//! `in_state(Playing)` runs on the very first frame after the transition is
+//! applied rather than waiting for the state to settle.
+
+use bevy_ecs::{prelude::*, schedule::Condition};
+
+use macroquad::prelude::KeyCode;
+
+use super::{Action, Actions, CurrentState, GameState, KeyInput};
+
+pub use bevy_ecs::schedule::common_conditions::{not, resource_equals};
+
+/// True while `state` is on top of the stack.
+pub fn in_state(state: GameState) -> impl Fn(Res<CurrentState>) -> bool + Clone {
+    move |res| res.get() == state
+}
+
+/// True while `state` is anywhere on the stack, including when it is paused
+/// underneath another state.
+pub fn in_stack(state: GameState) -> impl Fn(Res<CurrentState>) -> bool + Clone {
+    move |res| res.contains(state)
+}
+
+/// True on the frame `key` goes down.
+pub fn key_pressed(key: KeyCode) -> impl Fn(Res<KeyInput>) -> bool + Clone {
+    move |keys| keys.is_pressed(key)
+}
+
+/// True on the frame `action` is triggered.
+pub fn action_pressed(action: Action) -> impl Fn(Res<Actions>) -> bool + Clone {
+    move |actions| actions.is_pressed(action)
+}
+
+/// True the first time the condition is evaluated after any state was
+/// entered or exited.
+pub fn state_changed(res: Res<CurrentState>, mut seen: Local<u64>) -> bool {
+    let changed = *seen != res.generation();
+    *seen = res.generation();
+    changed
+}
+
+/// True when both conditions are. `b` is only evaluated if `a` passes.
+pub fn and_then<MA, MB>(a: impl Condition<MA>, b: impl Condition<MB>) -> impl Condition<()> {
+    a.and(b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engine::{App, OnEnter, OnUpdate, Update};
+
+    #[derive(Resource, Default)]
+    struct Runs(Vec<&'static str>);
+
+    fn log(name: &'static str) -> impl Fn(ResMut<Runs>) {
+        move |mut runs| runs.0.push(name)
+    }
+
+    fn take(app: &mut App) -> Vec<&'static str> {
+        std::mem::take(&mut app.world.resource_mut::<Runs>().0)
+    }
+
+    fn test_app() -> App {
+        let mut app = App::new();
+        app.init_resource::<Runs>();
+        app.startup();
+        app
+    }
+
+    #[test]
+    fn in_state_runs_on_the_frame_after_a_transition() {
+        let mut app = test_app();
+        app.add_systems(Update, log("playing").run_if(in_state(GameState::Playing)));
+
+        app.world
+            .resource_mut::<CurrentState>()
+            .set(GameState::Playing);
+        app.update();
+        assert_eq!(take(&mut app), Vec::<&str>::new());
+
+        app.update();
+        assert_eq!(take(&mut app), vec!["playing"]);
+    }
+
+    #[test]
+    fn in_state_is_false_while_paused_but_in_stack_is_true() {
+        let mut app = test_app();
+        app.add_systems(
+            Update,
+            (
+                log("state").run_if(in_state(GameState::Playing)),
+                log("stack").run_if(in_stack(GameState::Playing)),
+            )
+                .chain(),
+        );
+
+        app.world
+            .resource_mut::<CurrentState>()
+            .set(GameState::Playing);
+        app.update();
+        app.world
+            .resource_mut::<CurrentState>()
+            .push(GameState::Paused);
+        app.update();
+        take(&mut app);
+
+        app.update();
+        assert_eq!(take(&mut app), vec!["stack"]);
+
+        app.world.resource_mut::<CurrentState>().pop();
+        app.update();
+        take(&mut app);
+
+        app.update();
+        assert_eq!(take(&mut app), vec!["state", "stack"]);
+    }
+
+    #[test]
+    fn state_changed_fires_once_per_transition() {
+        let mut app = test_app();
+        app.add_systems(Update, log("changed").run_if(state_changed));
+
+        app.update();
+        assert_eq!(take(&mut app), vec!["changed"]);
+
+        app.update();
+        assert_eq!(take(&mut app), Vec::<&str>::new());
+
+        app.world
+            .resource_mut::<CurrentState>()
+            .set(GameState::Playing);
+        app.update();
+        app.update();
+        assert_eq!(take(&mut app), vec!["changed"]);
+
+        app.update();
+        assert_eq!(take(&mut app), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn enter_and_update_schedules_run_once_per_transition() {
+        let mut app = App::new();
+        app.init_resource::<Runs>()
+            .add_systems(OnEnter(GameState::Playing), log("enter"))
+            .add_systems(OnUpdate(GameState::Playing), log("update"));
+        app.startup();
+
+        app.world
+            .resource_mut::<CurrentState>()
+            .set(GameState::Playing);
+        app.update();
+        assert_eq!(take(&mut app), vec!["enter"]);
+
+        app.update();
+        app.update();
+        assert_eq!(take(&mut app), vec!["update", "update"]);
+    }
+
+    #[test]
+    fn combinators() {
+        #[derive(Resource, PartialEq)]
+        struct Lives(u32);
+
+        let mut app = test_app();
+        app.insert_resource(Lives(3)).add_systems(
+            Update,
+            (
+                log("not").run_if(not(in_state(GameState::Playing))),
+                log("and_then").run_if(and_then(
+                    in_state(GameState::MainMenu),
+                    resource_equals(Lives(3)),
+                )),
+                log("equals").run_if(resource_equals(Lives(0))),
+            )
+                .chain(),
+        );
+
+        app.update();
+        assert_eq!(take(&mut app), vec!["not", "and_then"]);
+
+        app.world.resource_mut::<Lives>().0 = 0;
+        app.update();
+        assert_eq!(take(&mut app), vec!["not", "equals"]);
+    }
+}
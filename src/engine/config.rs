@@ -0,0 +1,247 @@
+//! Window and engine settings kept in [`CONFIG_PATH`], read before the
+//! window opens. Unlike [`Settings`](crate::domain::Settings), nothing in
+//! game changes these; they're edited by hand or overridden on the command
+//! line.
+
+use std::{collections::BTreeMap, sync::OnceLock};
+
+use bevy_ecs::prelude::*;
+use macroquad::{miniquad::conf::Platform, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    BENCH_SEED, BENCH_TICKS, BroadphaseKind, LogConfig, LogLevel, configure_logger, install_logger,
+    write_atomic,
+};
+
+pub const CONFIG_PATH: &str = "config.ron";
+
+/// Switches for developer aids.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct DebugFlags {
+    pub show_fps: bool,
+    /// Opens the profiler overlay at startup.
+    pub profiler: bool,
+    /// Runs the simulation in [`Deterministic`](super::Deterministic) mode.
+    pub deterministic: bool,
+    /// Where to write a checksum of the gameplay state every tick.
+    pub checksum_log: Option<String>,
+    /// A checksum log from an earlier run to compare every tick against.
+    pub checksum_compare: Option<String>,
+}
+
+impl DebugFlags {
+    /// Checksums are only comparable between deterministic runs, so asking
+    /// for them turns it on too.
+    pub fn is_deterministic(&self) -> bool {
+        self.deterministic || self.checksum_log.is_some() || self.checksum_compare.is_some()
+    }
+}
+
+impl Default for DebugFlags {
+    fn default() -> Self {
+        Self {
+            show_fps: true,
+            profiler: false,
+            deterministic: false,
+            checksum_log: None,
+            checksum_compare: None,
+        }
+    }
+}
+
+/// How to start, given on the command line for one run rather than kept
+/// in the file.
+#[derive(Clone, Debug, Default)]
+pub struct Launch {
+    /// Seeds the first run, over [`SEED_ENV_VAR`](super::SEED_ENV_VAR).
+    pub seed: Option<u64>,
+    /// Goes straight into a run once loading finishes.
+    pub skip_menu: bool,
+    /// A recording to play back once loading finishes.
+    pub replay: Option<String>,
+    /// Ticks to benchmark for, rather than play.
+    pub bench: Option<u32>,
+}
+
+#[derive(Resource, Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct Config {
+    pub window_width: i32,
+    pub window_height: i32,
+    pub fullscreen: bool,
+    pub vsync: bool,
+    /// MSAA samples; 1 turns it off.
+    pub sample_count: i32,
+    /// Window pixels per texel.
+    pub texel_size: u32,
+    /// How collisions find what's near each other. Either gives the same
+    /// results, just faster or slower.
+    pub broadphase: BroadphaseKind,
+    pub debug: DebugFlags,
+    pub log: LogConfig,
+    /// Shader uniforms tuned away from their defaults, by
+    /// `"material.uniform"`, e.g. `"crt.curvature": [1.5]`. The uniform
+    /// panel exports here.
+    pub uniforms: BTreeMap<String, Vec<f32>>,
+    #[serde(skip)]
+    pub launch: Launch,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            window_width: 800,
+            window_height: 600,
+            fullscreen: false,
+            vsync: true,
+            sample_count: 1,
+            texel_size: 2,
+            broadphase: BroadphaseKind::default(),
+            debug: DebugFlags::default(),
+            log: LogConfig::default(),
+            uniforms: BTreeMap::new(),
+            launch: Launch::default(),
+        }
+    }
+}
+
+impl Config {
+    /// Reads `path`, writing the defaults there first if it doesn't exist.
+    /// An unreadable file is left alone so hand edits aren't lost.
+    pub fn load(path: &str) -> Self {
+        let Ok(src) = std::fs::read_to_string(path) else {
+            let config = Self::default();
+
+            if let Err(e) = config.save(path) {
+                warn!("Failed to save {}: {}", path, e);
+            }
+
+            return config;
+        };
+
+        ron::from_str(&src).unwrap_or_else(|e| {
+            warn!("Ignoring unreadable {}: {}", path, e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|e| e.to_string())
+            .and_then(|src| write_atomic(path, &src).map_err(|e| e.to_string()))
+    }
+
+    /// Applies command line overrides on top of the file. Unknown or
+    /// malformed arguments are skipped with a warning.
+    pub fn apply_args(&mut self, args: impl IntoIterator<Item = String>) {
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--fullscreen" => self.fullscreen = true,
+                "--windowed" => self.fullscreen = false,
+                "--vsync" => self.vsync = true,
+                "--no-vsync" => self.vsync = false,
+                "--show-fps" => self.debug.show_fps = true,
+                "--hide-fps" => self.debug.show_fps = false,
+                "--profiler" => self.debug.profiler = true,
+                "--deterministic" => self.debug.deterministic = true,
+                "--checksum-log" => set_path(&mut self.debug.checksum_log, &arg, args.next()),
+                "--checksum-compare" => {
+                    set_path(&mut self.debug.checksum_compare, &arg, args.next())
+                }
+                "--width" => set_from(&mut self.window_width, &arg, args.next()),
+                "--height" => set_from(&mut self.window_height, &arg, args.next()),
+                "--samples" => set_from(&mut self.sample_count, &arg, args.next()),
+                "--texel-size" => set_from(&mut self.texel_size, &arg, args.next()),
+                "--log-level" => set_level(&mut self.log.level, &arg, args.next()),
+                "--seed" => set_some(&mut self.launch.seed, &arg, args.next()),
+                "--skip-menu" => self.launch.skip_menu = true,
+                "--replay" => set_path(&mut self.launch.replay, &arg, args.next()),
+                "--bench" => self.launch.bench = self.launch.bench.or(Some(BENCH_TICKS)),
+                "--bench-ticks" => set_some(&mut self.launch.bench, &arg, args.next()),
+                _ => warn!("Ignoring unknown argument {}", arg),
+            }
+        }
+
+        self.texel_size = self.texel_size.max(1);
+
+        // A benchmark times the same ticks every run, as fast as they go.
+        if self.launch.bench.is_some() {
+            self.vsync = false;
+            self.debug.deterministic = true;
+            self.launch.skip_menu = true;
+            self.launch.seed.get_or_insert(BENCH_SEED);
+        }
+    }
+
+    /// [`CONFIG_PATH`] with this process' arguments applied, loaded on
+    /// first use. The window is configured before any resources exist, so
+    /// this is shared rather than read twice. It's also the first thing to
+    /// run, so it starts the logger.
+    pub fn get() -> &'static Config {
+        static CONFIG: OnceLock<Config> = OnceLock::new();
+
+        CONFIG.get_or_init(|| {
+            install_logger();
+
+            let mut config = Config::load(CONFIG_PATH);
+            config.apply_args(std::env::args().skip(1));
+            configure_logger(&config.log);
+            config
+        })
+    }
+
+    pub fn window_conf(&self, title: &str) -> Conf {
+        Conf {
+            window_title: title.to_string(),
+            window_width: self.window_width,
+            window_height: self.window_height,
+            fullscreen: self.fullscreen,
+            sample_count: self.sample_count,
+            window_resizable: true,
+            platform: Platform {
+                swap_interval: Some(self.vsync as i32),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+}
+
+fn set_from<T: std::str::FromStr>(field: &mut T, arg: &str, value: Option<String>) {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(value)) => *field = value,
+        _ => warn!("{} expects a number", arg),
+    }
+}
+
+fn set_some<T: std::str::FromStr>(field: &mut Option<T>, arg: &str, value: Option<String>) {
+    match value.as_deref().map(str::parse) {
+        Some(Ok(value)) => *field = Some(value),
+        _ => warn!("{} expects a number", arg),
+    }
+}
+
+fn set_path(field: &mut Option<String>, arg: &str, value: Option<String>) {
+    match value {
+        Some(path) => *field = Some(path),
+        None => warn!("{} expects a path", arg),
+    }
+}
+
+fn set_level(field: &mut LogLevel, arg: &str, value: Option<String>) {
+    match value.as_deref().and_then(LogLevel::from_name) {
+        Some(level) => *field = level,
+        None => warn!(
+            "{} expects one of off, error, warn, info, debug or trace",
+            arg
+        ),
+    }
+}
+
+pub fn show_fps(config: Res<Config>) -> bool {
+    config.debug.show_fps
+}
@@ -0,0 +1,364 @@
+//! A drop-down console for poking at the game while it runs, opened and
+//! closed with [`CONSOLE_KEY`]. Plugins add their own commands with
+//! [`App::add_console_command`](super::App::add_console_command), so each
+//! subsystem brings whatever makes sense for it; `help` lists them all.
+//!
+//! While it's open the console has the keyboard to itself, so typing a
+//! command doesn't also steer the ship.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Config, CurrentState, GameState, KeyInput, Profiler, TextInput, Time, short_name};
+
+pub const CONSOLE_KEY: KeyCode = KeyCode::GraveAccent;
+
+/// Longest line that can be typed.
+const MAX_INPUT: usize = 48;
+
+/// Lines of output kept, oldest dropped first.
+const SCROLLBACK: usize = 64;
+
+/// Commands kept for stepping back through with up and down.
+const RECALL: usize = 32;
+
+/// Archetypes listed by `entities`, most populous first.
+const MAX_ARCHETYPES: usize = 10;
+
+/// What a command prints when it succeeds, or why it failed.
+pub type CommandResult = Result<String, String>;
+
+#[derive(Clone, Copy)]
+pub struct ConsoleCommand {
+    /// Arguments shown by `help`, e.g. `<prefab> [x y]`.
+    pub usage: &'static str,
+    pub help: &'static str,
+    pub run: fn(&mut World, &[&str]) -> CommandResult,
+}
+
+/// Every command the [`Console`] understands, by name.
+#[derive(Resource, Default)]
+pub struct ConsoleCommands(BTreeMap<&'static str, ConsoleCommand>);
+
+impl ConsoleCommands {
+    pub fn get(&self, name: &str) -> Option<&ConsoleCommand> {
+        self.0.get(name)
+    }
+
+    /// Replaces any command already called `name`.
+    pub fn insert(&mut self, name: &'static str, command: ConsoleCommand) {
+        self.0.insert(name, command);
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &ConsoleCommand)> {
+        self.0.iter().map(|(name, command)| (*name, command))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineKind {
+    /// A command as it was typed.
+    Input,
+    Output,
+    Error,
+}
+
+#[derive(Resource)]
+pub struct Console {
+    open: bool,
+    pub input: TextInput,
+    /// Everything printed, oldest first.
+    pub lines: VecDeque<(LineKind, String)>,
+    /// Commands run, oldest first.
+    recall: Vec<String>,
+    /// Where in `recall` up and down have stepped to, or `None` while
+    /// typing a new line.
+    recalling: Option<usize>,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        Self {
+            open: false,
+            // The key that opens the console types a character of its own.
+            input: TextInput::new(MAX_INPUT, |c| {
+                (c == ' ' || c.is_ascii_graphic() && !matches!(c, '`' | '~')).then_some(c)
+            }),
+            lines: VecDeque::with_capacity(SCROLLBACK),
+            recall: Vec::new(),
+            recalling: None,
+        }
+    }
+}
+
+impl Console {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Adds `text` to the output, a line at a time.
+    pub fn print(&mut self, kind: LineKind, text: &str) {
+        for line in text.lines() {
+            if self.lines.len() == SCROLLBACK {
+                self.lines.pop_front();
+            }
+
+            self.lines.push_back((kind, line.to_string()));
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Replaces the input with an earlier command, `step` back or forward.
+    /// Stepping forward past the last one leaves the input empty again.
+    fn step_recall(&mut self, step: i32) {
+        let Some(last) = self.recall.len().checked_sub(1) else {
+            return;
+        };
+
+        self.recalling = match (self.recalling, step < 0) {
+            (None, true) => Some(last),
+            (None, false) => None,
+            (Some(index), true) => Some(index.saturating_sub(1)),
+            (Some(index), false) => (index < last).then_some(index + 1),
+        };
+
+        self.input.clear();
+
+        if let Some(index) = self.recalling {
+            self.input.text = self.recall[index].clone();
+        }
+    }
+
+    /// Takes the typed line, echoing it, unless it's blank.
+    fn submit(&mut self) -> Option<String> {
+        let line = self.input.text.trim().to_string();
+        self.input.clear();
+        self.recalling = None;
+
+        if line.is_empty() {
+            return None;
+        }
+
+        if self.recall.last() != Some(&line) {
+            if self.recall.len() == RECALL {
+                self.recall.remove(0);
+            }
+
+            self.recall.push(line.clone());
+        }
+
+        self.print(LineKind::Input, &format!("> {}", line));
+        Some(line)
+    }
+
+    /// Applies this frame's typing, returning a command once one is entered.
+    fn update(&mut self, keys: &KeyInput, dt: f32) -> Option<String> {
+        self.input.update(keys, dt);
+
+        if keys.is_pressed(KeyCode::Escape) {
+            self.open = false;
+        }
+
+        if keys.is_pressed(KeyCode::Up) {
+            self.step_recall(-1);
+        }
+
+        if keys.is_pressed(KeyCode::Down) {
+            self.step_recall(1);
+        }
+
+        if keys.is_pressed(KeyCode::Enter) || keys.is_pressed(KeyCode::KpEnter) {
+            return self.submit();
+        }
+
+        None
+    }
+}
+
+pub fn console_open(console: Res<Console>) -> bool {
+    console.is_open()
+}
+
+/// Opens and closes the console, and while it's open takes the frame's
+/// keyboard input for itself before anything else reads it. Commands run
+/// right away, with the whole world to work on.
+pub fn update_console(world: &mut World) {
+    let dt = world.resource::<Time>().unscaled_dt;
+
+    let line = world.resource_scope(|world, mut console: Mut<Console>| {
+        let mut keys = world.resource_mut::<KeyInput>();
+        let toggled = keys.is_pressed(CONSOLE_KEY);
+
+        if toggled {
+            console.open = !console.open;
+        }
+
+        if !console.open && !toggled {
+            return None;
+        }
+
+        let line = if toggled {
+            None
+        } else {
+            console.update(&keys, dt)
+        };
+
+        *keys = KeyInput::default();
+        line
+    });
+
+    if let Some(line) = line {
+        run_command(world, &line);
+    }
+}
+
+/// Runs `line` as if it had been typed into the console, printing the
+/// result there.
+pub fn run_command(world: &mut World, line: &str) {
+    let words: Vec<&str> = line.split_whitespace().collect();
+
+    let Some((name, args)) = words.split_first() else {
+        return;
+    };
+
+    let result = match world.resource::<ConsoleCommands>().get(name).copied() {
+        Some(command) => (command.run)(world, args),
+        None => Err(format!("Unknown command {}, try help", name)),
+    };
+
+    let mut console = world.resource_mut::<Console>();
+
+    match result {
+        Ok(output) => console.print(LineKind::Output, &output),
+        Err(error) => console.print(LineKind::Error, &error),
+    }
+}
+
+/// Parses the argument at `index`, called `name` in errors.
+pub fn parse_arg<T: std::str::FromStr>(
+    args: &[&str],
+    index: usize,
+    name: &str,
+) -> Result<T, String> {
+    let arg = args.get(index).ok_or_else(|| format!("Missing {}", name))?;
+    arg.parse()
+        .map_err(|_| format!("{} isn't a valid {}", arg, name))
+}
+
+pub fn help_command(world: &mut World, _args: &[&str]) -> CommandResult {
+    let lines: Vec<String> = world
+        .resource::<ConsoleCommands>()
+        .iter()
+        .map(|(name, command)| {
+            let usage = [name, command.usage].join(" ");
+            format!("{} - {}", usage.trim_end(), command.help)
+        })
+        .collect();
+
+    Ok(lines.join("\n"))
+}
+
+pub fn clear_command(world: &mut World, _args: &[&str]) -> CommandResult {
+    world.resource_mut::<Console>().clear();
+    Ok(String::new())
+}
+
+pub fn state_command(world: &mut World, args: &[&str]) -> CommandResult {
+    let Some(name) = args.first() else {
+        let stack: Vec<String> = world
+            .resource::<CurrentState>()
+            .stack()
+            .iter()
+            .map(|state| format!("{:?}", state))
+            .collect();
+
+        return Ok(stack.join(" > "));
+    };
+
+    let state = GameState::ALL
+        .into_iter()
+        .find(|state| format!("{:?}", state).eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("No state called {}", name))?;
+
+    world.resource_mut::<CurrentState>().set(state);
+    Ok(format!("Switching to {:?}", state))
+}
+
+pub fn time_scale_command(world: &mut World, args: &[&str]) -> CommandResult {
+    let mut time = world.resource_mut::<Time>();
+
+    if !args.is_empty() {
+        time.debug_scale = parse_arg::<f32>(args, 0, "scale")?.max(0.);
+    }
+
+    Ok(format!("Time scale {}", time.debug_scale))
+}
+
+pub fn fps_command(world: &mut World, _args: &[&str]) -> CommandResult {
+    let mut config = world.resource_mut::<Config>();
+    config.debug.show_fps = !config.debug.show_fps;
+    Ok(format!("FPS counter {}", on_off(config.debug.show_fps)))
+}
+
+pub fn profiler_command(world: &mut World, _args: &[&str]) -> CommandResult {
+    let mut profiler = world.resource_mut::<Profiler>();
+    let enabled = !profiler.is_enabled();
+    profiler.set_enabled(enabled);
+    Ok(format!("Profiler {}", on_off(enabled)))
+}
+
+fn on_off(on: bool) -> &'static str {
+    if on { "on" } else { "off" }
+}
+
+/// Counts entities by the components they have, optionally only those with
+/// a component called `args[0]`.
+pub fn entities_command(world: &mut World, args: &[&str]) -> CommandResult {
+    let filter = args.first();
+    let components = world.components();
+    let mut total = 0;
+    let mut archetypes: Vec<(usize, Vec<String>)> = Vec::new();
+
+    for archetype in world.archetypes().iter() {
+        if archetype.is_empty() {
+            continue;
+        }
+
+        let names: Vec<String> = archetype
+            .components()
+            .filter_map(|id| components.get_name(id))
+            .map(short_name)
+            .collect();
+
+        if let Some(filter) = filter
+            && !names.iter().any(|name| name.eq_ignore_ascii_case(filter))
+        {
+            continue;
+        }
+
+        total += archetype.len();
+        archetypes.push((archetype.len(), names));
+    }
+
+    archetypes.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    let mut lines = vec![format!("{} entities", total)];
+
+    for (count, names) in archetypes.iter().take(MAX_ARCHETYPES) {
+        lines.push(format!("{:>5} {}", count, names.join(" ")));
+    }
+
+    if archetypes.len() > MAX_ARCHETYPES {
+        lines.push(format!(
+            "and {} more kinds",
+            archetypes.len() - MAX_ARCHETYPES
+        ));
+    }
+
+    Ok(lines.join("\n"))
+}
@@ -0,0 +1,8 @@
+/// The entry `offset` steps away from `current` in `all`, wrapping around
+/// either end. Enums stepped through on menus call it over their `ALL`.
+pub fn cycle<T: Copy + PartialEq>(all: &[T], current: &T, offset: isize) -> T {
+    let len = all.len() as isize;
+    let index = all.iter().position(|item| item == current).unwrap() as isize;
+
+    all[(index + offset).rem_euclid(len) as usize]
+}
@@ -0,0 +1,176 @@
+//! Deterministic mode, so the same seed and input always produce the same
+//! run: replays, tests and netplay depend on it. While the [`Deterministic`]
+//! resource exists, every frame advances exactly one fixed tick instead of
+//! following the wall clock, and every schedule runs its systems one at a
+//! time in the same order. All gameplay randomness already goes through the
+//! seeded [`Rng`](super::Rng).
+//!
+//! To check it holds, the gameplay state is hashed every tick into a
+//! [`ChecksumLog`], which can be written out and compared against the log
+//! of an earlier run, reporting the first tick they diverge on.
+
+use std::{
+    collections::HashMap,
+    fs::File,
+    hash::Hasher,
+    io::{BufWriter, Write},
+};
+
+use bevy_ecs::{
+    prelude::*,
+    schedule::{ExecutorKind, Schedules},
+};
+use macroquad::prelude::*;
+
+use super::DebugFlags;
+
+/// Present when the simulation runs in deterministic mode.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Deterministic;
+
+/// Runs every schedule single threaded, so systems that don't order
+/// themselves against each other still run in a fixed order rather than
+/// whichever thread gets there first.
+pub fn run_single_threaded(world: &mut World) {
+    for (_, schedule) in world.resource_mut::<Schedules>().iter_mut() {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    }
+}
+
+/// FNV-1a, which unlike the standard library's hasher is guaranteed to give
+/// the same result on every platform and build.
+pub struct Checksum(u64);
+
+impl Default for Checksum {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Checksum {
+    pub fn write_f32(&mut self, value: f32) {
+        self.write_u32(value.to_bits());
+    }
+
+    pub fn write_vec2(&mut self, value: Vec2) {
+        self.write_f32(value.x);
+        self.write_f32(value.y);
+    }
+}
+
+impl Hasher for Checksum {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    // Integers go in little endian and lengths as 64 bits, so wasm and
+    // native builds agree.
+    fn write_u32(&mut self, value: u32) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, value: u64) {
+        self.write(&value.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+}
+
+/// Gameplay state checksums, one per tick of the current run, written as
+/// `tick checksum` lines.
+#[derive(Resource, Default)]
+pub struct ChecksumLog {
+    tick: u64,
+    writer: Option<BufWriter<File>>,
+    expected: HashMap<u64, u64>,
+    /// The first tick that didn't match the expected log.
+    pub diverged: Option<u64>,
+}
+
+impl ChecksumLog {
+    /// Opens the log and comparison files `flags` name. Either failing is
+    /// reported and that half is skipped.
+    pub fn new(flags: &DebugFlags) -> Self {
+        let writer = flags.checksum_log.as_ref().and_then(|path| {
+            File::create(path)
+                .inspect_err(|e| warn!("Failed to create {}: {}", path, e))
+                .ok()
+                .map(BufWriter::new)
+        });
+
+        let expected = flags
+            .checksum_compare
+            .as_ref()
+            .and_then(|path| {
+                std::fs::read_to_string(path)
+                    .inspect_err(|e| warn!("Failed to read {}: {}", path, e))
+                    .ok()
+            })
+            .map(|src| parse_log(&src))
+            .unwrap_or_default();
+
+        Self {
+            tick: 0,
+            writer,
+            expected,
+            diverged: None,
+        }
+    }
+
+    /// Starts counting ticks from zero for a new run.
+    pub fn restart(&mut self) {
+        self.tick = 0;
+        self.diverged = None;
+
+        if let Some(writer) = &mut self.writer {
+            let _ = writeln!(writer, "# run");
+        }
+    }
+
+    /// Logs this tick's checksum and checks it against the expected one.
+    pub fn record(&mut self, checksum: u64) {
+        let tick = self.tick;
+        self.tick += 1;
+
+        if let Some(writer) = &mut self.writer
+            && let Err(e) = writeln!(writer, "{} {:016x}", tick, checksum)
+        {
+            warn!("Failed to write checksum: {}", e);
+            self.writer = None;
+        }
+
+        if self.diverged.is_none()
+            && let Some(&expected) = self.expected.get(&tick)
+            && expected != checksum
+        {
+            warn!(
+                "Diverged at tick {}: {:016x}, expected {:016x}",
+                tick, checksum, expected
+            );
+            self.diverged = Some(tick);
+        }
+    }
+}
+
+/// The first run's `tick checksum` lines; anything else is skipped.
+fn parse_log(src: &str) -> HashMap<u64, u64> {
+    src.split("# run")
+        .map(str::trim)
+        .find(|run| !run.is_empty())
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let (tick, checksum) = line.split_once(' ')?;
+            Some((tick.parse().ok()?, u64::from_str_radix(checksum, 16).ok()?))
+        })
+        .collect()
+}
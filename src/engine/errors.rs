@@ -0,0 +1,74 @@
+//! Failures the game can carry on from. Rather than panicking, whatever runs
+//! into one raises it with [`Errors::raise`], and [`GameState::Error`] goes
+//! on top of whatever was running to show the message, where the player can
+//! try again or quit.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Action, Actions, AppExit, CurrentState, GameState};
+
+/// Puts right whatever failed, raising it again if it still can't be.
+pub type Retry = fn(&mut World);
+
+#[derive(Resource, Default)]
+pub struct Errors {
+    /// Everything raised since the error screen was last left, oldest
+    /// first.
+    messages: Vec<String>,
+    retries: Vec<Retry>,
+}
+
+impl Errors {
+    /// Logs `message` and shows it to the player. With a `retry`, they can
+    /// try again rather than only quit.
+    pub fn raise(&mut self, message: impl Into<String>, retry: Option<Retry>) {
+        let message = message.into();
+        error!("{}", message);
+
+        self.messages.push(message);
+        self.retries.extend(retry);
+    }
+
+    pub fn messages(&self) -> &[String] {
+        &self.messages
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Whether anything raised can be tried again.
+    pub fn can_retry(&self) -> bool {
+        !self.retries.is_empty()
+    }
+}
+
+/// Shows the error screen once anything has been raised.
+pub fn show_errors(errors: Res<Errors>, mut state: ResMut<CurrentState>) {
+    if !errors.is_empty() && !state.contains(GameState::Error) {
+        state.push(GameState::Error);
+    }
+}
+
+/// [`Action::Confirm`] leaves the error screen and tries everything raised
+/// again, [`Action::Back`] quits.
+pub fn update_error_screen(world: &mut World) {
+    let actions = world.resource::<Actions>();
+
+    if actions.is_pressed(Action::Back) {
+        world.resource_mut::<AppExit>().0 = true;
+        return;
+    }
+
+    if !actions.is_pressed(Action::Confirm) || !world.resource::<Errors>().can_retry() {
+        return;
+    }
+
+    let errors = std::mem::take(&mut *world.resource_mut::<Errors>());
+    world.resource_mut::<CurrentState>().pop();
+
+    for retry in errors.retries {
+        retry(world);
+    }
+}
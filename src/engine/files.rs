@@ -0,0 +1,124 @@
+//! Reading asset files. Normally they come from disk, found through
+//! [`resolve_asset`] so the game runs from any working directory; with the
+//! `embed` feature they're baked into the binary by `build.rs`, so a single
+//! executable or wasm file runs anywhere. Web builds without `embed` fetch
+//! them relative to the page.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+/// Environment variable naming a directory to look for asset paths in
+/// before anywhere else.
+#[cfg(all(not(feature = "embed"), not(target_arch = "wasm32")))]
+pub const ASSET_ROOT_ENV_VAR: &str = "CATHEDRAL_ASSET_ROOT";
+
+/// Every file under `assets/`, by path.
+#[cfg(feature = "embed")]
+static EMBEDDED: &[(&str, &[u8])] = include!(concat!(env!("OUT_DIR"), "/embedded.rs"));
+
+/// Directories asset paths are tried relative to, in order:
+/// [`ASSET_ROOT_ENV_VAR`], the executable's directory, the working
+/// directory, and the crate the game was built from, so `cargo run` works
+/// from anywhere.
+#[cfg(all(not(feature = "embed"), not(target_arch = "wasm32")))]
+fn asset_roots() -> &'static [std::path::PathBuf] {
+    use std::{path::PathBuf, sync::OnceLock};
+
+    static ROOTS: OnceLock<Vec<PathBuf>> = OnceLock::new();
+
+    ROOTS.get_or_init(|| {
+        let candidates = [
+            std::env::var_os(ASSET_ROOT_ENV_VAR).map(PathBuf::from),
+            std::env::current_exe()
+                .ok()
+                .and_then(|exe| exe.parent().map(PathBuf::from)),
+            std::env::current_dir().ok(),
+            Some(PathBuf::from(env!("CARGO_MANIFEST_DIR"))),
+        ];
+
+        let mut roots: Vec<PathBuf> = Vec::new();
+
+        for root in candidates.into_iter().flatten() {
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+
+        roots
+    })
+}
+
+/// Where `path` is on disk: the first of the asset roots it exists under.
+/// The error lists every place that was searched.
+#[cfg(all(not(feature = "embed"), not(target_arch = "wasm32")))]
+pub fn resolve_asset(path: &str) -> Result<std::path::PathBuf, String> {
+    let candidates: Vec<_> = asset_roots().iter().map(|root| root.join(path)).collect();
+
+    candidates
+        .iter()
+        .find(|candidate| candidate.exists())
+        .cloned()
+        .ok_or_else(|| {
+            let searched: Vec<String> = candidates
+                .iter()
+                .map(|candidate| candidate.display().to_string())
+                .collect();
+
+            format!("not found, searched:\n{}", searched.join("\n"))
+        })
+}
+
+#[cfg(feature = "embed")]
+pub async fn read_file(path: &str) -> Result<Vec<u8>, String> {
+    let path = path.trim_start_matches("./");
+
+    EMBEDDED
+        .iter()
+        .find(|(name, _)| *name == path)
+        .map(|(_, bytes)| bytes.to_vec())
+        .ok_or_else(|| format!("{} is not embedded", path))
+}
+
+#[cfg(all(not(feature = "embed"), target_arch = "wasm32"))]
+pub async fn read_file(path: &str) -> Result<Vec<u8>, String> {
+    load_file(path).await.map_err(|e| e.to_string())
+}
+
+#[cfg(all(not(feature = "embed"), not(target_arch = "wasm32")))]
+pub async fn read_file(path: &str) -> Result<Vec<u8>, String> {
+    let resolved = resolve_asset(path)?;
+
+    std::fs::read(&resolved).map_err(|e| format!("{}: {}", resolved.display(), e))
+}
+
+/// Present when the player's settings, progress and recordings are neither
+/// read nor written, e.g. under the headless harness, so tests start from
+/// the defaults and leave the files alone.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct Ephemeral;
+
+/// Run condition: settings, progress and recordings are written to disk.
+pub fn persistent(ephemeral: Option<Res<Ephemeral>>) -> bool {
+    ephemeral.is_none()
+}
+
+/// Writes `contents` beside `path` and then moves it into place, so a crash
+/// or full disk mid-write leaves the old file rather than half of the new
+/// one.
+pub fn write_atomic(path: &str, contents: &str) -> std::io::Result<()> {
+    let temp = format!("{}.tmp", path);
+
+    std::fs::write(&temp, contents)?;
+    std::fs::rename(&temp, path)
+}
+
+pub async fn read_string(path: &str) -> Result<String, String> {
+    let bytes = read_file(path).await?;
+    String::from_utf8(bytes).map_err(|e| e.to_string())
+}
+
+pub async fn read_texture(path: &str) -> Result<Texture2D, String> {
+    let bytes = read_file(path).await?;
+    let image = Image::from_file_with_format(&bytes, None).map_err(|e| e.to_string())?;
+    Ok(Texture2D::from_image(&image))
+}
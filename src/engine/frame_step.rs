@@ -0,0 +1,83 @@
+//! Freezing the simulation to watch it a tick at a time, for timing issues
+//! such as collisions and spawns. [`FRAME_STEP_KEY`] freezes and thaws it
+//! and [`STEP_KEY`] advances it exactly one fixed tick while frozen, as does
+//! the `step` console command. Everything is still drawn, so the frozen
+//! frame can be looked over with the inspector.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{CommandResult, KeyInput, Time, parse_arg};
+
+pub const FRAME_STEP_KEY: KeyCode = KeyCode::F9;
+pub const STEP_KEY: KeyCode = KeyCode::F10;
+
+#[derive(Resource, Default)]
+pub struct FrameStep {
+    paused: bool,
+    /// Ticks to run on the next frame.
+    steps: u32,
+}
+
+impl FrameStep {
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn toggle(&mut self) {
+        self.paused = !self.paused;
+        self.steps = 0;
+    }
+
+    /// Pauses, if it wasn't already, and queues `ticks` to run.
+    pub fn step(&mut self, ticks: u32) {
+        self.paused = true;
+        self.steps += ticks;
+    }
+
+    /// The ticks to run this frame, none unless paused and stepping.
+    pub fn take_steps(&mut self) -> u32 {
+        std::mem::take(&mut self.steps)
+    }
+}
+
+pub fn update_frame_step(keys: Res<KeyInput>, mut frame_step: ResMut<FrameStep>) {
+    if keys.is_pressed(FRAME_STEP_KEY) {
+        frame_step.toggle();
+    }
+
+    if keys.is_pressed(STEP_KEY) && frame_step.is_paused() {
+        frame_step.step(1);
+    }
+}
+
+pub fn frame_step_paused(frame_step: Res<FrameStep>) -> bool {
+    frame_step.is_paused()
+}
+
+pub fn pause_command(world: &mut World, _args: &[&str]) -> CommandResult {
+    let mut frame_step = world.resource_mut::<FrameStep>();
+    frame_step.toggle();
+
+    Ok(format!(
+        "Simulation {}",
+        if frame_step.is_paused() {
+            "frozen"
+        } else {
+            "running"
+        }
+    ))
+}
+
+pub fn step_command(world: &mut World, args: &[&str]) -> CommandResult {
+    let ticks = if args.is_empty() {
+        1
+    } else {
+        parse_arg::<u32>(args, 0, "tick count")?
+    };
+
+    world.resource_mut::<FrameStep>().step(ticks);
+
+    let tick = world.resource::<Time>().tick + ticks as u64;
+    Ok(format!("Stepping to tick {}", tick))
+}
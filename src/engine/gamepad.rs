@@ -0,0 +1,175 @@
+//! Gamepad input, read straight from the Linux joystick interface
+//! (`/dev/input/js*`) so no extra dependency is needed. Other platforms never
+//! see a pad. Buttons and the left stick feed [`Actions`](super::Actions)
+//! like any other device.
+
+use std::collections::HashSet;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Stick deflection below this is treated as centered.
+pub const STICK_DEADZONE: f32 = 0.2;
+
+/// Face, shoulder and menu buttons, named by position on the pad.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    South,
+    East,
+    West,
+    North,
+    LeftShoulder,
+    RightShoulder,
+    Select,
+    Start,
+}
+
+impl GamepadButton {
+    /// Button numbering used by the Linux driver for Xbox-style pads.
+    #[cfg(target_os = "linux")]
+    fn from_index(index: u8) -> Option<Self> {
+        Some(match index {
+            0 => GamepadButton::South,
+            1 => GamepadButton::East,
+            2 => GamepadButton::West,
+            3 => GamepadButton::North,
+            4 => GamepadButton::LeftShoulder,
+            5 => GamepadButton::RightShoulder,
+            6 => GamepadButton::Select,
+            7 => GamepadButton::Start,
+            _ => return None,
+        })
+    }
+}
+
+/// The first connected pad.
+#[derive(Resource, Default)]
+pub struct Gamepad {
+    pub down: HashSet<GamepadButton>,
+    pub pressed: HashSet<GamepadButton>,
+    pub released: HashSet<GamepadButton>,
+    /// Left stick with the deadzone removed, rescaled so it still reaches
+    /// full length at the edge.
+    pub stick: Vec2,
+    raw_stick: Vec2,
+    #[cfg(target_os = "linux")]
+    device: Option<std::fs::File>,
+}
+
+/// Applies a radial deadzone to `raw`, keeping its direction.
+fn apply_deadzone(raw: Vec2) -> Vec2 {
+    let length = raw.length().min(1.);
+
+    if length <= STICK_DEADZONE {
+        return Vec2::ZERO;
+    }
+
+    raw.normalize() * (length - STICK_DEADZONE) / (1. - STICK_DEADZONE)
+}
+
+#[cfg(target_os = "linux")]
+mod joydev {
+    use std::{fs::File, io::Read, os::unix::fs::OpenOptionsExt};
+
+    /// `O_NONBLOCK`, so reads return straight away when nothing happened.
+    const O_NONBLOCK: i32 = 0o4000;
+
+    const JS_EVENT_BUTTON: u8 = 0x01;
+    const JS_EVENT_AXIS: u8 = 0x02;
+    /// Set on the synthetic events describing the initial state.
+    const JS_EVENT_INIT: u8 = 0x80;
+
+    pub enum Event {
+        Button { index: u8, down: bool },
+        Axis { index: u8, value: f32 },
+    }
+
+    pub fn open() -> Option<File> {
+        (0..4).find_map(|i| {
+            std::fs::OpenOptions::new()
+                .read(true)
+                .custom_flags(O_NONBLOCK)
+                .open(format!("/dev/input/js{i}"))
+                .ok()
+        })
+    }
+
+    /// The next queued event. `Err` means the pad was unplugged.
+    pub fn read(device: &mut File) -> Result<Option<Event>, ()> {
+        let mut buf = [0u8; 8];
+
+        match device.read(&mut buf) {
+            Ok(8) => {}
+            Ok(_) => return Err(()),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(None),
+            Err(_) => return Err(()),
+        }
+
+        let value = i16::from_ne_bytes([buf[4], buf[5]]);
+        let kind = buf[6] & !JS_EVENT_INIT;
+        let index = buf[7];
+
+        Ok(Some(match kind {
+            JS_EVENT_BUTTON => Event::Button {
+                index,
+                down: value != 0,
+            },
+            JS_EVENT_AXIS => Event::Axis {
+                index,
+                value: value as f32 / i16::MAX as f32,
+            },
+            _ => return Ok(None),
+        }))
+    }
+}
+
+/// Drains the pad's queued events. Until a pad is found, every poll looks
+/// for one again, so plugging one in mid-game works.
+pub fn poll_gamepad(mut gamepad: ResMut<Gamepad>) {
+    gamepad.pressed.clear();
+    gamepad.released.clear();
+
+    #[cfg(target_os = "linux")]
+    {
+        if gamepad.device.is_none() {
+            gamepad.device = joydev::open();
+        }
+
+        let gamepad = &mut *gamepad;
+
+        while let Some(device) = gamepad.device.as_mut() {
+            let event = match joydev::read(device) {
+                Ok(Some(event)) => event,
+                Ok(None) => break,
+                Err(()) => {
+                    gamepad.device = None;
+                    gamepad.released.extend(gamepad.down.drain());
+                    gamepad.raw_stick = Vec2::ZERO;
+                    break;
+                }
+            };
+
+            match event {
+                joydev::Event::Button { index, down } => {
+                    let Some(button) = GamepadButton::from_index(index) else {
+                        continue;
+                    };
+
+                    if !down {
+                        if gamepad.down.remove(&button) {
+                            gamepad.released.insert(button);
+                        }
+                    } else if gamepad.down.insert(button) {
+                        gamepad.pressed.insert(button);
+                    }
+                }
+                joydev::Event::Axis { index: 0, value } => gamepad.raw_stick.x = value,
+                joydev::Event::Axis { index: 1, value } => gamepad.raw_stick.y = value,
+                joydev::Event::Axis { .. } => {}
+            }
+        }
+    }
+
+    gamepad.stick = apply_deadzone(gamepad.raw_stick);
+}
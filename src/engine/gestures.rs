@@ -0,0 +1,71 @@
+//! Timing patterns on top of [`Actions`]: double taps and holds. Abilities
+//! read [`GestureEvent`]s rather than each keeping their own timers.
+
+use std::collections::HashMap;
+
+use bevy_ecs::prelude::*;
+
+use super::{Action, Actions, Time};
+
+/// Longest gap, in seconds, between two presses of a double tap.
+pub const DOUBLE_TAP_WINDOW: f64 = 0.25;
+
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub enum GestureEvent {
+    /// Pressed twice within [`DOUBLE_TAP_WINDOW`]. A third press starts a
+    /// new pair rather than completing another.
+    DoubleTap(Action),
+    /// Let go after being held down for `seconds`.
+    HoldReleased { action: Action, seconds: f32 },
+}
+
+/// When each action was last pressed and how long it's been held. Runs on
+/// game time, so holds charge slower in slow motion.
+#[derive(Resource, Default)]
+pub struct Gestures {
+    now: f64,
+    last_press: HashMap<Action, f64>,
+    held_since: HashMap<Action, f64>,
+}
+
+impl Gestures {
+    /// Seconds `action` has been held down, or zero if it's up.
+    pub fn held_for(&self, action: Action) -> f32 {
+        self.held_since
+            .get(&action)
+            .map_or(0., |since| (self.now - since) as f32)
+    }
+}
+
+pub fn detect_gestures(
+    actions: Res<Actions>,
+    time: Res<Time>,
+    mut gestures: ResMut<Gestures>,
+    mut ev_gesture: EventWriter<GestureEvent>,
+) {
+    let now = time.elapsed;
+    gestures.now = now;
+
+    for action in Action::ALL {
+        if actions.is_pressed(action) {
+            if let Some(at) = gestures.last_press.remove(&action)
+                && now - at <= DOUBLE_TAP_WINDOW
+            {
+                ev_gesture.send(GestureEvent::DoubleTap(action));
+            } else {
+                gestures.last_press.insert(action, now);
+            }
+
+            gestures.held_since.insert(action, now);
+        }
+
+        if actions.is_released(action)
+            && let Some(since) = gestures.held_since.remove(&action)
+        {
+            ev_gesture.send(GestureEvent::HoldReleased {
+                action,
+                seconds: (now - since) as f32,
+            });
+        }
+    }
+}
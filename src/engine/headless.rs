@@ -0,0 +1,66 @@
+//! The engine without a window, for driving the game from tests. Nothing
+//! is drawn and no device is read: each frame is exactly one fixed tick
+//! long, and input comes from whatever [`InputOverride`] holds, the same
+//! way a replay feeds it.
+
+use bevy_ecs::prelude::*;
+
+use super::{
+    App, Assets, Config, Ephemeral, GameSet, InputOverride, MouseInput, Plugin, PreUpdate, Screen,
+    Time, add_core, detect_gestures, update_actions,
+};
+
+/// Everything [`CorePlugin`](super::CorePlugin) sets up except the window
+/// and devices, running deterministically on the default [`Config`]. It's
+/// [`Ephemeral`] too, so no files or arguments change how it plays and
+/// nothing it does is saved.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        let mut config = Config::default();
+        config.debug.deterministic = true;
+
+        let size = (
+            config.window_width as u32 / config.texel_size,
+            config.window_height as u32 / config.texel_size,
+        );
+
+        app.insert_resource(Assets::blocking())
+            .insert_resource(Ephemeral);
+        add_core(app, config);
+
+        let mut screen = app.world.resource_mut::<Screen>();
+        screen.width = size.0 as usize;
+        screen.height = size.1 as usize;
+
+        app.add_systems(
+            PreUpdate,
+            (
+                update_headless_time,
+                update_headless_pointer,
+                update_actions
+                    .after(update_headless_time)
+                    .after(update_headless_pointer),
+                detect_gestures.after(update_actions),
+            )
+                .in_set(GameSet::Input),
+        );
+    }
+}
+
+/// Moves the clock on one fixed tick, or as long as the
+/// [`InputOverride`] says.
+pub fn update_headless_time(mut time: ResMut<Time>, input_override: Option<Res<InputOverride>>) {
+    let dt = input_override.map_or(time.fixed_dt, |input| input.0.dt);
+    time.advance(dt, false);
+}
+
+pub fn update_headless_pointer(
+    mut mouse: ResMut<MouseInput>,
+    input_override: Option<Res<InputOverride>>,
+) {
+    if let Some(input) = input_override {
+        mouse.position = input.pointer();
+    }
+}
@@ -0,0 +1,54 @@
+//! Dev builds only: textures are reloaded when their file changes, so an
+//! atlas can be edited while the game runs. Reloading keeps the texture's
+//! [`Handle`](super::Handle), so whatever draws it picks up the new pixels
+//! on the next frame.
+
+use std::{collections::HashMap, time::SystemTime};
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Assets, Time, resolve_asset};
+
+/// Seconds between checks of every texture's modification time.
+const WATCH_INTERVAL: f32 = 0.5;
+
+#[derive(Resource, Default)]
+pub struct TextureWatcher {
+    /// When each texture's file was last modified, as of the last check.
+    modified: HashMap<String, SystemTime>,
+    since_check: f32,
+}
+
+pub fn watch_textures(
+    mut watcher: ResMut<TextureWatcher>,
+    mut assets: ResMut<Assets>,
+    time: Res<Time>,
+) {
+    watcher.since_check += time.unscaled_dt;
+
+    if watcher.since_check < WATCH_INTERVAL {
+        return;
+    }
+
+    watcher.since_check = 0.;
+
+    let paths: Vec<String> = assets.names::<Texture2D>().map(str::to_string).collect();
+
+    for path in paths {
+        let Some(modified) = resolve_asset(&path)
+            .ok()
+            .and_then(|file| std::fs::metadata(file).ok())
+            .and_then(|meta| meta.modified().ok())
+        else {
+            continue;
+        };
+
+        if let Some(previous) = watcher.modified.insert(path.clone(), modified)
+            && previous != modified
+        {
+            info!("Reloading {}", path);
+            assets.load_texture(&path);
+        }
+    }
+}
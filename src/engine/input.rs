@@ -0,0 +1,107 @@
+use std::collections::HashSet;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{InputOverride, Screen};
+
+#[derive(Resource, Default)]
+pub struct KeyInput {
+    pub down: HashSet<KeyCode>,
+    pub pressed: HashSet<KeyCode>,
+    pub released: HashSet<KeyCode>,
+    /// Characters typed this frame, in order.
+    pub chars: Vec<char>,
+}
+
+impl KeyInput {
+    pub fn is_down(&self, key: KeyCode) -> bool {
+        self.down.contains(&key)
+    }
+
+    pub fn is_pressed(&self, key: KeyCode) -> bool {
+        self.pressed.contains(&key)
+    }
+
+    pub fn is_released(&self, key: KeyCode) -> bool {
+        self.released.contains(&key)
+    }
+}
+
+/// The mouse, with its position in texels on the render target rather than
+/// window pixels.
+#[derive(Resource, Default)]
+pub struct MouseInput {
+    pub position: Vec2,
+    pub down: HashSet<MouseButton>,
+    pub pressed: HashSet<MouseButton>,
+    pub released: HashSet<MouseButton>,
+    /// Scroll this frame. Positive `y` is away from the user.
+    pub wheel: Vec2,
+}
+
+impl MouseInput {
+    pub fn is_down(&self, button: MouseButton) -> bool {
+        self.down.contains(&button)
+    }
+
+    pub fn is_pressed(&self, button: MouseButton) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    pub fn is_released(&self, button: MouseButton) -> bool {
+        self.released.contains(&button)
+    }
+}
+
+/// Converts a position in window pixels to texels on the render target.
+pub fn window_to_texels(position: Vec2, screen: &Screen) -> Vec2 {
+    let texel_size = screen.texel_size as f32;
+    // The canvas is centered in the window, see `present_canvas`.
+    let padding = (vec2(screen_width(), screen_height())
+        - vec2(screen.width as f32, screen.height as f32) * texel_size)
+        * 0.5;
+
+    ((position - padding) / texel_size).floor()
+}
+
+pub fn update_mouse_input(
+    mut mouse: ResMut<MouseInput>,
+    screen: Res<Screen>,
+    input_override: Option<Res<InputOverride>>,
+) {
+    mouse.position = match input_override {
+        Some(replayed) => replayed.pointer(),
+        None => window_to_texels(Vec2::from(mouse_position()), &screen),
+    };
+    mouse.wheel = Vec2::from(mouse_wheel());
+
+    mouse.down.clear();
+    mouse.pressed.clear();
+    mouse.released.clear();
+
+    for button in [MouseButton::Left, MouseButton::Right, MouseButton::Middle] {
+        if is_mouse_button_down(button) {
+            mouse.down.insert(button);
+        }
+
+        if is_mouse_button_pressed(button) {
+            mouse.pressed.insert(button);
+        }
+
+        if is_mouse_button_released(button) {
+            mouse.released.insert(button);
+        }
+    }
+}
+
+pub fn update_key_input(mut keys: ResMut<KeyInput>) {
+    keys.down = get_keys_down();
+    keys.pressed = get_keys_pressed();
+    keys.released = get_keys_released();
+
+    keys.chars.clear();
+    while let Some(c) = get_char_pressed() {
+        keys.chars.push(c);
+    }
+}
@@ -0,0 +1,23 @@
+use bevy_ecs::prelude::*;
+
+use super::{Action, Actions, AppExit, Assets, CurrentState, GameState};
+
+/// Leaves [`GameState::Loading`] once every background load has finished.
+/// If any failed the game can't start, so it stays on the errors until
+/// [`Action::Back`] quits.
+pub fn finish_loading(
+    assets: Res<Assets>,
+    actions: Res<Actions>,
+    mut state: ResMut<CurrentState>,
+    mut exit: ResMut<AppExit>,
+) {
+    if assets.is_loading() {
+        return;
+    }
+
+    if assets.failures().next().is_none() {
+        state.set(GameState::MainMenu);
+    } else if actions.is_pressed(Action::Back) {
+        exit.0 = true;
+    }
+}
@@ -0,0 +1,126 @@
+//! Translated UI text. Each [`Language`] has a table of keys to strings in
+//! [`LOCALES_DIR`], looked up with [`t!`](crate::t). English is also built
+//! in, so there's text before the tables load, and keys a translation
+//! leaves out show in English rather than as the bare key.
+
+use std::{
+    collections::HashMap,
+    sync::{LazyLock, RwLock},
+};
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{cycle, read_string};
+
+pub const LOCALES_DIR: &str = "assets/locales";
+
+const BUILT_IN: &str = include_str!("../../assets/locales/en.ron");
+
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::Spanish];
+
+    /// The table's file name in [`LOCALES_DIR`], without the extension.
+    pub fn code(self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::Spanish => "es",
+        }
+    }
+
+    /// The language's name in itself, so it can be found whatever language
+    /// is showing.
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "ENGLISH",
+            Language::Spanish => "ESPANOL",
+        }
+    }
+
+    /// The language `offset` steps away from this one, wrapping around.
+    pub fn cycle(&self, offset: isize) -> Self {
+        cycle(&Self::ALL, self, offset)
+    }
+}
+
+pub type StringTable = HashMap<String, String>;
+
+struct Locale {
+    language: Language,
+    tables: HashMap<Language, StringTable>,
+    built_in: StringTable,
+}
+
+// A global rather than a resource so any code that shows text can call
+// `t!` without threading it through as a system parameter.
+static LOCALE: LazyLock<RwLock<Locale>> = LazyLock::new(|| {
+    RwLock::new(Locale {
+        language: Language::default(),
+        tables: HashMap::new(),
+        built_in: ron::from_str(BUILT_IN).expect("built in string table"),
+    })
+});
+
+/// Reads every language's table from [`LOCALES_DIR`].
+pub async fn load_locales() -> Result<HashMap<Language, StringTable>, String> {
+    let mut tables = HashMap::new();
+
+    for language in Language::ALL {
+        let path = format!("{}/{}.ron", LOCALES_DIR, language.code());
+        let src = read_string(&path).await?;
+        let table = ron::from_str(&src).map_err(|e| format!("{}: {}", path, e))?;
+        tables.insert(language, table);
+    }
+
+    Ok(tables)
+}
+
+pub fn set_locales(tables: HashMap<Language, StringTable>) {
+    LOCALE.write().unwrap().tables = tables;
+}
+
+/// Switches the text [`t!`](crate::t) returns to the [`Language`]
+/// resource's.
+pub fn apply_language(language: Res<Language>) {
+    LOCALE.write().unwrap().language = *language;
+}
+
+/// The text for `key` in the current language, falling back to English and
+/// then to the key itself.
+pub fn translate(key: &str) -> String {
+    let locale = LOCALE.read().unwrap();
+
+    locale
+        .tables
+        .get(&locale.language)
+        .and_then(|table| table.get(key))
+        .or_else(|| locale.built_in.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string())
+}
+
+/// Like [`translate`], replacing each `{name}` with its value.
+pub fn translate_with(key: &str, args: &[(&str, String)]) -> String {
+    args.iter().fold(translate(key), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Looks up UI text by key: `t!("menu.tutorial")`, or with named values
+/// for its placeholders: `t!("hud.score", score = score.0)`.
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::engine::translate($key)
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::engine::translate_with($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
@@ -0,0 +1,291 @@
+//! Where `info!`, `warn!` and the rest end up. macroquad hands them to the
+//! `log` crate, and the logger installed by [`install_logger`] writes each
+//! one, stamped with the UTC time, level and module, to the console and to
+//! the file in [`LogConfig`], so a player's log can be sent in along with a
+//! bug report.
+//!
+//! The logger goes in before [`Config`](super::Config) is read, so problems
+//! reading it are logged too. Until [`configure_logger`] hands it the
+//! settings, lines are only printed and held back for the file.
+
+use std::{collections::BTreeMap, sync::Mutex};
+
+use log::{LevelFilter, Log, Metadata, Record};
+use macroquad::miniquad::{self, date};
+use serde::{Deserialize, Serialize};
+
+use super::{format_date, format_time_of_day};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    pub const ALL: [LogLevel; 6] = [
+        LogLevel::Off,
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Off => LevelFilter::Off,
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Trace => LevelFilter::Trace,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        LogLevel::ALL
+            .into_iter()
+            .find(|level| format!("{:?}", level).eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct LogConfig {
+    /// The level for modules without one of their own.
+    pub level: LogLevel,
+    /// Levels for particular modules and everything under them, e.g.
+    /// `"cathedral::domain::save": Debug`. The most specific one applies.
+    pub modules: BTreeMap<String, LogLevel>,
+    /// Where to write the log, or `None` for the console only. Each run
+    /// starts a fresh file, moving the last one to `<file>.1`.
+    pub file: Option<String>,
+    /// Bytes the file may grow to before it's moved aside mid-run too.
+    pub max_size: u64,
+    /// Old files kept, `<file>.1` the newest.
+    pub keep: u32,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: LogLevel::Info,
+            modules: BTreeMap::new(),
+            file: Some("cathedral.log".to_string()),
+            max_size: 1024 * 1024,
+            keep: 3,
+        }
+    }
+}
+
+impl LogConfig {
+    /// The level that applies to `target`, a module path.
+    fn filter(&self, target: &str) -> LevelFilter {
+        self.modules
+            .iter()
+            .filter(|(module, _)| {
+                target
+                    .strip_prefix(module.as_str())
+                    .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"))
+            })
+            .max_by_key(|(module, _)| module.len())
+            .map_or(self.level, |(_, level)| *level)
+            .filter()
+    }
+
+    /// The most verbose level anything is logged at.
+    fn max_filter(&self) -> LevelFilter {
+        self.modules
+            .values()
+            .map(|level| level.filter())
+            .fold(self.level.filter(), Ord::max)
+    }
+}
+
+/// The log file being written and how big it has got.
+struct LogFile {
+    path: String,
+    file: std::fs::File,
+    size: u64,
+}
+
+impl LogFile {
+    /// Moves `path` to `path.1`, `path.1` to `path.2` and so on, dropping
+    /// whatever falls past `keep`.
+    fn rotate(path: &str, keep: u32) {
+        if keep == 0 {
+            let _ = std::fs::remove_file(path);
+            return;
+        }
+
+        for i in (1..keep).rev() {
+            let _ = std::fs::rename(format!("{}.{}", path, i), format!("{}.{}", path, i + 1));
+        }
+
+        let _ = std::fs::rename(path, format!("{}.1", path));
+    }
+
+    fn create(path: &str) -> std::io::Result<Self> {
+        Ok(Self {
+            path: path.to_string(),
+            file: std::fs::File::create(path)?,
+            size: 0,
+        })
+    }
+
+    fn write(&mut self, line: &str, config: &LogConfig) {
+        use std::io::Write;
+
+        if self.size > 0 && self.size + line.len() as u64 > config.max_size {
+            Self::rotate(&self.path, config.keep);
+
+            match Self::create(&self.path) {
+                Ok(fresh) => *self = fresh,
+                Err(_) => return,
+            }
+        }
+
+        if self.file.write_all(line.as_bytes()).is_ok() {
+            self.size += line.len() as u64;
+        }
+    }
+}
+
+struct LoggerState {
+    /// `None` until [`configure_logger`] runs.
+    config: Option<LogConfig>,
+    file: Option<LogFile>,
+    /// Lines logged before the file was opened.
+    pending: Vec<String>,
+}
+
+struct Logger {
+    state: Mutex<LoggerState>,
+}
+
+static LOGGER: Logger = Logger {
+    state: Mutex::new(LoggerState {
+        config: None,
+        file: None,
+        pending: Vec::new(),
+    }),
+};
+
+/// Seconds since the Unix epoch as `YYYY-MM-DD HH:MM:SS.mmm`.
+fn timestamp(now: f64) -> String {
+    let secs = now as u64;
+    let millis = (now.fract() * 1000.) as u32;
+
+    format!(
+        "{} {}:{:02}.{:03}",
+        format_date(secs),
+        format_time_of_day(secs),
+        secs % 60,
+        millis
+    )
+}
+
+/// Prints through miniquad, which reaches the browser's console on the web
+/// as well as stderr everywhere else.
+fn print(line: &str, level: log::Level) {
+    let level = match level {
+        log::Level::Error => miniquad::log::Level::Error,
+        log::Level::Warn => miniquad::log::Level::Warn,
+        log::Level::Info => miniquad::log::Level::Info,
+        log::Level::Debug => miniquad::log::Level::Debug,
+        log::Level::Trace => miniquad::log::Level::Trace,
+    };
+
+    miniquad::log::__private_api_log_lit(line, level, &("", "", "", 0));
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        let state = self.state.lock().unwrap();
+
+        match &state.config {
+            Some(config) => metadata.level() <= config.filter(metadata.target()),
+            None => metadata.level() <= LevelFilter::Info,
+        }
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format!(
+            "{} {:<5} {}: {}",
+            timestamp(date::now()),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        print(&line, record.level());
+
+        let mut state = self.state.lock().unwrap();
+        let state = &mut *state;
+        let line = line + "\n";
+
+        match (&state.config, &mut state.file) {
+            (Some(config), Some(file)) => file.write(&line, config),
+            (Some(_), None) => {}
+            (None, _) => state.pending.push(line),
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Starts catching log lines and panics. Until [`configure_logger`] runs
+/// they're only printed, at `Info` and above.
+pub fn install_logger() {
+    if log::set_logger(&LOGGER).is_err() {
+        return;
+    }
+
+    log::set_max_level(LevelFilter::Trace);
+
+    // A panic's message otherwise only reaches stderr, which players never
+    // see, so it goes in the log as well.
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log::error!("{}", info);
+        default_hook(info);
+    }));
+}
+
+/// Applies `config`, opening the log file and writing out everything
+/// logged before it was.
+pub fn configure_logger(config: &LogConfig) {
+    let mut state = LOGGER.state.lock().unwrap();
+    let pending = std::mem::take(&mut state.pending);
+
+    #[cfg(not(target_arch = "wasm32"))]
+    if let Some(path) = &config.file {
+        LogFile::rotate(path, config.keep);
+
+        match LogFile::create(path) {
+            Ok(mut file) => {
+                for line in &pending {
+                    file.write(line, config);
+                }
+
+                state.file = Some(file);
+            }
+            Err(e) => print(
+                &format!("Failed to open log file {}: {}", path, e),
+                log::Level::Warn,
+            ),
+        }
+    }
+
+    log::set_max_level(config.max_filter());
+    state.config = Some(config.clone());
+}
@@ -0,0 +1,75 @@
+mod actions;
+mod app;
+mod assets;
+mod bench;
+mod broadphase;
+mod conditions;
+mod config;
+mod console;
+mod cycle;
+mod determinism;
+mod errors;
+mod files;
+mod frame_step;
+mod gamepad;
+mod gestures;
+mod headless;
+#[cfg(all(debug_assertions, not(target_arch = "wasm32"), not(feature = "embed")))]
+mod hot_reload;
+mod input;
+mod keys;
+mod loading;
+mod locale;
+mod logging;
+mod pacing;
+mod plugin;
+mod pool;
+mod profiler;
+mod replay;
+mod rng;
+mod schedules;
+mod screen;
+mod state;
+mod text_input;
+mod time;
+mod timers;
+mod touch;
+mod window;
+
+pub use actions::*;
+pub use app::*;
+pub use assets::*;
+pub use bench::*;
+pub use broadphase::*;
+pub use conditions::*;
+pub use config::*;
+pub use console::*;
+pub use cycle::*;
+pub use determinism::*;
+pub use errors::*;
+pub use files::*;
+pub use frame_step::*;
+pub use gamepad::*;
+pub use gestures::*;
+pub use headless::*;
+#[cfg(all(debug_assertions, not(target_arch = "wasm32"), not(feature = "embed")))]
+pub use hot_reload::*;
+pub use input::*;
+pub use keys::*;
+pub use loading::*;
+pub use locale::*;
+pub use logging::*;
+pub use pacing::*;
+pub use plugin::*;
+pub use pool::*;
+pub use profiler::*;
+pub use replay::*;
+pub use rng::*;
+pub use schedules::*;
+pub use screen::*;
+pub use state::*;
+pub use text_input::*;
+pub use time::*;
+pub use timers::*;
+pub use touch::*;
+pub use window::*;
@@ -0,0 +1,95 @@
+//! Holding the main loop to a [`FrameCap`], so an idle menu doesn't render
+//! as fast as the hardware allows when vsync is off or ignored.
+
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::cycle;
+use crate::t;
+
+/// The most frames drawn per second, chosen in the settings.
+#[derive(Resource, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FrameCap {
+    Fps30,
+    Fps60,
+    Fps120,
+    #[default]
+    Unlimited,
+}
+
+impl FrameCap {
+    pub const ALL: [FrameCap; 4] = [
+        FrameCap::Fps30,
+        FrameCap::Fps60,
+        FrameCap::Fps120,
+        FrameCap::Unlimited,
+    ];
+
+    pub fn fps(self) -> Option<u32> {
+        match self {
+            FrameCap::Fps30 => Some(30),
+            FrameCap::Fps60 => Some(60),
+            FrameCap::Fps120 => Some(120),
+            FrameCap::Unlimited => None,
+        }
+    }
+
+    pub fn name(self) -> String {
+        match self.fps() {
+            Some(fps) => t!("frame_cap.fps", fps = fps),
+            None => t!("frame_cap.unlimited"),
+        }
+    }
+
+    /// The cap `offset` steps away from this one, wrapping around.
+    pub fn cycle(&self, offset: isize) -> Self {
+        cycle(&Self::ALL, self, offset)
+    }
+}
+
+/// Paces frames to a [`FrameCap`]. The browser already paces the web build
+/// and the main thread can't sleep there, so it never waits.
+#[derive(Default)]
+pub struct FrameLimiter {
+    #[cfg(not(target_arch = "wasm32"))]
+    next: Option<std::time::Instant>,
+}
+
+impl FrameLimiter {
+    /// How early to wake from sleeping and spin instead, since sleeps
+    /// routinely overshoot by a millisecond or more.
+    #[cfg(not(target_arch = "wasm32"))]
+    const SPIN: std::time::Duration = std::time::Duration::from_millis(2);
+
+    /// Waits until the next frame is due. Call once a frame, just before it
+    /// is presented.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn wait(&mut self, cap: FrameCap) {
+        use std::time::{Duration, Instant};
+
+        let Some(fps) = cap.fps() else {
+            self.next = None;
+            return;
+        };
+
+        let frame = Duration::from_secs_f64(1. / fps as f64);
+        let now = Instant::now();
+
+        // A frame that ran more than a whole frame late starts the cadence
+        // over instead of rushing the ones after it to catch up.
+        let due = self.next.filter(|due| *due + frame > now).unwrap_or(now);
+
+        if let Some(sleep) = due.saturating_duration_since(now).checked_sub(Self::SPIN) {
+            std::thread::sleep(sleep);
+        }
+
+        while Instant::now() < due {
+            std::hint::spin_loop();
+        }
+
+        self.next = Some(due + frame);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    pub fn wait(&mut self, _cap: FrameCap) {}
+}
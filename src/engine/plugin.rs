@@ -0,0 +1,131 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{
+    Actions, App, AppExit, Bindings, ChecksumLog, Config, Console, Deterministic, Errors,
+    FocusChanged, FrameStep, GameSet, GameState, Gamepad, GestureEvent, Gestures, InputBuffer,
+    KeyInput, LOCALES_DIR, Language, MouseInput, OnUpdate, Pool, PostUpdate, PreUpdate, Profiler,
+    QuitRequested, Screen, Startup, TouchInput, WindowResized, apply_language, clear_command,
+    detect_gestures, entities_command, exit_on_quit_request, fps_command, help_command,
+    load_locales, pause_command, poll_gamepad, poll_window_events, profiler_command, set_locales,
+    setup_touch, setup_window, show_errors, state_command, step_command, time_scale_command,
+    toggle_profiler, update_actions, update_console, update_error_screen, update_frame_step,
+    update_key_input, update_mouse_input, update_screen, update_time, update_touch_input,
+};
+
+/// A group of resources, events and systems registered together.
+pub trait Plugin {
+    fn build(&self, app: &mut App);
+}
+
+/// Time, input from every device, screen size and window events, refreshed
+/// at the start of every frame.
+pub struct CorePlugin;
+
+impl Plugin for CorePlugin {
+    fn build(&self, app: &mut App) {
+        add_core(app, Config::get().clone());
+
+        app.add_systems(Startup, (setup_window, setup_touch))
+            .add_systems(
+                PreUpdate,
+                (
+                    update_time,
+                    update_key_input,
+                    poll_gamepad,
+                    poll_window_events,
+                    update_screen.after(poll_window_events),
+                    update_mouse_input.after(update_screen),
+                    update_touch_input.after(update_screen),
+                    update_console.after(update_time).after(update_key_input),
+                    update_actions
+                        .after(update_time)
+                        .after(update_console)
+                        .after(poll_gamepad)
+                        .after(update_mouse_input)
+                        .after(update_touch_input),
+                    detect_gestures.after(update_actions),
+                    toggle_profiler.after(update_console),
+                    update_frame_step.after(update_console),
+                )
+                    .in_set(GameSet::Input),
+            )
+            .load(LOCALES_DIR, load_locales(), |_, tables| set_locales(tables));
+
+        #[cfg(all(debug_assertions, not(target_arch = "wasm32"), not(feature = "embed")))]
+        app.init_resource::<super::TextureWatcher>()
+            .add_systems(PostUpdate, super::watch_textures);
+    }
+}
+
+/// The resources, events, state handling and console commands the app has
+/// whether or not there's a window, all but the devices they're fed from.
+pub(super) fn add_core(app: &mut App, config: Config) {
+    if config.debug.is_deterministic() {
+        info!("Running deterministically");
+        app.insert_resource(Deterministic);
+    }
+
+    app.insert_resource(ChecksumLog::new(&config.debug))
+        .insert_resource(Screen::new(config.texel_size))
+        .insert_resource(Profiler::new(config.debug.profiler))
+        .insert_resource(config)
+        .init_resource::<KeyInput>()
+        .init_resource::<Gamepad>()
+        .init_resource::<MouseInput>()
+        .init_resource::<TouchInput>()
+        .init_resource::<Bindings>()
+        .init_resource::<InputBuffer>()
+        .init_resource::<Actions>()
+        .init_resource::<Gestures>()
+        .init_resource::<AppExit>()
+        .init_resource::<Pool>()
+        .init_resource::<Language>()
+        .init_resource::<Console>()
+        .init_resource::<Errors>()
+        .init_resource::<FrameStep>()
+        .add_event::<GestureEvent>()
+        .add_event::<WindowResized>()
+        .add_event::<FocusChanged>()
+        .add_event::<QuitRequested>()
+        .add_systems(
+            PreUpdate,
+            apply_language.run_if(resource_changed::<Language>),
+        )
+        .add_systems(PostUpdate, (exit_on_quit_request, show_errors))
+        .add_systems(OnUpdate(GameState::Error), update_error_screen)
+        .add_console_command("help", "", "lists every command", help_command)
+        .add_console_command("clear", "", "empties the console", clear_command)
+        .add_console_command(
+            "state",
+            "[name]",
+            "switches state, or shows the stack",
+            state_command,
+        )
+        .add_console_command(
+            "time_scale",
+            "[scale]",
+            "speeds up or slows down the simulation",
+            time_scale_command,
+        )
+        .add_console_command("fps", "", "toggles the FPS counter", fps_command)
+        .add_console_command("profiler", "", "toggles the profiler", profiler_command)
+        .add_console_command(
+            "pause",
+            "",
+            "freezes or thaws the simulation",
+            pause_command,
+        )
+        .add_console_command(
+            "step",
+            "[ticks]",
+            "freezes the simulation and advances it a tick, or several",
+            step_command,
+        )
+        .add_console_command(
+            "entities",
+            "[component]",
+            "counts entities by their components",
+            entities_command,
+        );
+}
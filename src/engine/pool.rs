@@ -0,0 +1,79 @@
+//! Recycling for short-lived entities. Bullets and particles come and go by
+//! the hundred every second, so rather than despawning one, a [`Pooled`]
+//! entity is stripped back to just that component and handed out again by
+//! the next [`Pool::spawn`].
+//!
+//! Anything that ends an entity's life should [`release`](ReleaseExt::release)
+//! it instead of despawning it. Entities that aren't pooled are despawned
+//! as before, so callers don't need to know which kind they have.
+
+use bevy_ecs::{prelude::*, system::EntityCommands, world::Command};
+
+/// Marks an entity spawned from the [`Pool`]. A free one has no other
+/// components and is invisible to every query.
+#[derive(Component, Debug)]
+pub struct Pooled {
+    free: bool,
+}
+
+/// Free [`Pooled`] entities, reused most recently freed first.
+#[derive(Resource, Default)]
+pub struct Pool {
+    free: Vec<Entity>,
+}
+
+impl Pool {
+    /// Entities waiting to be reused.
+    pub fn free_count(&self) -> usize {
+        self.free.len()
+    }
+
+    /// Spawns `bundle`, reusing a free entity if there is one.
+    pub fn spawn<'a>(&mut self, cmds: &'a mut Commands, bundle: impl Bundle) -> EntityCommands<'a> {
+        let bundle = (bundle, Pooled { free: false });
+
+        match self.free.pop() {
+            Some(entity) => {
+                let mut entity = cmds.entity(entity);
+                entity.insert(bundle);
+                entity
+            }
+            None => cmds.spawn(bundle),
+        }
+    }
+}
+
+/// Frees a [`Pooled`] entity for reuse, or despawns anything else. Entities
+/// already freed or despawned are left alone, so releasing twice is fine.
+pub struct Release(pub Entity);
+
+impl Command for Release {
+    fn apply(self, world: &mut World) {
+        let Ok(mut entity) = world.get_entity_mut(self.0) else {
+            return;
+        };
+
+        match entity.get::<Pooled>() {
+            Some(Pooled { free: true }) => {}
+            Some(Pooled { free: false }) => {
+                entity.retain::<Pooled>();
+                entity.insert(Pooled { free: true });
+                world.resource_mut::<Pool>().free.push(self.0);
+            }
+            None => entity.despawn(),
+        }
+    }
+}
+
+pub trait ReleaseExt {
+    /// Ends the entity's life, returning it to the [`Pool`] if it came
+    /// from there.
+    fn release(&mut self);
+}
+
+impl ReleaseExt for EntityCommands<'_> {
+    fn release(&mut self) {
+        let entity = self.id();
+        self.commands().queue(Release(entity));
+    }
+}
@@ -0,0 +1,409 @@
+//! Where each frame's time goes. bevy_ecs opens a tracing span around every
+//! system it runs, and the [`SpanTimer`] installed by [`install_profiler`]
+//! adds up how long each was open. The app wraps every schedule in a
+//! [`scope`] of its own. Once a frame both are gathered into the
+//! [`Profiler`] for the overlay, along with the frame time and entity count.
+//!
+//! Nothing is timed while the overlay is closed, so it costs next to nothing
+//! the rest of the time. [`PROFILER_KEY`] opens and closes it.
+//!
+//! Being the tracing subscriber, the [`SpanTimer`] also passes on bevy_ecs'
+//! warnings and errors to the log, which would otherwise be dropped.
+
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, VecDeque},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use bevy_ecs::prelude::*;
+use macroquad::{miniquad::date, prelude::*};
+use tracing::{
+    Dispatch, Event, Id, Metadata, Subscriber,
+    field::{Field, Visit},
+    span::{Attributes, Record},
+    subscriber::Interest,
+};
+
+use super::{Bench, CurrentState, GameState, KeyInput, Pool};
+
+/// Opens and closes the overlay, from anywhere.
+pub const PROFILER_KEY: KeyCode = KeyCode::F3;
+
+/// Frames kept for the frame time graph.
+pub const FRAME_HISTORY: usize = 120;
+
+/// How far each frame moves the averages shown, so the lists stay readable
+/// instead of flickering.
+const SMOOTHING: f32 = 0.1;
+
+/// Averages below this many seconds are dropped, so systems that stopped
+/// running fall off the list.
+const MIN_SECONDS: f32 = 1e-7;
+
+static TIMINGS: Timings = Timings::new();
+
+thread_local! {
+    /// Spans open on this thread and the time each was entered.
+    static OPEN: RefCell<Vec<(u64, f64)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Seconds spent in each span and scope since the last frame was gathered.
+#[derive(Default)]
+struct Totals {
+    systems: BTreeMap<Arc<str>, f64>,
+    /// In the order they first ran.
+    scopes: Vec<(&'static str, f64)>,
+}
+
+/// What the [`SpanTimer`] and every [`Scope`] add to.
+struct Timings {
+    enabled: AtomicBool,
+    /// The system each span times, by span id less one.
+    names: Mutex<Vec<Arc<str>>>,
+    totals: Mutex<Totals>,
+}
+
+impl Timings {
+    const fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            names: Mutex::new(Vec::new()),
+            totals: Mutex::new(Totals {
+                systems: BTreeMap::new(),
+                scopes: Vec::new(),
+            }),
+        }
+    }
+
+    fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    fn take(&self) -> Totals {
+        std::mem::take(&mut *self.totals.lock().unwrap())
+    }
+}
+
+fn is_system_span(metadata: &Metadata) -> bool {
+    metadata.is_span() && metadata.name() == "system" && metadata.target().starts_with("bevy_ecs")
+}
+
+/// Warnings and errors, such as a system skipped for want of a parameter.
+fn is_logged_event(metadata: &Metadata) -> bool {
+    metadata.is_event() && *metadata.level() <= tracing::Level::WARN
+}
+
+/// Pulls the system's name out of a span's fields.
+#[derive(Default)]
+struct NameVisitor(Option<String>);
+
+impl Visit for NameVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "name" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "name" {
+            self.0 = Some(format!("{:?}", value).trim_matches('"').to_string());
+        }
+    }
+}
+
+/// Pulls the message out of an event's fields.
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{:?}", value);
+        }
+    }
+}
+
+/// A system's type name without module paths, e.g.
+/// `cathedral::engine::timers::tick_cooldowns<cathedral::domain::Dash>` as
+/// `tick_cooldowns<Dash>`.
+pub fn short_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut ident = String::new();
+    let mut chars = name.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            ident.clear();
+        } else if c.is_alphanumeric() || c == '_' {
+            ident.push(c);
+        } else {
+            short.push_str(&ident);
+            short.push(c);
+            ident.clear();
+        }
+    }
+
+    short.push_str(&ident);
+    short
+}
+
+/// Times bevy_ecs' system spans into [`TIMINGS`] and logs warnings and
+/// errors. Every other span and event is turned away when its callsite
+/// registers, so they're never even built.
+struct SpanTimer;
+
+impl Subscriber for SpanTimer {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if is_system_span(metadata) || is_logged_event(metadata) {
+            Interest::always()
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        is_system_span(metadata) || is_logged_event(metadata)
+    }
+
+    fn new_span(&self, span: &Attributes) -> Id {
+        let mut visitor = NameVisitor::default();
+        span.record(&mut visitor);
+
+        let name = short_name(visitor.0.as_deref().unwrap_or("?"));
+        let mut names = TIMINGS.names.lock().unwrap();
+        names.push(name.into());
+
+        Id::from_u64(names.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, event: &Event) {
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        let metadata = event.metadata();
+        let level = if *metadata.level() == tracing::Level::ERROR {
+            log::Level::Error
+        } else {
+            log::Level::Warn
+        };
+
+        log::log!(target: metadata.target(), level, "{}", visitor.0);
+    }
+
+    fn enter(&self, span: &Id) {
+        if TIMINGS.is_enabled() {
+            OPEN.with_borrow_mut(|open| open.push((span.into_u64(), date::now())));
+        }
+    }
+
+    fn exit(&self, span: &Id) {
+        let id = span.into_u64();
+
+        // Spans entered before the overlay opened were never pushed.
+        let start = OPEN.with_borrow_mut(|open| match open.last() {
+            Some(&(top, start)) if top == id => {
+                open.pop();
+                Some(start)
+            }
+            _ => None,
+        });
+
+        let Some(start) = start else {
+            return;
+        };
+
+        let name = TIMINGS.names.lock().unwrap()[id as usize - 1].clone();
+        let mut totals = TIMINGS.totals.lock().unwrap();
+        *totals.systems.entry(name).or_default() += date::now() - start;
+    }
+}
+
+/// Routes bevy_ecs' spans to the profiler. Systems build their span when
+/// they're added, so this has to happen before any are.
+pub fn install_profiler() {
+    if tracing::dispatcher::set_global_default(Dispatch::new(SpanTimer)).is_err() {
+        warn!("Another tracing subscriber is installed, systems won't be profiled");
+    }
+}
+
+/// Times everything until it's dropped, under `name`.
+pub struct Scope {
+    name: &'static str,
+    start: Option<f64>,
+}
+
+pub fn scope(name: &'static str) -> Scope {
+    Scope {
+        name,
+        start: TIMINGS.is_enabled().then(date::now),
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            let seconds = date::now() - start;
+            let mut totals = TIMINGS.totals.lock().unwrap();
+
+            match totals
+                .scopes
+                .iter_mut()
+                .find(|(name, _)| *name == self.name)
+            {
+                Some((_, total)) => *total += seconds,
+                None => totals.scopes.push((self.name, seconds)),
+            }
+        }
+    }
+}
+
+/// Runs `run` inside a [`scope`].
+pub fn scoped<T>(name: &'static str, run: impl FnOnce() -> T) -> T {
+    let _scope = scope(name);
+    run()
+}
+
+/// What the overlay shows. Times are in seconds.
+#[derive(Resource)]
+pub struct Profiler {
+    enabled: bool,
+    /// How long each recent frame took, oldest first.
+    pub frames: VecDeque<f32>,
+    /// Average time per frame in each schedule, in the order they ran.
+    pub scopes: Vec<(&'static str, f32)>,
+    /// Average time per frame in each system.
+    pub systems: BTreeMap<Arc<str>, f32>,
+    pub entities: u32,
+    /// Entities waiting in the [`Pool`], counted in `entities`.
+    pub pooled: usize,
+}
+
+impl Profiler {
+    pub fn new(enabled: bool) -> Self {
+        let mut profiler = Self {
+            enabled: false,
+            frames: VecDeque::with_capacity(FRAME_HISTORY),
+            scopes: Vec::new(),
+            systems: BTreeMap::new(),
+            entities: 0,
+            pooled: 0,
+        };
+
+        profiler.set_enabled(enabled);
+        profiler
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Opening starts from a clean slate, so the overlay never shows numbers
+    /// from whenever it was last open.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.frames.clear();
+        self.scopes.clear();
+        self.systems.clear();
+        TIMINGS.enabled.store(enabled, Ordering::Relaxed);
+        TIMINGS.take();
+    }
+
+    /// The `count` systems taking longest, longest first.
+    pub fn worst(&self, count: usize) -> Vec<(&str, f32)> {
+        let mut systems: Vec<_> = self
+            .systems
+            .iter()
+            .map(|(name, seconds)| (name.as_ref(), *seconds))
+            .collect();
+
+        systems.sort_by(|a, b| b.1.total_cmp(&a.1));
+        systems.truncate(count);
+        systems
+    }
+
+    fn record(&mut self, frame_time: f32, totals: Totals) {
+        if self.frames.len() == FRAME_HISTORY {
+            self.frames.pop_front();
+        }
+
+        self.frames.push_back(frame_time);
+
+        for (name, seconds) in totals.scopes {
+            match self.scopes.iter_mut().find(|(scope, _)| *scope == name) {
+                Some((_, average)) => *average += (seconds as f32 - *average) * SMOOTHING,
+                None => self.scopes.push((name, seconds as f32)),
+            }
+        }
+
+        for (name, average) in self.systems.iter_mut() {
+            let seconds = totals.systems.get(name).copied().unwrap_or_default();
+            *average += (seconds as f32 - *average) * SMOOTHING;
+        }
+
+        for (name, seconds) in totals.systems {
+            self.systems.entry(name).or_insert(seconds as f32);
+        }
+
+        self.systems.retain(|_, average| *average >= MIN_SECONDS);
+    }
+}
+
+impl Default for Profiler {
+    fn default() -> Self {
+        Self::new(false)
+    }
+}
+
+pub fn toggle_profiler(keys: Res<KeyInput>, mut profiler: ResMut<Profiler>) {
+    if keys.is_pressed(PROFILER_KEY) {
+        let enabled = !profiler.is_enabled();
+        profiler.set_enabled(enabled);
+    }
+}
+
+pub fn profiler_enabled(profiler: Res<Profiler>) -> bool {
+    profiler.is_enabled()
+}
+
+/// Gathers the frame's timings into the [`Profiler`], and into the
+/// [`Bench`] during a benchmark. Runs once the frame has been drawn, from
+/// [`App::render`](super::App::render).
+pub fn record_frame(world: &mut World) {
+    let entities = world.entities().len();
+    let pooled = world.get_resource::<Pool>().map_or(0, Pool::free_count);
+    let benchmarking = world
+        .get_resource::<CurrentState>()
+        .is_some_and(|state| state.contains(GameState::Playing));
+    let profiling = world
+        .get_resource::<Profiler>()
+        .is_some_and(Profiler::is_enabled);
+
+    let totals = TIMINGS.take();
+
+    if let Some(mut bench) = world.get_resource_mut::<Bench>() {
+        // Timing stays on for the whole run, whatever the overlay does.
+        TIMINGS.enabled.store(true, Ordering::Relaxed);
+
+        if benchmarking {
+            bench.record(get_frame_time(), entities, &totals.scopes, &totals.systems);
+        }
+    }
+
+    if !profiling {
+        return;
+    }
+
+    let mut profiler = world.resource_mut::<Profiler>();
+    profiler.entities = entities;
+    profiler.pooled = pooled;
+    profiler.record(get_frame_time(), totals);
+}
@@ -0,0 +1,53 @@
+//! The input side of recording and replaying a run. Each frame's actions,
+//! frame time and pointer are captured as an [`InputFrame`]; while an
+//! [`InputOverride`] is present, the input systems and the clock read it
+//! instead of the devices, so the same frames play out the same way.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{Action, Actions, MouseInput, Time};
+
+/// One frame of input, as the game saw it.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct InputFrame {
+    /// Unscaled frame time.
+    pub dt: f32,
+    pub down: Vec<Action>,
+    pub pressed: Vec<Action>,
+    pub released: Vec<Action>,
+    pub stick: (f32, f32),
+    /// Mouse position in texels, for aiming.
+    pub pointer: (f32, f32),
+}
+
+impl InputFrame {
+    pub fn capture(actions: &Actions, time: &Time, mouse: &MouseInput) -> Self {
+        Self {
+            dt: time.unscaled_dt,
+            down: actions.down.iter().copied().collect(),
+            pressed: actions.pressed.iter().copied().collect(),
+            released: actions.released.iter().copied().collect(),
+            stick: actions.stick.into(),
+            pointer: mouse.position.into(),
+        }
+    }
+
+    pub(super) fn apply(&self, actions: &mut Actions) {
+        actions.down = self.down.iter().copied().collect();
+        actions.pressed = self.pressed.iter().copied().collect();
+        actions.released = self.released.iter().copied().collect();
+        actions.stick = self.stick.into();
+    }
+}
+
+/// The frame of input to use this frame in place of the devices.
+#[derive(Resource)]
+pub struct InputOverride(pub InputFrame);
+
+impl InputOverride {
+    pub fn pointer(&self) -> Vec2 {
+        self.0.pointer.into()
+    }
+}
@@ -0,0 +1,100 @@
+use bevy_ecs::prelude::*;
+use serde::{Deserialize, Serialize};
+
+const PCG_MULTIPLIER: u64 = 6364136223846793005;
+
+/// Environment variable that, when set, fixes the seed used at startup.
+pub const SEED_ENV_VAR: &str = "CATHEDRAL_SEED";
+
+/// Seeded PCG32 generator. All gameplay randomness goes through this
+/// resource so a run can be reproduced from its seed, and the full state
+/// can be saved and restored mid-run.
+#[derive(Resource, Clone, Debug, Hash, Serialize, Deserialize)]
+pub struct Rng {
+    seed: u64,
+    state: u64,
+    inc: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = Self {
+            seed,
+            state: 0,
+            inc: (seed << 1) | 1,
+        };
+
+        rng.next_u32();
+        rng.state = rng.state.wrapping_add(seed);
+        rng.next_u32();
+        rng
+    }
+
+    /// Seeds from [`SEED_ENV_VAR`] if it is set, otherwise from the clock.
+    pub fn from_env_or_time() -> Self {
+        let seed = Self::env_seed().unwrap_or_else(|| macroquad::miniquad::date::now().to_bits());
+
+        Self::new(seed)
+    }
+
+    /// The seed in [`SEED_ENV_VAR`], if it's set to one.
+    pub fn env_seed() -> Option<u64> {
+        std::env::var(SEED_ENV_VAR)
+            .ok()
+            .and_then(|s| s.parse().ok())
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn next_u32(&mut self) -> u32 {
+        let old = self.state;
+        self.state = old.wrapping_mul(PCG_MULTIPLIER).wrapping_add(self.inc);
+
+        let xorshifted = (((old >> 18) ^ old) >> 27) as u32;
+        let rot = (old >> 59) as u32;
+        xorshifted.rotate_right(rot)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        ((self.next_u32() as u64) << 32) | self.next_u32() as u64
+    }
+
+    /// Uniform in `[0, 1)`.
+    pub fn next_f32(&mut self) -> f32 {
+        (self.next_u32() >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Uniform in `[low, high)`.
+    pub fn gen_range<T: RandomRange>(&mut self, low: T, high: T) -> T {
+        T::gen_range(self, low, high)
+    }
+}
+
+pub trait RandomRange: Sized {
+    fn gen_range(rng: &mut Rng, low: Self, high: Self) -> Self;
+}
+
+impl RandomRange for f32 {
+    fn gen_range(rng: &mut Rng, low: Self, high: Self) -> Self {
+        low + (high - low) * rng.next_f32()
+    }
+}
+
+macro_rules! impl_random_range_int {
+    ($($t:ty),*) => {
+        $(impl RandomRange for $t {
+            fn gen_range(rng: &mut Rng, low: Self, high: Self) -> Self {
+                if high <= low {
+                    return low;
+                }
+
+                let span = high.abs_diff(low) as u64;
+                low.wrapping_add((rng.next_u32() as u64 % span) as $t)
+            }
+        })*
+    };
+}
+
+impl_random_range_int!(i32, u32, usize);
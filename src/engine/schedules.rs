@@ -0,0 +1,88 @@
+use bevy_ecs::{
+    prelude::*,
+    schedule::{ExecutorKind, InternedScheduleLabel, ScheduleLabel},
+};
+
+/// Runs once from [`App::startup`](super::App::startup), before the initial
+/// state is entered. Plugins create materials and other one-time resources
+/// here.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Startup;
+
+/// Runs at the very start of every frame. Event buffers are swapped here.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct First;
+
+/// Runs every frame after [`First`], before any state schedules. Time, input
+/// and screen size are refreshed here.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PreUpdate;
+
+/// Runs zero or more times per frame at a constant rate (see
+/// [`FIXED_TIMESTEP`](super::FIXED_TIMESTEP)). Movement, spawning and
+/// collisions live here so they behave the same at any frame rate.
+///
+/// The only schedule that runs systems in parallel, so systems here must
+/// not touch macroquad's input, window or drawing. Ones whose order changes
+/// the outcome, like those drawing from the [`Rng`](super::Rng) or spawning
+/// entities, have to be ordered explicitly or replays stop matching.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FixedUpdate;
+
+/// Runs every frame before the top state's `OnUpdate` schedule,
+/// regardless of which states are active.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Update;
+
+/// Runs last every frame, just before pending state transitions are applied.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PostUpdate;
+
+/// Runs once per frame after all update schedules, with the camera bound to
+/// the main render target. All drawing happens here.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Render;
+
+/// Coarse phases every schedule is split into. Each schedule runs its sets
+/// in declaration order, so a new system only has to pick the phase it
+/// belongs to instead of being slotted into a `.chain()`.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    /// Reads the outside world: time, keyboard, window size.
+    Input,
+    /// Moves entities and advances their per-tick state.
+    Simulation,
+    /// Detects collisions and reacts to them.
+    Collision,
+    /// Spawns new entities, so they are first simulated on the next tick.
+    Spawning,
+    /// Draws the world.
+    Render,
+    /// Draws menus and overlays on top of the world.
+    UI,
+}
+
+/// Creates a schedule with the [`GameSet`] ordering already configured.
+/// Everything but [`FixedUpdate`] runs on the main thread, since macroquad
+/// panics if input or drawing is touched from any other.
+pub fn new_schedule(label: InternedScheduleLabel) -> Schedule {
+    let mut schedule = Schedule::new(label);
+
+    if label != FixedUpdate.intern() {
+        schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+    }
+
+    schedule.configure_sets(
+        (
+            GameSet::Input,
+            GameSet::Simulation,
+            GameSet::Collision,
+            GameSet::Spawning,
+            GameSet::Render,
+            GameSet::UI,
+        )
+            .chain(),
+    );
+
+    schedule
+}
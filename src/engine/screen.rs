@@ -0,0 +1,39 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::WindowResized;
+
+/// Size of the pixel render target, in texels.
+#[derive(Resource)]
+pub struct Screen {
+    pub width: usize,
+    pub height: usize,
+    /// Window pixels per texel, from [`Config`](super::Config).
+    pub texel_size: u32,
+}
+
+impl Screen {
+    pub fn new(texel_size: u32) -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            texel_size,
+        }
+    }
+}
+
+pub fn get_preferred_size(texel_size: u32) -> IVec2 {
+    ivec2(
+        (screen_width() / texel_size as f32) as i32,
+        (screen_height() / texel_size as f32) as i32,
+    )
+}
+
+pub fn update_screen(mut screen: ResMut<Screen>, mut ev_resized: EventReader<WindowResized>) {
+    let Some(resized) = ev_resized.read().last() else {
+        return;
+    };
+
+    screen.width = (resized.width / screen.texel_size as f32) as usize;
+    screen.height = (resized.height / screen.texel_size as f32) as usize;
+}
@@ -0,0 +1,274 @@
+use std::collections::VecDeque;
+
+use bevy_ecs::{prelude::*, schedule::ScheduleLabel};
+
+use super::ReleaseExt;
+
+#[derive(Default, Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum GameState {
+    /// Waits for everything loading into [`Assets`](super::Assets) before
+    /// showing the main menu.
+    Loading,
+    #[default]
+    MainMenu,
+    Playing,
+    Paused,
+    GameOver,
+    /// Typing a name for a new high score, on top of `GameOver`.
+    EnterName,
+    /// Summary of the sector about to be played, on top of `Playing`.
+    SectorIntro,
+    /// Spending credits on upgrades after a boss, on top of `Playing`.
+    Shop,
+    /// Spending stardust on permanent unlocks, on top of `MainMenu`.
+    Hangar,
+    /// The scripted tutorial, underneath the `Playing` it runs in.
+    Tutorial,
+    /// Preferences, on top of `MainMenu`.
+    Settings,
+    /// Rebinding actions, on top of `Settings`.
+    Controls,
+    /// Typing a seed for the next run, on top of `MainMenu`.
+    EnterSeed,
+    /// Picking a recorded run to watch, on top of `MainMenu`.
+    Replays,
+    /// Picking a save slot to continue from, on top of `MainMenu`, or to
+    /// save into, on top of `Paused`.
+    SaveSlots,
+    /// Something failed that the game can carry on from, on top of whatever
+    /// was running. See [`Errors`](super::Errors).
+    Error,
+}
+
+impl GameState {
+    pub const ALL: [GameState; 16] = [
+        GameState::Loading,
+        GameState::MainMenu,
+        GameState::Playing,
+        GameState::Paused,
+        GameState::GameOver,
+        GameState::EnterName,
+        GameState::SectorIntro,
+        GameState::Shop,
+        GameState::Hangar,
+        GameState::Tutorial,
+        GameState::Settings,
+        GameState::Controls,
+        GameState::EnterSeed,
+        GameState::Replays,
+        GameState::SaveSlots,
+        GameState::Error,
+    ];
+
+    /// Whether the state is a menu navigated with the menu actions, rather
+    /// than gameplay or typing.
+    pub fn is_menu(self) -> bool {
+        matches!(
+            self,
+            GameState::MainMenu
+                | GameState::Paused
+                | GameState::GameOver
+                | GameState::SectorIntro
+                | GameState::Shop
+                | GameState::Hangar
+                | GameState::Settings
+                | GameState::Controls
+                | GameState::Replays
+                | GameState::SaveSlots
+                | GameState::Error
+        )
+    }
+}
+
+/// Runs once when a state is pushed onto the stack.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnEnter(pub GameState);
+
+/// Runs every frame while a state is on top of the stack.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnUpdate(pub GameState);
+
+/// Runs once when a state is removed from the stack.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnExit(pub GameState);
+
+/// Runs when another state is pushed on top of this one.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnPause(pub GameState);
+
+/// Runs when the state above this one is popped and it is on top again.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct OnResume(pub GameState);
+
+/// Runs after every individual state enter or exit. Systems that react to
+/// any transition, rather than a specific state, live here.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StateTransition;
+
+/// Sent when a state is removed from the stack, after its `OnExit` ran.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct StateExited(pub GameState);
+
+/// Despawns the entity when the given state is exited. Pausing a state by
+/// pushing another on top does not count as exiting it.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct DespawnOnExit(pub GameState);
+
+enum Transition {
+    Set(GameState),
+    Reset(GameState),
+    Push(GameState),
+    Pop,
+}
+
+/// A stack of game states. Only the top state receives `OnUpdate`; states
+/// underneath stay alive (their entities are untouched) until popped or
+/// replaced. Systems queue transitions which are applied at the end of the
+/// frame by [`apply_state_transitions`].
+#[derive(Resource)]
+pub struct CurrentState {
+    stack: Vec<GameState>,
+    pending: VecDeque<Transition>,
+    generation: u64,
+}
+
+impl Default for CurrentState {
+    fn default() -> Self {
+        Self::new(GameState::default())
+    }
+}
+
+impl CurrentState {
+    /// A stack that starts in `initial` once [`enter_initial_state`] runs.
+    pub fn new(initial: GameState) -> Self {
+        Self {
+            stack: vec![initial],
+            pending: VecDeque::new(),
+            generation: 0,
+        }
+    }
+
+    /// The state on top of the stack.
+    pub fn get(&self) -> GameState {
+        *self.stack.last().unwrap()
+    }
+
+    /// Every state, bottom first.
+    pub fn stack(&self) -> &[GameState] {
+        &self.stack
+    }
+
+    pub fn contains(&self, state: GameState) -> bool {
+        self.stack.contains(&state)
+    }
+
+    /// Incremented every time a state is entered or exited.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Replaces the whole stack with `state`.
+    pub fn set(&mut self, state: GameState) {
+        self.pending.push_back(Transition::Set(state));
+    }
+
+    /// Like [`CurrentState::set`], but exits and re-enters `state` even if
+    /// it's already the only state, e.g. to start a run over.
+    pub fn reset(&mut self, state: GameState) {
+        self.pending.push_back(Transition::Reset(state));
+    }
+
+    /// Pushes `state` on top of the current one, pausing it.
+    pub fn push(&mut self, state: GameState) {
+        self.pending.push_back(Transition::Push(state));
+    }
+
+    /// Pops the top state, resuming the one beneath. The bottom state is
+    /// never popped.
+    pub fn pop(&mut self) {
+        self.pending.push_back(Transition::Pop);
+    }
+}
+
+pub fn despawn_on_exit(
+    mut cmds: Commands,
+    mut ev_exited: EventReader<StateExited>,
+    q_scoped: Query<(Entity, &DespawnOnExit)>,
+) {
+    for StateExited(state) in ev_exited.read() {
+        for (entity, scope) in q_scoped.iter() {
+            if scope.0 == *state {
+                cmds.entity(entity).release();
+            }
+        }
+    }
+}
+
+fn enter(world: &mut World, state: GameState) {
+    let mut current = world.resource_mut::<CurrentState>();
+    current.stack.push(state);
+    current.generation += 1;
+
+    let _ = world.try_run_schedule(OnEnter(state));
+    let _ = world.try_run_schedule(StateTransition);
+}
+
+fn exit(world: &mut World) {
+    let state = world.resource::<CurrentState>().get();
+    let _ = world.try_run_schedule(OnExit(state));
+
+    let mut current = world.resource_mut::<CurrentState>();
+    current.stack.pop();
+    current.generation += 1;
+
+    world.send_event(StateExited(state));
+    let _ = world.try_run_schedule(StateTransition);
+}
+
+pub fn enter_initial_state(world: &mut World) {
+    let initial = world.resource_mut::<CurrentState>().stack.pop().unwrap();
+    enter(world, initial);
+}
+
+pub fn run_state_update(world: &mut World) {
+    let current = world.resource::<CurrentState>().get();
+    let _ = world.try_run_schedule(OnUpdate(current));
+}
+
+/// Applies queued transitions in order. Transitions requested from within
+/// `OnEnter`/`OnExit`/`OnPause`/`OnResume` are applied right after.
+pub fn apply_state_transitions(world: &mut World) {
+    while let Some(transition) = world.resource_mut::<CurrentState>().pending.pop_front() {
+        match transition {
+            Transition::Set(next) | Transition::Reset(next) => {
+                let is_set = matches!(transition, Transition::Set(_));
+
+                if is_set && world.resource::<CurrentState>().stack == [next] {
+                    continue;
+                }
+
+                while !world.resource::<CurrentState>().stack.is_empty() {
+                    exit(world);
+                }
+
+                enter(world, next);
+            }
+            Transition::Push(next) => {
+                let top = world.resource::<CurrentState>().get();
+                let _ = world.try_run_schedule(OnPause(top));
+
+                enter(world, next);
+            }
+            Transition::Pop => {
+                if world.resource::<CurrentState>().stack.len() <= 1 {
+                    continue;
+                }
+
+                exit(world);
+
+                let resumed = world.resource::<CurrentState>().get();
+                let _ = world.try_run_schedule(OnResume(resumed));
+            }
+        }
+    }
+}
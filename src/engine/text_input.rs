@@ -0,0 +1,66 @@
+//! A single line of typed text with a blinking caret, shared by every
+//! screen that asks the player to type something.
+
+use macroquad::prelude::*;
+
+use super::KeyInput;
+
+/// Seconds the caret spends shown, then hidden.
+const CARET_BLINK: f32 = 0.5;
+
+#[derive(Clone, Debug)]
+pub struct TextInput {
+    pub text: String,
+    pub max_len: usize,
+    /// Maps each typed character to the one kept, or `None` to ignore it.
+    filter: fn(char) -> Option<char>,
+    blink: f32,
+}
+
+impl TextInput {
+    pub fn new(max_len: usize, filter: fn(char) -> Option<char>) -> Self {
+        Self {
+            text: String::new(),
+            max_len,
+            filter,
+            blink: 0.,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.blink = 0.;
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.text.chars().count() >= self.max_len
+    }
+
+    /// Applies this frame's typing and backspace. `dt` drives the caret.
+    pub fn update(&mut self, keys: &KeyInput, dt: f32) {
+        self.blink = (self.blink + dt) % (CARET_BLINK * 2.);
+
+        for c in keys.chars.iter().filter_map(|&c| (self.filter)(c)) {
+            if !self.is_full() {
+                self.text.push(c);
+                self.blink = 0.;
+            }
+        }
+
+        if keys.is_pressed(KeyCode::Backspace) && self.text.pop().is_some() {
+            self.blink = 0.;
+        }
+    }
+
+    /// The text padded with `pad` to its full length, with the caret
+    /// blinking in the next free slot. Typing keeps the caret shown.
+    pub fn display(&self, pad: char) -> String {
+        let len = self.text.chars().count();
+        let caret = if self.blink < CARET_BLINK { '_' } else { ' ' };
+
+        self.text
+            .chars()
+            .chain((len..self.max_len).map(|i| if i == len { caret } else { pad }))
+            .collect()
+    }
+}
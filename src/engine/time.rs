@@ -0,0 +1,161 @@
+use bevy_ecs::{
+    event::{EventRegistry, ShouldUpdateEvents},
+    prelude::*,
+};
+use macroquad::prelude::*;
+
+use super::{Deterministic, FixedUpdate, FrameStep, InputOverride};
+
+/// Rate at which the `FixedUpdate` schedule simulates the world.
+pub const FIXED_TIMESTEP: f32 = 1.0 / 60.0;
+
+/// Longest frame the simulation will try to catch up on. Anything beyond
+/// this is dropped so a stall doesn't turn into a burst of fixed ticks.
+pub const MAX_FRAME_TIME: f32 = 0.25;
+
+#[derive(Resource)]
+pub struct Time {
+    /// Seconds since the last frame, multiplied by `time_scale`.
+    pub dt: f32,
+    /// Seconds since the last frame, ignoring `time_scale`. Menus and other
+    /// UI that should not slow down with the game use this.
+    pub unscaled_dt: f32,
+    pub fps: i32,
+    /// Multiplier applied to `dt`, and therefore to how often `FixedUpdate`
+    /// runs. `1.0` is normal speed, `0.0` freezes the simulation.
+    pub time_scale: f32,
+    /// Multiplier on top of `time_scale` that gameplay leaves alone, set
+    /// from the console to watch things slowly or hurry them along.
+    pub debug_scale: f32,
+    /// Total scaled seconds since startup.
+    pub elapsed: f64,
+    /// Number of `FixedUpdate` ticks run since startup.
+    pub tick: u64,
+    /// Seconds simulated by each `FixedUpdate` tick.
+    pub fixed_dt: f32,
+    /// Frame time not yet consumed by fixed ticks.
+    pub accumulator: f32,
+}
+
+impl Default for Time {
+    fn default() -> Self {
+        Self {
+            dt: 0.,
+            unscaled_dt: 0.,
+            fps: 0,
+            time_scale: 1.,
+            debug_scale: 1.,
+            elapsed: 0.,
+            tick: 0,
+            fixed_dt: FIXED_TIMESTEP,
+            accumulator: 0.,
+        }
+    }
+}
+
+impl Time {
+    /// Takes one fixed step out of the accumulator, returning `false` when
+    /// there isn't enough time banked for another tick.
+    pub fn expend_fixed(&mut self) -> bool {
+        if self.accumulator < self.fixed_dt {
+            return false;
+        }
+
+        self.accumulator -= self.fixed_dt;
+        self.tick += 1;
+        true
+    }
+
+    /// Moves the clock on by a frame `unscaled_dt` long, or while `frozen`
+    /// keeps the simulation where it is.
+    pub fn advance(&mut self, unscaled_dt: f32, frozen: bool) {
+        self.unscaled_dt = unscaled_dt;
+
+        if frozen {
+            self.dt = 0.;
+            self.accumulator = 0.;
+        } else {
+            self.dt = unscaled_dt * self.time_scale.max(0.) * self.debug_scale;
+        }
+
+        self.elapsed += self.dt as f64;
+        self.accumulator += self.dt;
+    }
+}
+
+/// Deterministic mode ignores the clock and advances one fixed tick per
+/// frame. While [`FrameStep`] has the simulation frozen no scaled time
+/// passes at all.
+pub fn update_time(
+    mut time: ResMut<Time>,
+    input_override: Option<Res<InputOverride>>,
+    deterministic: Option<Res<Deterministic>>,
+    frame_step: Option<Res<FrameStep>>,
+) {
+    let unscaled_dt = match (input_override, deterministic) {
+        (Some(replayed), _) => replayed.0.dt,
+        (None, Some(_)) => time.fixed_dt,
+        (None, None) => get_frame_time().min(MAX_FRAME_TIME),
+    };
+
+    time.advance(
+        unscaled_dt,
+        frame_step.is_some_and(|frame_step| frame_step.is_paused()),
+    );
+    time.fps = get_fps();
+}
+
+fn run_fixed_tick(world: &mut World) {
+    let _ = world.try_run_schedule(FixedUpdate);
+
+    if let Some(mut registry) = world.get_resource_mut::<EventRegistry>() {
+        registry.should_update = ShouldUpdateEvents::Ready;
+    }
+}
+
+/// Runs `FixedUpdate` as many times as the accumulated frame time allows,
+/// or while frozen by [`FrameStep`], as many times as it's been stepped.
+pub fn run_fixed_update(world: &mut World) {
+    let steps = world
+        .get_resource_mut::<FrameStep>()
+        .filter(|frame_step| frame_step.is_paused())
+        .map(|mut frame_step| frame_step.take_steps());
+
+    if let Some(steps) = steps {
+        for _ in 0..steps {
+            let mut time = world.resource_mut::<Time>();
+            time.tick += 1;
+            time.elapsed += time.fixed_dt as f64;
+
+            run_fixed_tick(world);
+        }
+
+        return;
+    }
+
+    while world.resource_mut::<Time>().expend_fixed() {
+        run_fixed_tick(world);
+    }
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DD`.
+pub fn format_date(secs: u64) -> String {
+    // Howard Hinnant's days-to-civil algorithm.
+    let days = (secs / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Formats seconds since the Unix epoch as `HH:MM` UTC.
+pub fn format_time_of_day(secs: u64) -> String {
+    let minutes = secs % 86_400 / 60;
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
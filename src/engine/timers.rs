@@ -0,0 +1,79 @@
+use std::{marker::PhantomData, time::Duration};
+
+use bevy_ecs::prelude::*;
+
+use super::{ReleaseExt, Time};
+
+/// Time left before the entity is released.
+#[derive(Component, Clone, Copy)]
+pub struct Lifetime(pub Duration);
+
+/// Rate-limits an ability. `T` is a marker naming the ability, so one entity
+/// can carry independent cooldowns (e.g. `Cooldown<Shoot>` and
+/// `Cooldown<Dash>`). Each marker needs its own [`tick_cooldowns`] system.
+#[derive(Component)]
+pub struct Cooldown<T> {
+    pub duration: Duration,
+    remaining: Duration,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Cooldown<T> {
+    /// A cooldown that is ready immediately.
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            duration,
+            remaining: Duration::ZERO,
+            marker: PhantomData,
+        }
+    }
+
+    /// A cooldown that has just been triggered, so the ability first becomes
+    /// usable after `duration`.
+    pub fn started(duration: Duration) -> Self {
+        Self {
+            duration,
+            remaining: duration,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.remaining.is_zero()
+    }
+
+    /// Starts the cooldown if it is ready, returning whether the ability may
+    /// be used.
+    pub fn trigger(&mut self) -> bool {
+        if !self.is_ready() {
+            return false;
+        }
+
+        self.remaining = self.duration;
+        true
+    }
+}
+
+pub fn tick_lifetimes(
+    mut cmds: Commands,
+    mut q_lifetimes: Query<(Entity, &mut Lifetime)>,
+    time: Res<Time>,
+) {
+    let dt = Duration::from_secs_f32(time.fixed_dt);
+
+    for (entity, mut lifetime) in q_lifetimes.iter_mut() {
+        lifetime.0 = lifetime.0.saturating_sub(dt);
+
+        if lifetime.0.is_zero() {
+            cmds.entity(entity).release();
+        }
+    }
+}
+
+pub fn tick_cooldowns<T: 'static>(mut q_cooldowns: Query<&mut Cooldown<T>>, time: Res<Time>) {
+    let dt = Duration::from_secs_f32(time.fixed_dt);
+
+    for mut cooldown in q_cooldowns.iter_mut() {
+        cooldown.remaining = cooldown.remaining.saturating_sub(dt);
+    }
+}
@@ -0,0 +1,98 @@
+//! On-screen controls for touch devices. A finger landing on the left half
+//! of the screen becomes a virtual joystick centered where it landed, one on
+//! the right half holds fire, and the button at the top pauses. Like the
+//! gamepad, these feed [`Actions`](super::Actions) rather than being read by
+//! gameplay directly.
+
+use std::collections::HashSet;
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{Action, Screen, window_to_texels};
+
+/// How far, in texels, the joystick finger has to move for full deflection.
+pub const TOUCH_STICK_RADIUS: f32 = 24.;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TouchButton {
+    /// The right half of the screen.
+    Fire,
+    /// The button at the top, see [`pause_button_rect`].
+    Pause,
+}
+
+impl TouchButton {
+    /// Touch buttons aren't rebindable, so each always triggers the same
+    /// gameplay action and its menu counterpart.
+    pub fn actions(&self) -> [Action; 2] {
+        match self {
+            TouchButton::Fire => [Action::Fire, Action::Confirm],
+            TouchButton::Pause => [Action::Pause, Action::Back],
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct TouchInput {
+    /// Set by the first touch. Until then there's no sign of a touch screen
+    /// and the overlay stays hidden.
+    pub enabled: bool,
+    pub down: HashSet<TouchButton>,
+    /// Joystick deflection, up to length 1.
+    pub stick: Vec2,
+    /// Where the joystick finger landed, while it's down.
+    pub stick_origin: Option<Vec2>,
+    stick_touch: Option<u64>,
+}
+
+/// The pause button, centered at the top of the screen.
+pub fn pause_button_rect(screen: &Screen) -> Rect {
+    Rect::new(screen.width as f32 / 2. - 12., 2., 24., 18.)
+}
+
+/// Touches stay touches; otherwise the joystick finger would also click and
+/// fire.
+pub fn setup_touch() {
+    simulate_mouse_with_touch(false);
+}
+
+pub fn update_touch_input(mut touch: ResMut<TouchInput>, screen: Res<Screen>) {
+    let touches = touches();
+
+    if !touches.is_empty() {
+        touch.enabled = true;
+    }
+
+    touch.down.clear();
+    touch.stick = Vec2::ZERO;
+
+    let mut stick_held = false;
+
+    for finger in touches {
+        if matches!(finger.phase, TouchPhase::Ended | TouchPhase::Cancelled) {
+            continue;
+        }
+
+        let position = window_to_texels(finger.position, &screen);
+
+        if touch.stick_touch == Some(finger.id) {
+            let origin = touch.stick_origin.unwrap_or(position);
+            touch.stick = ((position - origin) / TOUCH_STICK_RADIUS).clamp_length_max(1.);
+            stick_held = true;
+        } else if pause_button_rect(&screen).contains(position) {
+            touch.down.insert(TouchButton::Pause);
+        } else if position.x >= screen.width as f32 / 2. {
+            touch.down.insert(TouchButton::Fire);
+        } else if touch.stick_touch.is_none() {
+            touch.stick_touch = Some(finger.id);
+            touch.stick_origin = Some(position);
+            stick_held = true;
+        }
+    }
+
+    if !stick_held {
+        touch.stick_touch = None;
+        touch.stick_origin = None;
+    }
+}
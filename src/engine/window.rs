@@ -0,0 +1,69 @@
+//! Window state macroquad only exposes by polling, turned into events.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::MAX_FRAME_TIME;
+
+/// The window changed size. Sizes are in window pixels, not texels.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct WindowResized {
+    pub width: f32,
+    pub height: f32,
+}
+
+/// The window lost or regained focus.
+///
+/// Macroquad does not report focus, so this is inferred from the frame loop
+/// stalling, which is what happens while the window is minimized or hidden
+/// on most platforms. A stall sends `focused: false` followed by
+/// `focused: true` on the same frame.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct FocusChanged {
+    pub focused: bool,
+}
+
+/// The user asked to close the window. Quitting is deferred to the end of
+/// the frame so systems get a chance to react.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct QuitRequested;
+
+/// Set to leave the main loop once the current frame has finished.
+#[derive(Resource, Default)]
+pub struct AppExit(pub bool);
+
+pub fn setup_window() {
+    prevent_quit();
+}
+
+pub fn poll_window_events(
+    mut last_size: Local<Vec2>,
+    mut ev_resized: EventWriter<WindowResized>,
+    mut ev_focus: EventWriter<FocusChanged>,
+    mut ev_quit: EventWriter<QuitRequested>,
+) {
+    let size = vec2(screen_width(), screen_height());
+
+    if size != *last_size {
+        *last_size = size;
+        ev_resized.send(WindowResized {
+            width: size.x,
+            height: size.y,
+        });
+    }
+
+    if get_frame_time() > MAX_FRAME_TIME {
+        ev_focus.send(FocusChanged { focused: false });
+        ev_focus.send(FocusChanged { focused: true });
+    }
+
+    if is_quit_requested() {
+        ev_quit.send(QuitRequested);
+    }
+}
+
+pub fn exit_on_quit_request(mut ev_quit: EventReader<QuitRequested>, mut exit: ResMut<AppExit>) {
+    if ev_quit.read().next().is_some() {
+        exit.0 = true;
+    }
+}
@@ -5,19 +5,22 @@ precision lowp float;
 in vec2 uv;
 
 uniform float idx;
+uniform vec2 cell; // texels per glyph, which varies between tilesets
 uniform vec4 fg1;
 uniform vec4 fg2;
 uniform vec4 outline;
 uniform vec4 bg;
+// Tunable, see rendering/uniforms.rs.
+uniform vec4 outline_tint;
 uniform sampler2D Texture;
 
 void main() {
-    vec2 uv_scaled = uv / 16.0; // atlas is 16x16
     float x = float(uint(idx) % 16u);
     float y = float(uint(idx) / 16u);
-    vec2 uv_offset = vec2(x, y) / 16.0;
 
-    vec2 tex_uv = uv_offset + uv_scaled;
+    // Stay half a texel inside the cell so neighbours never bleed in.
+    vec2 texel = clamp(uv * cell, vec2(0.5), cell - 0.5);
+    vec2 tex_uv = (vec2(x, y) * cell + texel) / (cell * 16.0); // atlas is 16x16
 
     vec4 tex = texture2D(Texture, tex_uv);
 
@@ -30,7 +33,7 @@ void main() {
     } else if (tex.r == 1 && tex.g == 1 && tex.b == 1 && fg2.a > 0) { // White (Secondary)
         gl_FragColor = fg2;
     } else if (tex.r == 1 && tex.g == 0 && tex.b == 0 && outline.a > 0) { // Red (Outline)
-        gl_FragColor = outline;
+        gl_FragColor = outline * outline_tint;
     } else { // debug
         gl_FragColor = vec4(1.0, 1.0, 0.0, 1.0);
     }
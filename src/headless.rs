@@ -0,0 +1,171 @@
+//! Runs the game without a window, one fixed tick at a time, for tests.
+//!
+//! [`Headless`] builds the world the way `main.rs` does, minus the window,
+//! renderer, audio and menus, starts a seeded run and then steps it with
+//! whatever actions a test holds down, e.g.
+//!
+//! ```no_run
+//! use cathedral::{domain::Score, engine::Action, headless::Headless};
+//!
+//! let mut game = Headless::new(42);
+//! game.run(600, &[Action::MoveLeft, Action::Fire]);
+//! println!("{} points", game.world().resource::<Score>().0);
+//! ```
+//!
+//! Nothing is read from or written to the player's files, so every run with
+//! the same seed and input plays out the same.
+
+use std::collections::BTreeSet;
+
+use bevy_ecs::{prelude::*, system::SystemState};
+use macroquad::prelude::*;
+
+use crate::{
+    domain::{CustomSeed, GameplayPlugin, Player, PrefabSpawner},
+    engine::{
+        Action, App, CurrentState, GameState, HeadlessPlugin, InputFrame, InputOverride, Time,
+        apply_state_transitions,
+    },
+    rendering::{ScreenShake, Shockwave, Starfield},
+};
+
+/// A run of the game driven by a test rather than a player.
+pub struct Headless {
+    pub app: App,
+    /// Where the mouse points, in texels, for anything aimed at it.
+    pub pointer: Vec2,
+    /// Actions held on the last tick, to tell what's newly pressed.
+    held: BTreeSet<Action>,
+}
+
+impl Headless {
+    /// Loads everything and starts a run on `seed`, one tick in and
+    /// ready to play.
+    pub fn new(seed: u64) -> Self {
+        let mut app = App::new();
+
+        app.insert_resource(CurrentState::new(GameState::MainMenu))
+            .add_plugins(HeadlessPlugin)
+            // Gameplay nudges these, but nothing here draws them.
+            .init_resource::<ScreenShake>()
+            .init_resource::<Shockwave>()
+            .insert_resource(Starfield {
+                material: None,
+                direction_modifier: 0.,
+                tint: Vec3::ONE,
+            })
+            .add_plugins(GameplayPlugin);
+
+        app.startup();
+        // Loads finish as the first frame starts.
+        app.update();
+        app.world.clear_trackers();
+
+        app.world.insert_resource(CustomSeed(Some(seed)));
+        app.world
+            .resource_mut::<CurrentState>()
+            .set(GameState::Playing);
+        apply_state_transitions(&mut app.world);
+
+        let mut game = Self {
+            app,
+            pointer: Vec2::ZERO,
+            held: BTreeSet::new(),
+        };
+
+        // The first tick moves into the first sector, whose summary then
+        // waits for a key.
+        game.step(&[]);
+        game.skip_intro();
+        game
+    }
+
+    /// Closes the summary shown on moving into a new sector, if that's
+    /// what's up, as Confirm would.
+    pub fn skip_intro(&mut self) {
+        let mut state = self.app.world.resource_mut::<CurrentState>();
+
+        if state.get() == GameState::SectorIntro {
+            state.pop();
+            apply_state_transitions(&mut self.app.world);
+        }
+    }
+
+    pub fn world(&self) -> &World {
+        &self.app.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    /// Fixed ticks run since the run started.
+    pub fn tick(&self) -> u64 {
+        self.app.world.resource::<Time>().tick
+    }
+
+    /// The player's ship, unless it's been destroyed.
+    pub fn player(&mut self) -> Option<Entity> {
+        self.app
+            .world
+            .query_filtered::<Entity, With<Player>>()
+            .iter(&self.app.world)
+            .next()
+    }
+
+    /// Spawns the prefab called `name` at `position`, as `spawn` in the
+    /// console does.
+    pub fn spawn(&mut self, name: &str, position: Vec2) -> Option<Entity> {
+        let world = &mut self.app.world;
+        let mut state = SystemState::<PrefabSpawner>::new(world);
+        let entity = state.get_mut(world).spawn_prefab(name, position);
+        state.apply(world);
+        entity
+    }
+
+    /// Runs one fixed tick with `down` held, pressing whatever wasn't held
+    /// on the tick before and releasing whatever no longer is.
+    pub fn step(&mut self, down: &[Action]) {
+        let down: BTreeSet<Action> = down.iter().copied().collect();
+        let frame = InputFrame {
+            dt: self.app.world.resource::<Time>().fixed_dt,
+            down: down.iter().copied().collect(),
+            pressed: down.difference(&self.held).copied().collect(),
+            released: self.held.difference(&down).copied().collect(),
+            stick: (0., 0.),
+            pointer: self.pointer.into(),
+        };
+
+        self.held = down;
+        self.step_frame(frame);
+    }
+
+    /// Runs one tick exactly as `frame` describes, e.g. one from a replay.
+    pub fn step_frame(&mut self, frame: InputFrame) {
+        self.app.world.insert_resource(InputOverride(frame));
+        self.app.update();
+        self.app.world.clear_trackers();
+    }
+
+    /// Runs `ticks` ticks with `down` held throughout.
+    pub fn run(&mut self, ticks: u32, down: &[Action]) {
+        for _ in 0..ticks {
+            self.step(down);
+        }
+    }
+
+    /// Runs ticks with `down` held until `done` is true of the world,
+    /// returning how many it took, or `None` if it still wasn't after
+    /// `max_ticks`.
+    pub fn run_until(
+        &mut self,
+        max_ticks: u32,
+        down: &[Action],
+        mut done: impl FnMut(&mut World) -> bool,
+    ) -> Option<u32> {
+        (1..=max_ticks).find(|_| {
+            self.step(down);
+            done(&mut self.app.world)
+        })
+    }
+}
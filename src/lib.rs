@@ -0,0 +1,9 @@
+//! Everything but the menus and the window: the engine, the renderer, audio
+//! and the gameplay itself. The game in `main.rs` builds on it, and so does
+//! the [`headless`] harness the integration tests drive it with.
+
+pub mod audio;
+pub mod domain;
+pub mod engine;
+pub mod headless;
+pub mod rendering;
@@ -1,325 +1,558 @@
 use bevy_ecs::prelude::*;
-use macroquad::{
-    miniquad::{BlendFactor, BlendState, BlendValue, Equation},
-    prelude::*,
-};
-
-use std::collections::HashSet;
-
-const STARFIELD_FRAGMENT_SHADER: &str = include_str!("starfield-shader.glsl");
-const STARFIELD_VERTEX_SHADER: &str = "#version 100
-attribute vec3 position;
-attribute vec2 texcoord;
-attribute vec4 color0;
-varying float iTime;
-
-uniform mat4 Model;
-uniform mat4 Projection;
-uniform vec4 _Time;
-
-void main() {
-    gl_Position = Projection * Model * vec4(position, 1);
-    iTime = _Time.x;
-}
-";
+use macroquad::prelude::*;
+
+use cathedral::{audio::*, domain::*, engine::*, rendering::*, t};
+
+/// Frames of the loading spinner, advanced eight times a second.
+const SPINNER: [&str; 4] = ["|", "/", "-", "\\"];
+
+fn render_loading(screen: Res<Screen>, assets: Res<Assets>, time: Res<Time>) {
+    let center = screen.width as f32 / 2.0;
+    let middle = screen.height as f32 / 2.0;
+    let failures: Vec<_> = assets.failures().collect();
+
+    if !failures.is_empty() {
+        let text = t!("loading.failed");
+        let text_dimensions = measure_text(&text, None, 16, 1.0);
+        draw_text(
+            &text,
+            center - text_dimensions.width / 2.0,
+            middle,
+            16.0,
+            RED,
+        );
 
-const GLYPH_FRAGMENT_SHADER: &str = include_str!("glyph-shader.glsl");
-const GLYPH_VERTEX_SHADER: &str = "#version 400
-attribute vec3 position;
-attribute vec2 texcoord;
+        // Errors can span lines, e.g. every path searched for a missing file.
+        let lines: Vec<String> = failures
+            .iter()
+            .flat_map(|(name, error)| {
+                let mut lines = error.lines();
+                let first = format!("{}: {}", name, lines.next().unwrap_or_default());
+                std::iter::once(first).chain(lines.map(|line| format!("  {}", line)))
+            })
+            .collect();
+
+        for (i, text) in lines.iter().enumerate() {
+            let text_dimensions = measure_text(text, None, 16, 1.0);
+            let y = middle + 24.0 + i as f32 * 16.0;
+
+            draw_text(text, center - text_dimensions.width / 2.0, y, 16.0, WHITE);
+        }
 
-varying lowp vec2 uv;
+        let text = t!("loading.quit");
+        let text_dimensions = measure_text(&text, None, 16, 1.0);
+        let y = middle + 40.0 + lines.len() as f32 * 16.0;
+        draw_text(&text, center - text_dimensions.width / 2.0, y, 16.0, GRAY);
+        return;
+    }
 
-uniform mat4 Model;
-uniform mat4 Projection;
+    let spinner = SPINNER[(time.elapsed * 8.0) as usize % SPINNER.len()];
+    let text = format!("{} {}", t!("loading.loading"), spinner);
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
+    draw_text(
+        &text,
+        center - text_dimensions.width / 2.0,
+        middle,
+        16.0,
+        WHITE,
+    );
+
+    let width = screen.width as f32 * 0.5;
+    let x = center - width / 2.0;
+    let y = middle + 8.0;
 
-void main() {
-    gl_Position = Projection * Model * vec4(position, 1);
-    uv = texcoord;
+    draw_rectangle(x, y, width, 4.0, DARKGRAY);
+    draw_rectangle(x, y, width * assets.progress(), 4.0, GOLD);
 }
-";
 
-const CRT_FRAGMENT_SHADER: &str = include_str!("crt-shader.glsl");
-const CRT_VERTEX_SHADER: &str = "#version 100
-attribute vec3 position;
-attribute vec2 texcoord;
-attribute vec4 color0;
+fn render_error(screen: Res<Screen>, errors: Res<Errors>) {
+    let center = screen.width as f32 / 2.0;
+    let middle = screen.height as f32 / 2.0;
 
-varying lowp vec2 uv;
-varying lowp vec4 color;
+    let text = t!("error.title");
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
+    draw_text(
+        &text,
+        center - text_dimensions.width / 2.0,
+        middle,
+        16.0,
+        RED,
+    );
 
-uniform mat4 Model;
-uniform mat4 Projection;
+    let lines: Vec<&str> = errors
+        .messages()
+        .iter()
+        .flat_map(|message| message.lines())
+        .collect();
 
-void main() {
-    gl_Position = Projection * Model * vec4(position, 1);
-    color = color0 / 255.0;
-    uv = texcoord;
-}
-";
+    for (i, text) in lines.iter().enumerate() {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+        let y = middle + 24.0 + i as f32 * 16.0;
 
-#[derive(Resource, Default)]
-struct GlyphMaterial {
-    pub material: Option<Material>,
-    pub texture: Option<Texture2D>,
-}
+        draw_text(text, center - text_dimensions.width / 2.0, y, 16.0, WHITE);
+    }
 
-#[derive(Resource, Default)]
-struct CurrentState {
-    pub previous: GameState,
-    pub current: GameState,
-    pub next: GameState,
-}
+    let mut prompts = vec![t!("error.quit")];
 
-#[derive(Resource, Default)]
-struct Screen {
-    pub width: usize,
-    pub height: usize,
-}
+    if errors.can_retry() {
+        prompts.insert(0, t!("error.retry"));
+    }
 
-#[derive(Resource, Default)]
-struct Time {
-    pub dt: f32,
-    pub fps: i32,
-}
+    for (i, text) in prompts.iter().enumerate() {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+        let y = middle + 40.0 + (lines.len() + i) as f32 * 16.0;
 
-#[derive(Resource, Default)]
-struct KeyInput {
-    pub down: HashSet<KeyCode>,
-    pub pressed: HashSet<KeyCode>,
+        draw_text(text, center - text_dimensions.width / 2.0, y, 16.0, GRAY);
+    }
 }
 
-impl KeyInput {
-    pub fn is_down(&self, key: KeyCode) -> bool {
-        self.down.contains(&key)
+fn update_main_menu(
+    keys: Res<KeyInput>,
+    mut actions: ResMut<Actions>,
+    mut state: ResMut<CurrentState>,
+    mut exit: ResMut<AppExit>,
+    mut difficulty: ResMut<Difficulty>,
+    mut mode: ResMut<GameMode>,
+) {
+    if actions.is_pressed(Action::Back) {
+        exit.0 = true;
     }
 
-    pub fn is_pressed(&self, key: KeyCode) -> bool {
-        self.pressed.contains(&key)
+    if actions.is_pressed(Action::MoveUp) {
+        *mode = mode.cycle(-1);
     }
-}
 
-#[derive(Component)]
-struct Player {
-    pub speed: f32,
-}
+    if actions.is_pressed(Action::MoveDown) {
+        *mode = mode.cycle(1);
+    }
 
-#[derive(Component)]
-struct Faller {
-    pub speed: f32,
-}
+    if actions.is_pressed(Action::MoveLeft) {
+        *difficulty = difficulty.step(-1);
+    }
 
-#[derive(Component)]
-struct Bullet {
-    pub speed: f32,
-}
+    if actions.is_pressed(Action::MoveRight) {
+        *difficulty = difficulty.step(1);
+    }
 
-#[derive(Component)]
-struct Glyph {
-    size: f32,
-    idx: usize,
-    x: f32,
-    y: f32,
-}
+    if keys.is_pressed(KeyCode::H) {
+        state.push(GameState::Hangar);
+    }
 
-impl Glyph {
-    fn collides_with(&self, other: &Self) -> bool {
-        self.rect().overlaps(&other.rect())
+    if keys.is_pressed(KeyCode::T) {
+        state.set(GameState::Tutorial);
     }
 
-    fn rect(&self) -> Rect {
-        Rect {
-            x: self.x - self.size / 2.0,
-            y: self.y - self.size / 2.0,
-            w: self.size,
-            h: self.size,
-        }
+    if keys.is_pressed(KeyCode::O) {
+        state.push(GameState::Settings);
     }
-}
 
-#[derive(Default, PartialEq, Eq, Clone, Copy)]
-enum GameState {
-    #[default]
-    MainMenu,
-    Playing,
-    Paused,
-    GameOver,
-}
+    if keys.is_pressed(KeyCode::R) {
+        state.push(GameState::EnterSeed);
+    }
 
-fn update_shapes(
-    mut cmds: Commands,
-    mut q_shapes: Query<(Entity, &Faller, &mut Glyph)>,
-    time: Res<Time>,
-    screen: Res<Screen>,
-) {
-    for (entity, faller, mut shape) in q_shapes.iter_mut() {
-        shape.y += faller.speed * time.dt;
+    if keys.is_pressed(KeyCode::V) {
+        state.push(GameState::Replays);
+    }
 
-        if shape.y > screen.height as f32 {
-            cmds.entity(entity).despawn();
-        }
+    if actions.consume(Action::Confirm) {
+        state.set(GameState::Playing);
     }
 }
 
-fn update_bullets(
-    mut cmds: Commands,
-    mut q_bullets: Query<(Entity, &Bullet, &mut Glyph)>,
-    time: Res<Time>,
-) {
-    for (entity, bullet, mut shape) in q_bullets.iter_mut() {
-        shape.y -= bullet.speed * time.dt;
-
-        if shape.y < 0. {
-            cmds.entity(entity).despawn();
-        }
+fn draw_high_scores(high_scores: &HighScores, center_x: f32, top: f32) {
+    for (rank, entry) in high_scores.entries.iter().enumerate() {
+        let text = format!(
+            "{:>2}. {} {:>7} {}",
+            rank + 1,
+            entry.name,
+            entry.score,
+            format_date(entry.date)
+        );
+        let text_dimensions = measure_text(&text, None, 16, 1.0);
+
+        draw_text(
+            &text,
+            center_x - text_dimensions.width / 2.0,
+            top + rank as f32 * 12.0,
+            16.0,
+            GRAY,
+        );
     }
 }
 
-fn check_collisions(
-    mut cmds: Commands,
-    q_bullets: Query<(Entity, &Glyph), With<Bullet>>,
-    q_fallers: Query<(Entity, &Glyph), With<Faller>>,
-    q_player: Single<(Entity, &Glyph), With<Player>>,
-    mut state: ResMut<CurrentState>,
+fn render_main_menu(
+    screen: Res<Screen>,
+    high_scores: Res<HighScores>,
+    difficulty: Res<Difficulty>,
+    mode: Res<GameMode>,
+    profile: Res<Profile>,
 ) {
-    for (e_bullet, s_bullet) in q_bullets.iter() {
-        for (e_faller, s_faller) in q_fallers.iter() {
-            if s_bullet.collides_with(s_faller) {
-                cmds.entity(e_bullet).despawn();
-                cmds.entity(e_faller).despawn();
-            }
-        }
-    }
+    let text = t!("menu.press_space");
+    let text_dimensions = measure_text(&text, None, 32, 1.0);
 
-    for (e_faller, s_faller) in q_fallers.iter() {
-        if s_faller.collides_with(q_player.1) {
-            cmds.entity(e_faller).despawn();
-            state.next = GameState::GameOver;
-        }
+    draw_text_ex(
+        &text,
+        screen.width as f32 / 2.0 - text_dimensions.width / 2.0,
+        screen.height as f32 / 3.0,
+        TextParams {
+            font: None,
+            font_size: 32,
+            font_scale: 1.0,
+            font_scale_aspect: 1.0,
+            rotation: 0.,
+            color: WHITE,
+        },
+    );
+
+    let options = [
+        has_save().then(|| t!("menu.continue")),
+        Some(format!("^ {} v", mode.name())),
+        Some(format!("< {} >", difficulty.name())),
+        Some(t!("menu.hangar", stardust = profile.stardust)),
+        Some(t!("menu.tutorial")),
+        Some(t!("menu.settings")),
+        Some(t!("menu.seeded_run")),
+        Some(t!("menu.watch_replay")),
+    ];
+
+    for (i, text) in options.iter().flatten().enumerate() {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+
+        draw_text(
+            text,
+            screen.width as f32 / 2.0 - text_dimensions.width / 2.0,
+            screen.height as f32 / 3.0 + 24.0 + i as f32 * 16.0,
+            16.0,
+            GOLD,
+        );
     }
+
+    draw_high_scores(
+        &high_scores,
+        screen.width as f32 / 2.0,
+        screen.height as f32 / 3.0 + 160.0,
+    );
 }
 
-fn spawn_shapes(mut cmds: Commands, screen: Res<Screen>) {
-    if rand::gen_range(0, 99) >= 95 {
-        let size = rand::gen_range(16.0, 64.0);
-
-        let min_x = size / 2.;
-        let max_x = screen.width as f32 - size / 2.;
-
-        cmds.spawn((
-            Glyph {
-                size,
-                idx: 25,
-                x: rand::gen_range(min_x, max_x),
-                y: -size,
-            },
-            Faller {
-                speed: rand::gen_range(50.0, 150.0),
-            },
-        ));
+fn render_hangar(screen: Res<Screen>, hangar: Res<Hangar>, profile: Res<Profile>) {
+    let center = screen.width as f32 / 2.0;
+    let top = screen.height as f32 / 4.0;
+
+    let header = [
+        (t!("hangar.title"), GOLD),
+        (t!("hangar.stardust", stardust = profile.stardust), WHITE),
+        (format!("< {} >", profile.ship.name()), GOLD),
+    ];
+
+    for (i, (text, color)) in header.iter().enumerate() {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+
+        draw_text(
+            text,
+            center - text_dimensions.width / 2.0,
+            top + i as f32 * 20.0,
+            16.0,
+            *color,
+        );
     }
-}
 
-fn update_time(mut time: ResMut<Time>) {
-    time.dt = get_frame_time();
-    time.fps = get_fps();
-}
+    for (i, unlock) in Unlock::ALL.into_iter().enumerate() {
+        let (price, color) = if profile.has(unlock) {
+            (t!("hangar.owned"), GRAY)
+        } else if profile.stardust >= unlock.cost() {
+            (unlock.cost().to_string(), WHITE)
+        } else {
+            (unlock.cost().to_string(), RED)
+        };
+        let cursor = if i == hangar.selected { ">" } else { " " };
+        let text = format!("{} {:<16} {:>5}", cursor, unlock.name(), price);
+        let text_dimensions = measure_text(&text, None, 16, 1.0);
+
+        draw_text(
+            &text,
+            center - text_dimensions.width / 2.0,
+            top + 72.0 + i as f32 * 16.0,
+            16.0,
+            color,
+        );
+    }
 
-fn update_key_input(mut keys: ResMut<KeyInput>) {
-    keys.down = get_keys_down();
-    keys.pressed = get_keys_pressed();
-}
+    let text = t!("hangar.hint");
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
 
-fn update_screen(mut screen: ResMut<Screen>) {
-    let screen_size = get_preferred_size(2);
-    screen.width = screen_size.x as usize;
-    screen.height = screen_size.y as usize;
+    draw_text(
+        &text,
+        center - text_dimensions.width / 2.0,
+        top + 88.0 + Unlock::ALL.len() as f32 * 16.0,
+        16.0,
+        WHITE,
+    );
 }
 
-fn update_player(
-    mut cmds: Commands,
-    keys: Res<KeyInput>,
-    q_player: Single<(&mut Glyph, &Player)>,
-    time: Res<Time>,
+fn render_settings(
     screen: Res<Screen>,
+    menu: Res<SettingsMenu>,
+    input_buffer: Res<InputBuffer>,
+    aim: Res<AimMode>,
+    audio: Res<AudioSettings>,
+    language: Res<Language>,
+    tileset: Res<Tileset>,
+    autosave: Res<Autosave>,
+    frame_cap: Res<FrameCap>,
 ) {
-    let (mut shape, player) = q_player.into_inner();
-
-    if keys.is_down(KeyCode::A) {
-        shape.x -= player.speed * time.dt;
+    let center = screen.width as f32 / 2.0;
+    let top = screen.height as f32 / 4.0;
+
+    let text = t!("settings.title");
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
+    draw_text(&text, center - text_dimensions.width / 2.0, top, 16.0, GOLD);
+
+    for (i, item) in SettingsItem::ALL.into_iter().enumerate() {
+        let cursor = if i == menu.selected { ">" } else { " " };
+        let text = match item {
+            SettingsItem::Controls => format!("{} {}", cursor, item.name()),
+            SettingsItem::InputBuffer => {
+                format!("{} {} < {} MS >", cursor, item.name(), input_buffer.0)
+            }
+            SettingsItem::Aim => format!("{} {} < {} >", cursor, item.name(), aim.name()),
+            SettingsItem::MasterVolume => {
+                format!("{} {} < {}% >", cursor, item.name(), audio.master)
+            }
+            SettingsItem::MusicVolume => {
+                format!("{} {} < {}% >", cursor, item.name(), audio.music)
+            }
+            SettingsItem::SfxVolume => format!("{} {} < {}% >", cursor, item.name(), audio.sfx),
+            SettingsItem::Mute => {
+                let muted = if audio.muted {
+                    "settings.on"
+                } else {
+                    "settings.off"
+                };
+                format!("{} {} < {} >", cursor, item.name(), t!(muted))
+            }
+            SettingsItem::Language => {
+                format!("{} {} < {} >", cursor, item.name(), language.name())
+            }
+            SettingsItem::Tileset => {
+                format!("{} {} < {} >", cursor, item.name(), tileset.name())
+            }
+            SettingsItem::Autosave => {
+                let enabled = if autosave.enabled {
+                    "settings.on"
+                } else {
+                    "settings.off"
+                };
+                format!("{} {} < {} >", cursor, item.name(), t!(enabled))
+            }
+            SettingsItem::FrameCap => {
+                format!("{} {} < {} >", cursor, item.name(), frame_cap.name())
+            }
+        };
+        let text_dimensions = measure_text(&text, None, 16, 1.0);
+
+        draw_text(
+            &text,
+            center - text_dimensions.width / 2.0,
+            top + 32.0 + i as f32 * 16.0,
+            16.0,
+            WHITE,
+        );
     }
+}
 
-    if keys.is_down(KeyCode::D) {
-        shape.x += player.speed * time.dt;
+fn render_controls(screen: Res<Screen>, menu: Res<ControlsMenu>, bindings: Res<Bindings>) {
+    let left = screen.width as f32 / 2.0 - 200.0;
+    let top = screen.height as f32 / 6.0;
+
+    draw_text(&t!("controls.title"), left, top, 16.0, GOLD);
+
+    let rows = Action::ALL
+        .into_iter()
+        .map(|action| {
+            let bound = if menu.capturing && menu.action() == Some(action) {
+                t!("controls.press_a_key")
+            } else {
+                bindings
+                    .get(action)
+                    .iter()
+                    .map(Binding::name)
+                    .collect::<Vec<_>>()
+                    .join(" / ")
+            };
+            format!("{:<12} {}", action.name(), bound)
+        })
+        .chain(std::iter::once(t!("controls.reset")));
+
+    for (i, row) in rows.enumerate() {
+        let (cursor, color) = if i == menu.selected {
+            (">", GOLD)
+        } else {
+            (" ", WHITE)
+        };
+
+        draw_text(
+            &format!("{cursor} {row}"),
+            left,
+            top + 24.0 + i as f32 * 16.0,
+            16.0,
+            color,
+        );
     }
 
-    if keys.is_down(KeyCode::W) {
-        shape.y -= player.speed * time.dt;
-    }
+    let footer = top + 40.0 + (Action::ALL.len() + 1) as f32 * 16.0;
 
-    if keys.is_down(KeyCode::S) {
-        shape.y += player.speed * time.dt;
+    if let Some(message) = &menu.message {
+        draw_text(message, left, footer, 16.0, RED);
     }
 
-    shape.x = clamp(shape.x, 0.0, screen.width as f32);
-    shape.y = clamp(shape.y, 0.0, screen.height as f32);
+    draw_text(&t!("controls.hint"), left, footer + 20.0, 16.0, WHITE);
+}
+
+fn render_replays(screen: Res<Screen>, browser: Res<ReplayBrowser>) {
+    let left = screen.width as f32 / 2.0 - 200.0;
+    let top = screen.height as f32 / 6.0;
+
+    draw_text(&t!("replays.title"), left, top, 16.0, GOLD);
 
-    if keys.is_pressed(KeyCode::Space) {
-        cmds.spawn((
-            Bullet {
-                speed: player.speed * 2.0,
-            },
-            Glyph {
-                idx: 22,
-                x: shape.x,
-                y: shape.y,
-                size: 5.0,
-            },
-        ));
+    if browser.entries.is_empty() {
+        draw_text(&t!("replays.empty"), left, top + 24.0, 16.0, WHITE);
     }
-}
 
-fn update_main_menu(keys: Res<KeyInput>, mut state: ResMut<CurrentState>, screen: Res<Screen>) {
-    if keys.is_pressed(KeyCode::Escape) {
-        std::process::exit(0);
+    for (i, entry) in browser.entries.iter().enumerate() {
+        let (cursor, color) = if i == browser.selected {
+            (">", GOLD)
+        } else {
+            (" ", WHITE)
+        };
+        let duration = entry.info.duration as u32;
+        let row = format!(
+            "{cursor} {} {:<9} {:<7} {:>7} {:>2}:{:02}",
+            format_date(entry.info.date),
+            entry.mode.name(),
+            entry.difficulty.name(),
+            entry.info.score,
+            duration / 60,
+            duration % 60,
+        );
+
+        draw_text(&row, left, top + 24.0 + i as f32 * 16.0, 16.0, color);
     }
 
-    if keys.is_pressed(KeyCode::Space) {
-        state.next = GameState::Playing;
+    let footer = top + 40.0 + browser.entries.len().max(1) as f32 * 16.0;
+    draw_text(&t!("replays.hint"), left, footer, 16.0, WHITE);
+}
+
+fn render_save_slots(screen: Res<Screen>, menu: Res<SlotMenu>) {
+    let left = screen.width as f32 / 2.0 - 200.0;
+    let top = screen.height as f32 / 6.0;
+
+    let (title, hint) = match menu.mode {
+        SlotMode::Load => (t!("slots.load_title"), t!("slots.load_hint")),
+        SlotMode::Save => (t!("slots.save_title"), t!("slots.save_hint")),
+    };
+
+    draw_text(&title, left, top, 16.0, GOLD);
+
+    for (i, slot) in menu.slots.iter().enumerate() {
+        let (cursor, color) = if i == menu.selected {
+            (">", GOLD)
+        } else {
+            (" ", WHITE)
+        };
+        let details = match slot {
+            Slot::Empty => t!("slots.empty"),
+            Slot::Unreadable => t!("slots.unreadable"),
+            Slot::Saved(info) => {
+                let playtime = info.playtime as u32;
+                t!(
+                    "slots.details",
+                    date = format!(
+                        "{} {}",
+                        format_date(info.date),
+                        format_time_of_day(info.date)
+                    ),
+                    score = info.score,
+                    wave = info.wave,
+                    time = format!("{}:{:02}", playtime / 60, playtime % 60)
+                )
+            }
+        };
+        let row = format!(
+            "{cursor} {:<8} {}",
+            t!("slots.slot", number = i + 1),
+            details
+        );
+
+        draw_text(&row, left, top + 24.0 + i as f32 * 16.0, 16.0, color);
     }
 
-    let text = "Press space";
-    let text_dimensions = measure_text(text, None, 32, 1.0);
+    let footer = top + 40.0 + menu.slots.len() as f32 * 16.0;
+    draw_text(&hint, left, footer, 16.0, WHITE);
+}
+
+/// Marks a run being played back, with where it's up to and how to
+/// control it.
+fn render_replay_overlay(screen: Res<Screen>, playback: Res<Playback>) {
+    let center = screen.width as f32 / 2.0;
+    let clock = |seconds: f32| format!("{}:{:02}", seconds as u32 / 60, seconds as u32 % 60);
+
+    let status = if playback.is_seeking() {
+        t!("replay.seeking")
+    } else if playback.paused {
+        t!("replay.paused")
+    } else if playback.fast_forward {
+        t!("replay.fast_forward")
+    } else {
+        t!("replay.title")
+    };
+    let text = format!(
+        "{} {} / {}",
+        status,
+        clock(playback.elapsed()),
+        clock(playback.duration())
+    );
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
+    draw_text(&text, center - text_dimensions.width / 2.0, 48.0, 16.0, RED);
 
-    draw_text_ex(
-        text,
-        screen.width as f32 / 2.0 - text_dimensions.width / 2.0,
-        screen.height as f32 / 2.0,
-        TextParams {
-            font: None,
-            font_size: 32,
-            font_scale: 1.0,
-            font_scale_aspect: 1.0,
-            rotation: 0.,
-            color: WHITE,
-        },
+    let text = t!("replay.hint");
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
+    draw_text(
+        &text,
+        center - text_dimensions.width / 2.0,
+        screen.height as f32 - 16.0,
+        16.0,
+        GRAY,
     );
 }
 
-fn update_paused(keys: Res<KeyInput>, mut state: ResMut<CurrentState>, screen: Res<Screen>) {
-    if keys.is_pressed(KeyCode::Escape) {
-        std::process::exit(0);
+fn update_paused(
+    keys: Res<KeyInput>,
+    mut actions: ResMut<Actions>,
+    mut state: ResMut<CurrentState>,
+    mut exit: ResMut<AppExit>,
+    playback: Option<Res<Playback>>,
+) {
+    // Escape stops a replay instead.
+    if keys.is_pressed(KeyCode::Escape) && playback.is_none() {
+        exit.0 = true;
     }
 
-    if keys.is_pressed(KeyCode::Space) {
-        state.next = GameState::Playing;
+    if actions.consume(Action::Confirm) {
+        state.pop();
     }
+}
+
+/// Save & Quit's second half, once the run is on disk.
+fn quit_to_menu(mut state: ResMut<CurrentState>) {
+    state.set(GameState::MainMenu);
+}
 
-    let text = "Paused";
-    let text_dimensions = measure_text(text, None, 32, 1.0);
+fn render_paused(screen: Res<Screen>) {
+    let text = t!("paused.title");
+    let text_dimensions = measure_text(&text, None, 32, 1.0);
 
     draw_text(
-        text,
+        &text,
         screen.width as f32 / 2.0 - text_dimensions.width / 2.0,
         screen.height as f32 / 2.0,
         32.0,
@@ -327,305 +560,412 @@ fn update_paused(keys: Res<KeyInput>, mut state: ResMut<CurrentState>, screen: R
     );
 }
 
-fn update_game_over(keys: Res<KeyInput>, mut state: ResMut<CurrentState>, screen: Res<Screen>) {
-    if keys.is_pressed(KeyCode::Space) {
-        state.next = GameState::MainMenu;
+fn update_game_over(mut actions: ResMut<Actions>, mut state: ResMut<CurrentState>) {
+    if actions.consume(Action::Confirm) {
+        state.set(GameState::MainMenu);
     }
+}
+
+fn render_game_over(
+    screen: Res<Screen>,
+    score: Res<Score>,
+    high_scores: Res<HighScores>,
+    victory: Res<Victory>,
+    stats: Res<RunStats>,
+    profile: Res<Profile>,
+) {
+    let center = screen.width as f32 / 2.0;
+    let top = screen.height as f32 / 4.0;
 
-    let text = "GAME OVER!";
-    let text_dimensions = measure_text(text, None, 16, 1.0);
+    let (text, color) = if victory.0 {
+        (t!("game_over.victory"), GOLD)
+    } else {
+        (t!("game_over.defeat"), RED)
+    };
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
 
     draw_text(
-        text,
-        screen.width as f32 / 2.0 - text_dimensions.width / 2.0,
-        screen.height as f32 / 2.0,
+        &text,
+        center - text_dimensions.width / 2.0,
+        top,
         16.0,
-        RED,
+        color,
     );
-}
 
-fn update_playing(keys: Res<KeyInput>, mut state: ResMut<CurrentState>) {
-    if keys.is_pressed(KeyCode::Escape) {
-        state.next = GameState::Paused;
+    let duration = stats.duration as u32;
+    let lifetime = &profile.lifetime;
+    let lines = [
+        t!("game_over.score", score = score.0),
+        t!(
+            "stats.shots",
+            shots = stats.shots,
+            accuracy = (stats.accuracy() * 100.).round()
+        ),
+        t!(
+            "stats.destroyed",
+            destroyed = stats.total_destroyed(),
+            damage = stats.damage_taken
+        ),
+        t!(
+            "stats.duration",
+            time = format!("{}:{:02}", duration / 60, duration % 60)
+        ),
+        stats
+            .most_destroyed(3)
+            .into_iter()
+            .map(|(name, count)| format!("{} {}", display_name(name), count))
+            .collect::<Vec<_>>()
+            .join("  "),
+        t!(
+            "stats.lifetime",
+            runs = lifetime.runs,
+            destroyed = lifetime.totals.total_destroyed(),
+            hours = format!("{:.1}", lifetime.totals.duration / 3600.)
+        ),
+    ];
+
+    for (i, text) in lines.iter().enumerate() {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+
+        draw_text(
+            text,
+            center - text_dimensions.width / 2.0,
+            top + 20.0 + i as f32 * 16.0,
+            16.0,
+            if i == 0 { WHITE } else { GRAY },
+        );
     }
+
+    draw_high_scores(&high_scores, center, top + 36.0 + lines.len() as f32 * 16.0);
 }
 
-fn in_state(state: GameState) -> impl Fn(Res<CurrentState>) -> bool {
-    move |res| res.current == state && res.next == state && res.previous == state
+fn render_name_entry(screen: Res<Screen>, name_entry: Res<NameEntry>) {
+    let name = name_entry.name.display('_');
+
+    for (i, text) in [t!("name_entry.title"), name, t!("name_entry.hint")]
+        .iter()
+        .enumerate()
+    {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+
+        draw_text(
+            text,
+            screen.width as f32 / 2.0 - text_dimensions.width / 2.0,
+            screen.height as f32 / 2.0 + i as f32 * 20.0,
+            16.0,
+            GOLD,
+        );
+    }
 }
 
-fn enter_state(state: GameState) -> impl Fn(Res<CurrentState>) -> bool {
-    move |res| res.current == state && res.previous != state
+fn render_seed_entry(screen: Res<Screen>, entry: Res<SeedEntry>) {
+    let seed = entry.seed.display('.');
+    let hint = match entry.parse() {
+        Ok(Some(_)) => t!("seed.start"),
+        Ok(None) => t!("seed.random"),
+        Err(_) => t!("seed.too_large"),
+    };
+
+    for (i, text) in [t!("seed.title"), seed, hint].iter().enumerate() {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+
+        draw_text(
+            text,
+            screen.width as f32 / 2.0 - text_dimensions.width / 2.0,
+            screen.height as f32 / 2.0 + i as f32 * 20.0,
+            16.0,
+            GOLD,
+        );
+    }
 }
 
-fn leave_state(state: GameState) -> impl Fn(Res<CurrentState>) -> bool {
-    move |res| res.current == state && res.next != state
+fn render_sector_intro(screen: Res<Screen>, sector: Res<Sector>) {
+    let lines = [
+        t!("sector.title", number = sector.number),
+        t!(sector.palette),
+        t!("sector.hazards", hazard = sector.hazard_name()),
+        sector.roster.join(" ").to_uppercase(),
+        t!("sector.boss", boss = sector.boss.name()),
+        t!("sector.hint"),
+    ];
+
+    for (i, text) in lines.iter().enumerate() {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+
+        draw_text(
+            text,
+            screen.width as f32 / 2.0 - text_dimensions.width / 2.0,
+            screen.height as f32 / 3.0 + i as f32 * 20.0,
+            16.0,
+            if i == 0 { GOLD } else { WHITE },
+        );
+    }
 }
 
-fn update_states(mut state: ResMut<CurrentState>) {
-    state.previous = state.current;
-    state.current = state.next;
+fn render_shop(
+    screen: Res<Screen>,
+    shop: Res<Shop>,
+    credits: Res<Credits>,
+    q_player: Single<&Upgrades, With<Player>>,
+) {
+    let center = screen.width as f32 / 2.0;
+    let top = screen.height as f32 / 3.0;
+
+    for (i, text) in [t!("shop.title"), t!("shop.credits", credits = credits.0)]
+        .iter()
+        .enumerate()
+    {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+
+        draw_text(
+            text,
+            center - text_dimensions.width / 2.0,
+            top + i as f32 * 20.0,
+            16.0,
+            if i == 0 { GOLD } else { WHITE },
+        );
+    }
+
+    let mut bottom = top;
+
+    for (i, upgrade) in Upgrade::ALL.into_iter().enumerate() {
+        let card = card_rect(i, &screen);
+        let cost = q_player.cost(upgrade);
+        let border = if i == shop.selected { GOLD } else { GRAY };
+
+        bottom = card.bottom();
+
+        draw_rectangle(card.x, card.y, card.w, card.h, Color::new(0., 0., 0., 0.6));
+        draw_rectangle_lines(card.x, card.y, card.w, card.h, 2.0, border);
+
+        let lines = [
+            (upgrade.name(), WHITE),
+            (upgrade.description(), LIGHTGRAY),
+            (t!("shop.level", level = q_player.level(upgrade)), LIGHTGRAY),
+            (
+                t!("shop.cost", cost = cost),
+                if cost <= credits.0 { GOLD } else { RED },
+            ),
+        ];
+
+        for (j, (text, color)) in lines.iter().enumerate() {
+            draw_text(
+                text,
+                card.x + 8.0,
+                card.y + 16.0 + j as f32 * 16.0,
+                16.0,
+                *color,
+            );
+        }
+    }
+
+    let text = t!("shop.hint");
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
+
+    draw_text(
+        &text,
+        center - text_dimensions.width / 2.0,
+        bottom + 24.0,
+        16.0,
+        WHITE,
+    );
 }
 
-fn window_conf() -> Conf {
-    Conf {
-        window_title: "Cathedral".to_string(),
-        window_width: 800,
-        window_height: 600,
-        // high_dpi: todo!(),
-        fullscreen: false,
-        // sample_count: todo!(),
-        window_resizable: true,
-        // icon: todo!(),
-        // platform: todo!(),
-        ..Default::default()
+fn render_tutorial(screen: Res<Screen>, tutorial: Res<Tutorial>) {
+    let lines = [Some(tutorial.step.prompt()), tutorial.goal()];
+
+    for (i, text) in lines.iter().flatten().enumerate() {
+        let text_dimensions = measure_text(text, None, 16, 1.0);
+
+        draw_text(
+            text,
+            screen.width as f32 / 2.0 - text_dimensions.width / 2.0,
+            screen.height as f32 * 2.0 / 3.0 + i as f32 * 20.0,
+            16.0,
+            GOLD,
+        );
     }
 }
 
-fn get_preferred_size(texel_size: u32) -> IVec2 {
-    ivec2(
-        (screen_width() / texel_size as f32) as i32,
-        (screen_height() / texel_size as f32) as i32,
-    )
+fn update_playing(actions: Res<Actions>, mut state: ResMut<CurrentState>) {
+    if actions.is_pressed(Action::Pause) {
+        state.push(GameState::Paused);
+    }
 }
 
-fn render_fps(time: Res<Time>) {
-    draw_text(time.fps.to_string().as_str(), 16.0, 32.0, 16.0, GOLD);
+/// Pauses when the window goes away so the player doesn't come back to a
+/// lost run. The pause is pressed as an action, before the frame is
+/// recorded, so replays pause at the same point.
+fn pause_on_focus_lost(mut ev_focus: EventReader<FocusChanged>, mut actions: ResMut<Actions>) {
+    if ev_focus.read().any(|ev| !ev.focused) {
+        actions.pressed.insert(Action::Pause);
+    }
 }
 
-fn render_shapes(q_shapes: Query<&Glyph>, mat: Res<GlyphMaterial>) {
-    let material = mat.material.clone().unwrap();
-    let texture = mat.texture.clone().unwrap();
-    gl_use_material(&material);
-
-    for shape in q_shapes.iter() {
-        material.set_uniform("fg1", Color::from_rgba(10, 20, 255, 255));
-        material.set_uniform("fg2", Color::from_rgba(10, 255, 30, 255));
-        material.set_uniform("outline", Color::from_rgba(10, 255, 30, 255));
-        material.set_uniform("bg", Color::from_rgba(0, 0, 0, 0));
-        material.set_uniform("idx", shape.idx as f32);
-        let x = shape.x - shape.size / 2.0;
-        let y = shape.y - shape.size / 2.0;
-        draw_texture_ex(
-            &texture,
-            x,
-            y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(shape.size, shape.size)),
-                source: None,
-                rotation: 0.,
-                flip_x: false,
-                flip_y: false,
-                pivot: None,
-            },
+/// The achievement just unlocked, in the top right corner. Fades out over
+/// its last half second.
+fn render_toast(screen: Res<Screen>, toasts: Res<Toasts>) {
+    let Some((achievement, shown)) = toasts.current else {
+        return;
+    };
+
+    let alpha = ((TOAST_DURATION - shown) / 0.5).clamp(0., 1.);
+    let lines = [
+        (t!("toast.achievement"), GOLD),
+        (achievement.name(), WHITE),
+        (achievement.description(), GRAY),
+    ];
+    let width = lines
+        .iter()
+        .map(|(text, _)| measure_text(text, None, 16, 1.0).width)
+        .fold(0., f32::max)
+        + 16.0;
+    let left = screen.width as f32 - width - 8.0;
+
+    draw_rectangle(left, 8.0, width, 60.0, Color::new(0., 0., 0., 0.8 * alpha));
+    draw_rectangle_lines(left, 8.0, width, 60.0, 1.0, Color { a: alpha, ..GOLD });
+
+    for (i, (text, color)) in lines.iter().enumerate() {
+        draw_text(
+            text,
+            left + 8.0,
+            26.0 + i as f32 * 18.0,
+            16.0,
+            Color { a: alpha, ..*color },
         );
     }
-    gl_use_default_material();
 }
 
-fn setup_player(mut cmds: Commands, screen: Res<Screen>) {
-    cmds.spawn((
-        Player { speed: 200. },
-        Glyph {
-            size: 32.,
-            idx: 4,
-            x: screen.width as f32 / 2.0,
-            y: screen.height as f32 / 2.0,
-        },
-    ));
+fn window_conf() -> Conf {
+    Config::get().window_conf("Cathedral")
 }
 
-fn teardown(mut cmds: Commands, q_shapes: Query<Entity, With<Glyph>>) {
-    for e in q_shapes.iter() {
-        cmds.entity(e).despawn();
-    }
+fn render_fps(time: Res<Time>) {
+    draw_text(time.fps.to_string().as_str(), 16.0, 32.0, 16.0, GOLD);
 }
 
-#[macroquad::main(window_conf)]
-async fn main() {
-    let mut world = World::new();
-
-    world.init_resource::<Time>();
-    world.init_resource::<Screen>();
-    world.init_resource::<KeyInput>();
-    world.init_resource::<CurrentState>();
-    world.init_resource::<GlyphMaterial>();
-
-    let mut schedule_update = Schedule::default();
-    let mut schedule_post_update = Schedule::default();
-
-    schedule_post_update.add_systems(update_states);
-
-    schedule_update.add_systems((update_time, render_fps, update_key_input, update_screen).chain());
-
-    schedule_update.add_systems(
-        (
-            update_main_menu.run_if(in_state(GameState::MainMenu)),
-            update_paused.run_if(in_state(GameState::Paused)),
-            update_game_over.run_if(in_state(GameState::GameOver)),
-            setup_player.run_if(enter_state(GameState::Playing)),
-            update_playing.run_if(in_state(GameState::Playing)),
-            teardown.run_if(leave_state(GameState::MainMenu)),
-        )
-            .chain(),
-    );
+/// Menus, overlays and the transitions between them.
+struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Render,
+            (
+                render_fps.run_if(show_fps),
+                render_loading.run_if(in_state(GameState::Loading)),
+                render_error.run_if(in_state(GameState::Error)),
+                render_main_menu.run_if(in_state(GameState::MainMenu)),
+                render_paused.run_if(in_state(GameState::Paused)),
+                render_game_over.run_if(in_state(GameState::GameOver)),
+                render_name_entry.run_if(in_state(GameState::EnterName)),
+                render_seed_entry.run_if(in_state(GameState::EnterSeed)),
+                render_sector_intro.run_if(in_state(GameState::SectorIntro)),
+                render_shop
+                    .param_warn_once()
+                    .run_if(in_state(GameState::Shop)),
+                render_hangar.run_if(in_state(GameState::Hangar)),
+                render_settings.run_if(in_state(GameState::Settings)),
+                render_controls.run_if(in_state(GameState::Controls)),
+                render_replays.run_if(in_state(GameState::Replays)),
+                render_save_slots.run_if(in_state(GameState::SaveSlots)),
+                render_replay_overlay.run_if(in_stack(GameState::Playing).and(is_replaying)),
+                render_tutorial.run_if(in_state(GameState::Playing).and(in_tutorial)),
+                render_toast,
+            )
+                .in_set(GameSet::UI),
+        );
 
-    schedule_update.add_systems(
-        (
-            check_collisions,
-            spawn_shapes,
-            update_player,
-            update_shapes,
-            update_bullets,
-            render_shapes,
-        )
-            .run_if(in_state(GameState::Playing)),
-    );
+        app.add_systems(OnUpdate(GameState::Loading), finish_loading);
 
-    set_default_filter_mode(FilterMode::Nearest);
-    let texel_size = 2;
-    let mut pref_size: IVec2 = get_preferred_size(texel_size);
+        app.add_systems(
+            OnUpdate(GameState::MainMenu),
+            (
+                update_main_menu,
+                open_slots(SlotMode::Load).run_if(key_pressed(KeyCode::C).and(has_save)),
+            ),
+        );
 
-    let mut main_render_target = render_target(pref_size.x as u32, pref_size.y as u32);
-    main_render_target.texture.set_filter(FilterMode::Nearest);
+        app.add_systems(
+            OnUpdate(GameState::Playing),
+            update_playing.in_set(GameSet::Input),
+        );
 
-    let glyph_material = load_material(
-        ShaderSource::Glsl {
-            vertex: GLYPH_VERTEX_SHADER,
-            fragment: GLYPH_FRAGMENT_SHADER,
-        },
-        MaterialParams {
-            uniforms: vec![
-                UniformDesc::new("fg1", UniformType::Float4),
-                UniformDesc::new("fg2", UniformType::Float4),
-                UniformDesc::new("bg", UniformType::Float4),
-                UniformDesc::new("outline", UniformType::Float4),
-                UniformDesc::new("idx", UniformType::Float1),
-            ],
-            pipeline_params: PipelineParams {
-                color_blend: Some(BlendState::new(
-                    Equation::Add,
-                    BlendFactor::Value(BlendValue::SourceAlpha),
-                    BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
-                )),
-                ..Default::default()
-            },
-            ..Default::default()
-        },
-    )
-    .unwrap();
+        app.add_systems(
+            PreUpdate,
+            pause_on_focus_lost
+                .after(update_actions)
+                .before(record_input)
+                .run_if(in_state(GameState::Playing))
+                .run_if(not(is_replaying)),
+        );
 
-    let glyph_texture = load_texture("./src/cowboy.png").await.unwrap();
+        app.add_systems(
+            OnUpdate(GameState::Paused),
+            (
+                update_paused,
+                open_slots(SlotMode::Save)
+                    .run_if(key_pressed(KeyCode::S))
+                    .run_if(not(in_tutorial.or(is_replaying))),
+            ),
+        );
 
-    world.insert_resource(GlyphMaterial {
-        material: Some(glyph_material),
-        texture: Some(glyph_texture),
-    });
+        app.add_systems(
+            OnUpdate(GameState::SaveSlots),
+            (
+                update_slot_menu,
+                (save_game, quit_to_menu)
+                    .chain()
+                    .run_if(slot_chosen(SlotMode::Save)),
+                load_game.run_if(slot_chosen(SlotMode::Load)),
+            )
+                .chain(),
+        );
 
-    rand::srand(miniquad::date::now() as u64);
+        app.add_systems(OnUpdate(GameState::GameOver), update_game_over);
+    }
+}
 
-    let mut direction_modifier: f32 = 0.0;
+#[macroquad::main(window_conf)]
+async fn main() {
+    let mut app = App::new();
+    let bench = Config::get().launch.bench.is_some();
 
-    let starfield_render_target = render_target(800, 600);
-    starfield_render_target
-        .texture
-        .set_filter(FilterMode::Nearest);
+    // A benchmark starts from the defaults and leaves no saves behind.
+    if bench {
+        app.insert_resource(Ephemeral);
+    }
 
-    let starfield_material = load_material(
-        ShaderSource::Glsl {
-            vertex: STARFIELD_VERTEX_SHADER,
-            fragment: STARFIELD_FRAGMENT_SHADER,
-        },
-        MaterialParams {
-            uniforms: vec![
-                UniformDesc::new("iResolution", UniformType::Float2),
-                UniformDesc::new("direction_modifier", UniformType::Float1),
-            ],
-            ..Default::default()
-        },
-    )
-    .unwrap();
+    app.insert_resource(CurrentState::new(GameState::Loading))
+        .add_plugins(CorePlugin)
+        .add_plugins(RenderingPlugin)
+        .add_plugins(AudioPlugin)
+        .add_plugins(GameplayPlugin)
+        .add_plugins(MenuPlugin);
 
-    let crt_material = load_material(
-        ShaderSource::Glsl {
-            vertex: CRT_VERTEX_SHADER,
-            fragment: CRT_FRAGMENT_SHADER,
-        },
-        MaterialParams {
-            uniforms: vec![
-                UniformDesc::new("iResolution", UniformType::Float2),
-                UniformDesc::new("iTime", UniformType::Float1),
-            ],
-            ..Default::default()
-        },
-    )
-    .unwrap();
-
-    loop {
-        pref_size = get_preferred_size(texel_size);
-        let pref_size_f32 = pref_size.as_vec2();
-
-        // NOTE: it is important that the render target outlives the current frame.
-        let cur_target_size = main_render_target.texture.size().as_ivec2();
-        if cur_target_size != pref_size {
-            main_render_target = render_target(pref_size.x as u32, pref_size.y as u32);
-            main_render_target.texture.set_filter(FilterMode::Nearest);
-        }
+    #[cfg(feature = "dev")]
+    app.add_plugins(DevPlugin);
 
-        set_camera(&Camera2D {
-            zoom: vec2(1. / pref_size_f32.x * 2., 1. / pref_size_f32.y * 2.),
-            target: vec2(
-                (pref_size_f32.x * 0.5f32).floor(),
-                (pref_size_f32.y * 0.5f32).floor(),
-            ),
-            render_target: Some(main_render_target.clone()),
-            ..Default::default()
-        });
-
-        clear_background(BLACK);
-
-        starfield_material.set_uniform("iResolution", (pref_size_f32.x, pref_size_f32.y));
-        starfield_material.set_uniform("direction_modifier", direction_modifier);
-        gl_use_material(&starfield_material);
-        draw_texture_ex(
-            &main_render_target.texture,
-            0.,
-            0.,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(vec2(pref_size_f32.x, pref_size_f32.y)),
-                ..Default::default()
-            },
-        );
-        gl_use_default_material();
+    if bench {
+        app.add_plugins(BenchPlugin);
+    }
 
-        schedule_update.run(&mut world);
-        schedule_post_update.run(&mut world);
+    app.startup();
 
-        set_default_camera();
-        clear_background(ORANGE);
-        crt_material.set_uniform("iTime", get_time() as f32);
-        crt_material.set_uniform("iResolution", (pref_size_f32.x, pref_size_f32.y));
-        gl_use_material(&crt_material);
+    let mut limiter = FrameLimiter::default();
 
-        let screen_pad_x = (screen_width() - ((pref_size.x as f32) * (texel_size as f32))) * 0.5;
-        let screen_pad_y = (screen_height() - ((pref_size.y as f32) * (texel_size as f32))) * 0.5;
+    while !app.should_exit() {
+        for _ in 0..app.updates_per_frame() {
+            app.update();
 
-        let dest_size = pref_size_f32 * vec2(texel_size as f32, texel_size as f32);
+            if app.should_exit() {
+                break;
+            }
+        }
 
-        draw_texture_ex(
-            &main_render_target.texture,
-            screen_pad_x,
-            screen_pad_y,
-            WHITE,
-            DrawTextureParams {
-                dest_size: Some(dest_size),
-                ..Default::default()
-            },
-        );
-        gl_use_default_material();
+        app.render();
 
+        limiter.wait(app.frame_cap());
         next_frame().await
     }
 }
@@ -0,0 +1,88 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{ScreenShake, Shockwave, Uniforms};
+use crate::engine::{Screen, get_preferred_size};
+
+/// The low resolution render target everything is drawn to, and the CRT
+/// material used to scale it up to the window.
+#[derive(Resource)]
+pub struct Canvas {
+    pub target: RenderTarget,
+    /// `None` until it has compiled, scaling the canvas up plainly.
+    pub crt: Option<Material>,
+}
+
+fn new_target(size: IVec2) -> RenderTarget {
+    let target = render_target(size.x as u32, size.y as u32);
+    target.texture.set_filter(FilterMode::Nearest);
+    target
+}
+
+pub fn setup_canvas(mut cmds: Commands, screen: Res<Screen>) {
+    set_default_filter_mode(FilterMode::Nearest);
+
+    cmds.insert_resource(Canvas {
+        target: new_target(get_preferred_size(screen.texel_size)),
+        crt: None,
+    });
+}
+
+/// Resizes the canvas to match the window and points the camera at it.
+pub fn bind_canvas(mut canvas: ResMut<Canvas>, screen: Res<Screen>, shake: Res<ScreenShake>) {
+    let size = ivec2(screen.width as i32, screen.height as i32);
+
+    // NOTE: it is important that the render target outlives the current frame.
+    if canvas.target.texture.size().as_ivec2() != size {
+        canvas.target = new_target(size);
+    }
+
+    let size = size.as_vec2();
+    let center = (size * 0.5 + shake.offset()).floor();
+
+    set_camera(&Camera2D {
+        zoom: vec2(1. / size.x * 2., 1. / size.y * 2.),
+        target: center,
+        render_target: Some(canvas.target.clone()),
+        ..Default::default()
+    });
+
+    clear_background(BLACK);
+}
+
+/// Draws the canvas to the window through the CRT material.
+pub fn present_canvas(
+    canvas: Res<Canvas>,
+    screen: Res<Screen>,
+    shockwave: Res<Shockwave>,
+    uniforms: Res<Uniforms>,
+) {
+    let size = canvas.target.texture.size();
+    let texel_size = screen.texel_size as f32;
+
+    set_default_camera();
+    clear_background(ORANGE);
+
+    if let Some(crt) = &canvas.crt {
+        crt.set_uniform("iTime", get_time() as f32);
+        crt.set_uniform("iResolution", (size.x, size.y));
+        crt.set_uniform("iShockwave", shockwave.uniform(size));
+        uniforms.apply("crt", crt);
+        gl_use_material(crt);
+    }
+
+    let screen_pad_x = (screen_width() - size.x * texel_size) * 0.5;
+    let screen_pad_y = (screen_height() - size.y * texel_size) * 0.5;
+
+    draw_texture_ex(
+        &canvas.target.texture,
+        screen_pad_x,
+        screen_pad_y,
+        WHITE,
+        DrawTextureParams {
+            dest_size: Some(size * texel_size),
+            ..Default::default()
+        },
+    );
+    gl_use_default_material();
+}
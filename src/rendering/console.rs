@@ -0,0 +1,39 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::engine::{Console, LineKind, Screen};
+
+/// Lines of output shown above the input.
+const VISIBLE_LINES: usize = 12;
+
+const LINE_HEIGHT: f32 = 10.;
+const FONT_SIZE: f32 = 12.;
+
+/// The latest output and the line being typed, dropped down over the top of
+/// the screen while the [`Console`] is open.
+pub fn render_console(console: Res<Console>, screen: Res<Screen>) {
+    let x = 4.;
+    let width = screen.width as f32;
+    let height = (VISIBLE_LINES + 1) as f32 * LINE_HEIGHT + 6.;
+
+    draw_rectangle(0., 0., width, height, Color::new(0., 0., 0., 0.85));
+    draw_line(0., height, width, height, 1., GRAY);
+
+    // Output sits on top of the input, so the newest line is always nearest.
+    let shown = console.lines.len().min(VISIBLE_LINES);
+    let mut y = (VISIBLE_LINES - shown + 1) as f32 * LINE_HEIGHT;
+
+    for (kind, text) in console.lines.iter().skip(console.lines.len() - shown) {
+        let color = match kind {
+            LineKind::Input => GRAY,
+            LineKind::Output => WHITE,
+            LineKind::Error => RED,
+        };
+
+        draw_text(text, x, y, FONT_SIZE, color);
+        y += LINE_HEIGHT;
+    }
+
+    let input = format!("> {}", console.input.display(' '));
+    draw_text(&input, x, height - 4., FONT_SIZE, GOLD);
+}
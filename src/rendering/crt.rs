@@ -0,0 +1,25 @@
+use macroquad::prelude::*;
+
+use super::{CRT_FRAGMENT_SHADER, CRT_VERTEX_SHADER, tunable_uniforms};
+
+pub fn load_crt_material() -> Result<Material, String> {
+    load_material(
+        ShaderSource::Glsl {
+            vertex: CRT_VERTEX_SHADER,
+            fragment: CRT_FRAGMENT_SHADER,
+        },
+        MaterialParams {
+            uniforms: [
+                vec![
+                    UniformDesc::new("iResolution", UniformType::Float2),
+                    UniformDesc::new("iTime", UniformType::Float1),
+                    UniformDesc::new("iShockwave", UniformType::Float4),
+                ],
+                tunable_uniforms("crt"),
+            ]
+            .concat(),
+            ..Default::default()
+        },
+    )
+    .map_err(|e| format!("Failed to compile the CRT shader: {}", e))
+}
@@ -0,0 +1,85 @@
+//! Screen-wide effects applied when the canvas is bound and presented.
+//! Gameplay kicks them off; they wind down on their own.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::engine::Time;
+
+/// Furthest the camera is thrown at full trauma, in texels.
+const MAX_SHAKE: f32 = 6.;
+
+/// Trauma lost per second.
+const SHAKE_DECAY: f32 = 1.5;
+
+/// Seconds a shockwave takes to sweep outward.
+const SHOCKWAVE_DURATION: f32 = 0.6;
+
+/// Camera shake driven by a `trauma` value in `[0, 1]`. The offset grows
+/// with the square of trauma so small bumps stay subtle.
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    pub trauma: f32,
+    elapsed: f32,
+}
+
+impl ScreenShake {
+    pub fn add(&mut self, trauma: f32) {
+        self.trauma = (self.trauma + trauma).min(1.);
+    }
+
+    pub fn offset(&self) -> Vec2 {
+        // Incommensurate frequencies instead of the game's Rng, so shaking
+        // never disturbs the simulation.
+        let t = self.elapsed;
+        let jitter = vec2((t * 47.).sin(), (t * 61.).cos());
+
+        jitter * self.trauma * self.trauma * MAX_SHAKE
+    }
+}
+
+/// A ring of distortion expanding from `center` in canvas texels, drawn by
+/// the CRT pass.
+#[derive(Resource, Default)]
+pub struct Shockwave {
+    center: Vec2,
+    /// Seconds since the wave started, or `None` when there is no wave.
+    elapsed: Option<f32>,
+}
+
+impl Shockwave {
+    pub fn start(&mut self, center: Vec2) {
+        self.center = center;
+        self.elapsed = Some(0.);
+    }
+
+    /// `(center_u, center_v, radius, strength)` for the CRT shader, with the
+    /// center and radius in UV units. Strength is zero with no wave.
+    pub fn uniform(&self, size: Vec2) -> (f32, f32, f32, f32) {
+        let center = self.center / size;
+
+        match self.elapsed {
+            Some(elapsed) => {
+                let t = elapsed / SHOCKWAVE_DURATION;
+                (center.x, center.y, t * 1.5, 0.03 * (1. - t))
+            }
+            None => (center.x, center.y, 0., 0.),
+        }
+    }
+}
+
+/// Uses unscaled time, so effects keep their pace through slow motion.
+pub fn tick_effects(mut shake: ResMut<ScreenShake>, mut wave: ResMut<Shockwave>, time: Res<Time>) {
+    let dt = time.unscaled_dt;
+
+    shake.elapsed += dt;
+    shake.trauma = (shake.trauma - SHAKE_DECAY * dt).max(0.);
+
+    if let Some(elapsed) = wave.elapsed.as_mut() {
+        *elapsed += dt;
+
+        if *elapsed >= SHOCKWAVE_DURATION {
+            wave.elapsed = None;
+        }
+    }
+}
@@ -0,0 +1,28 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::engine::{FRAME_STEP_KEY, STEP_KEY, Screen, Time};
+
+const FONT_SIZE: f32 = 12.;
+
+/// The tick the simulation is frozen on and the keys that move it along,
+/// along the bottom while [`FrameStep`](crate::engine::FrameStep) has it
+/// frozen.
+pub fn render_frame_step(time: Res<Time>, screen: Res<Screen>) {
+    let text = format!(
+        "FROZEN tick {}  {:?} resume  {:?} step",
+        time.tick, FRAME_STEP_KEY, STEP_KEY
+    );
+    let width = measure_text(&text, None, FONT_SIZE as u16, 1.).width;
+    let x = (screen.width as f32 - width) / 2.;
+    let y = screen.height as f32 - 8.;
+
+    draw_rectangle(
+        x - 4.,
+        y - FONT_SIZE,
+        width + 8.,
+        FONT_SIZE + 4.,
+        Color::new(0., 0., 0., 0.75),
+    );
+    draw_text(&text, x, y, FONT_SIZE, GOLD);
+}
@@ -0,0 +1,269 @@
+use std::collections::{BTreeMap, HashMap};
+
+use bevy_ecs::prelude::*;
+use macroquad::{
+    miniquad::{BlendFactor, BlendState, BlendValue, Equation},
+    prelude::*,
+};
+
+use super::{
+    GLYPH_FRAGMENT_SHADER, GLYPH_VERTEX_SHADER, GlyphTileset, ScreenShake, Tileset, Uniforms,
+    cell_size, tunable_uniforms,
+};
+use crate::domain::Position;
+use crate::engine::{Assets, Handle, Screen};
+
+/// Texels past the edge of the screen a glyph may be and still be drawn, so
+/// nothing pops in at the border.
+const CULL_MARGIN: f32 = 16.;
+
+#[derive(Resource, Default)]
+pub struct GlyphMaterial {
+    pub material: Option<Material>,
+    /// Every [`Tileset`]'s atlas, loaded up front so switching is instant.
+    pub atlases: HashMap<Tileset, Handle<Texture2D>>,
+}
+
+/// A single tile from the glyph atlas, drawn centered on the entity's
+/// [`Position`].
+#[derive(Component, Clone, Copy)]
+pub struct Glyph {
+    pub size: f32,
+    pub idx: usize,
+    pub fg1: Color,
+    pub fg2: Color,
+    pub outline: Color,
+    pub bg: Color,
+}
+
+impl Glyph {
+    /// A glyph in the default palette.
+    pub fn new(idx: usize, size: f32) -> Self {
+        Self {
+            size,
+            idx,
+            fg1: Color::from_rgba(10, 20, 255, 255),
+            fg2: Color::from_rgba(10, 255, 30, 255),
+            outline: Color::from_rgba(10, 255, 30, 255),
+            bg: Color::from_rgba(0, 0, 0, 0),
+        }
+    }
+
+    fn key(&self, tileset: Option<Tileset>) -> GlyphKey {
+        GlyphKey {
+            tileset,
+            idx: self.idx,
+            colors: [self.fg1, self.fg2, self.outline, self.bg]
+                .map(|c| u32::from_be_bytes(c.into())),
+        }
+    }
+}
+
+/// Everything that is uploaded as a uniform for a glyph, so glyphs sharing a
+/// key can be drawn without touching the material in between.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct GlyphKey {
+    /// `None` follows the active [`Tileset`].
+    tileset: Option<Tileset>,
+    idx: usize,
+    colors: [u32; 4],
+}
+
+pub fn load_glyph_material() -> Result<Material, String> {
+    load_material(
+        ShaderSource::Glsl {
+            vertex: GLYPH_VERTEX_SHADER,
+            fragment: GLYPH_FRAGMENT_SHADER,
+        },
+        MaterialParams {
+            uniforms: [
+                vec![
+                    UniformDesc::new("fg1", UniformType::Float4),
+                    UniformDesc::new("fg2", UniformType::Float4),
+                    UniformDesc::new("bg", UniformType::Float4),
+                    UniformDesc::new("outline", UniformType::Float4),
+                    UniformDesc::new("idx", UniformType::Float1),
+                    UniformDesc::new("cell", UniformType::Float2),
+                ],
+                tunable_uniforms("glyph"),
+            ]
+            .concat(),
+            pipeline_params: PipelineParams {
+                color_blend: Some(BlendState::new(
+                    Equation::Add,
+                    BlendFactor::Value(BlendValue::SourceAlpha),
+                    BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )
+    .map_err(|e| format!("Failed to compile the glyph shader: {}", e))
+}
+
+/// Cached draw rects for every glyph, grouped by [`GlyphKey`] so uniforms are
+/// uploaded once per group. Kept up to date incrementally by
+/// [`sync_glyph_batch`] from change detection instead of being rebuilt from
+/// every entity each frame.
+#[derive(Resource, Default)]
+pub struct GlyphBatch {
+    entries: HashMap<Entity, GlyphKey>,
+    groups: BTreeMap<GlyphKey, HashMap<Entity, Rect>>,
+}
+
+impl GlyphBatch {
+    fn insert(&mut self, entity: Entity, key: GlyphKey, rect: Rect) {
+        if let Some(old_key) = self.entries.insert(entity, key)
+            && old_key != key
+        {
+            self.remove_from_group(entity, old_key);
+        }
+
+        self.groups.entry(key).or_default().insert(entity, rect);
+    }
+
+    fn remove(&mut self, entity: Entity) {
+        if let Some(key) = self.entries.remove(&entity) {
+            self.remove_from_group(entity, key);
+        }
+    }
+
+    fn remove_from_group(&mut self, entity: Entity, key: GlyphKey) {
+        if let Some(group) = self.groups.get_mut(&key) {
+            group.remove(&entity);
+
+            if group.is_empty() {
+                self.groups.remove(&key);
+            }
+        }
+    }
+}
+
+type GlyphItem<'a> = (Entity, &'a Position, &'a Glyph, Option<&'a GlyphTileset>);
+
+pub fn sync_glyph_batch(
+    mut batch: ResMut<GlyphBatch>,
+    q_changed: Query<GlyphItem, Or<(Changed<Position>, Changed<Glyph>, Changed<GlyphTileset>)>>,
+    q_glyphs: Query<GlyphItem>,
+    mut removed_glyphs: RemovedComponents<Glyph>,
+    mut removed_positions: RemovedComponents<Position>,
+    mut removed_tilesets: RemovedComponents<GlyphTileset>,
+) {
+    for entity in removed_glyphs.read().chain(removed_positions.read()) {
+        batch.remove(entity);
+    }
+
+    // Back to the active tileset, if the glyph itself is still around.
+    let reverted = removed_tilesets
+        .read()
+        .filter_map(|entity| q_glyphs.get(entity).ok());
+
+    for (entity, position, glyph, tileset) in q_changed.iter().chain(reverted) {
+        let rect = Rect::new(
+            position.0.x - glyph.size / 2.0,
+            position.0.y - glyph.size / 2.0,
+            glyph.size,
+            glyph.size,
+        );
+
+        batch.insert(entity, glyph.key(tileset.map(|t| t.0)), rect);
+    }
+}
+
+/// Uniforms are only uploaded when they differ from the last group's, as
+/// every upload breaks the batch. Groups are sorted by key, so ones sharing
+/// a tileset, glyph or colours are drawn one after another.
+pub fn render_shapes(
+    batch: Res<GlyphBatch>,
+    mat: Res<GlyphMaterial>,
+    assets: Res<Assets>,
+    active: Res<Tileset>,
+    screen: Res<Screen>,
+    shake: Res<ScreenShake>,
+    uniforms: Res<Uniforms>,
+    mut visible: Local<Vec<Rect>>,
+) {
+    let Some(material) = &mat.material else {
+        return;
+    };
+
+    // The area the camera sees, which moves with the shake.
+    let offset = shake.offset().floor();
+    let view = Rect::new(
+        offset.x - CULL_MARGIN,
+        offset.y - CULL_MARGIN,
+        screen.width as f32 + CULL_MARGIN * 2.,
+        screen.height as f32 + CULL_MARGIN * 2.,
+    );
+
+    uniforms.apply("glyph", material);
+    gl_use_material(material);
+
+    let mut atlas: Option<(Tileset, Option<&Texture2D>)> = None;
+    let mut uploaded: Option<GlyphKey> = None;
+
+    for (key, rects) in batch.groups.iter() {
+        visible.clear();
+        visible.extend(rects.values().filter(|rect| view.overlaps(rect)));
+
+        // A group with nothing on screen doesn't need its uniforms either.
+        if visible.is_empty() {
+            continue;
+        }
+
+        let tileset = key.tileset.unwrap_or(*active);
+
+        if atlas.is_none_or(|(current, _)| current != tileset) {
+            let texture = mat
+                .atlases
+                .get(&tileset)
+                .and_then(|atlas| assets.get(*atlas));
+
+            if let Some(texture) = texture {
+                material.set_uniform("cell", cell_size(texture));
+            }
+
+            atlas = Some((tileset, texture));
+        }
+
+        let Some((_, Some(texture))) = atlas else {
+            continue;
+        };
+
+        let colors = key.colors.map(|c| {
+            let [r, g, b, a] = c.to_be_bytes();
+            Color::from_rgba(r, g, b, a)
+        });
+
+        for (i, name) in ["fg1", "fg2", "outline", "bg"].into_iter().enumerate() {
+            if uploaded.is_none_or(|last| last.colors[i] != key.colors[i]) {
+                material.set_uniform(name, colors[i]);
+            }
+        }
+
+        if uploaded.is_none_or(|last| last.idx != key.idx) {
+            material.set_uniform("idx", key.idx as f32);
+        }
+
+        uploaded = Some(*key);
+
+        for rect in visible.iter() {
+            draw_texture_ex(
+                texture,
+                rect.x,
+                rect.y,
+                WHITE,
+                DrawTextureParams {
+                    dest_size: Some(rect.size()),
+                    source: None,
+                    rotation: 0.,
+                    flip_x: false,
+                    flip_y: false,
+                    pivot: None,
+                },
+            );
+        }
+    }
+    gl_use_default_material();
+}
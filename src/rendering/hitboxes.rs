@@ -0,0 +1,15 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::domain::{Collider, Player, Position};
+
+/// Every collider's box, the player's in green, drawn over the world for the
+/// `dev` hitboxes flag.
+pub fn render_hitboxes(q_colliders: Query<(&Position, &Collider, Has<Player>)>) {
+    for (position, collider, is_player) in q_colliders.iter() {
+        let rect = collider.rect(position);
+        let color = if is_player { GREEN } else { RED };
+
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, 1., color);
+    }
+}
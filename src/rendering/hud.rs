@@ -0,0 +1,220 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::{
+    domain::{
+        AimMode, Bombs, Boss, BossSegment, BulletTime, ChargeShot, Combo, Credits, Health, Lives,
+        MAX_CHARGE, Player, Score, Waves, Weapon, WeaponKind,
+    },
+    engine::{
+        CurrentState, GameState, MouseInput, Screen, TOUCH_STICK_RADIUS, TouchButton, TouchInput,
+        pause_button_rect,
+    },
+    t,
+};
+
+pub fn render_hud(
+    score: Res<Score>,
+    lives: Res<Lives>,
+    waves: Res<Waves>,
+    combo: Res<Combo>,
+    bombs: Res<Bombs>,
+    credits: Res<Credits>,
+    q_player: Option<Single<&Health, With<Player>>>,
+    screen: Res<Screen>,
+) {
+    let text = t!("hud.score", score = score.0);
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
+
+    draw_text(
+        &text,
+        screen.width as f32 - text_dimensions.width - 8.0,
+        16.0,
+        16.0,
+        WHITE,
+    );
+
+    let text = t!("hud.wave", wave = waves.number);
+    draw_text(&text, 8.0, 16.0, 16.0, WHITE);
+
+    let text = t!("hud.bombs", bombs = bombs.0);
+    draw_text(&text, 8.0, 28.0, 16.0, WHITE);
+
+    let text = t!("hud.credits", credits = credits.0);
+    draw_text(&text, 8.0, 40.0, 16.0, WHITE);
+
+    if combo.multiplier() > 1 {
+        let text = format!("x{}", combo.multiplier());
+        let text_dimensions = measure_text(&text, None, 16, 1.0);
+
+        draw_text(
+            &text,
+            screen.width as f32 - text_dimensions.width - 8.0,
+            52.0,
+            16.0,
+            GOLD,
+        );
+    }
+
+    let text = t!("hud.lives", lives = lives.0);
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
+
+    draw_text(
+        &text,
+        screen.width as f32 - text_dimensions.width - 8.0,
+        28.0,
+        16.0,
+        WHITE,
+    );
+
+    let Some(health) = q_player else {
+        return;
+    };
+
+    let text = t!("hud.health", current = health.current, max = health.max);
+    let text_dimensions = measure_text(&text, None, 16, 1.0);
+
+    draw_text(
+        &text,
+        screen.width as f32 - text_dimensions.width - 8.0,
+        40.0,
+        16.0,
+        WHITE,
+    );
+}
+
+/// A bar across the top of the screen showing the boss's remaining health,
+/// segments included.
+pub fn render_boss_health(
+    q_boss: Query<&Health, With<Boss>>,
+    q_segments: Query<&Health, With<BossSegment>>,
+    screen: Res<Screen>,
+) {
+    let (current, max) = q_boss
+        .iter()
+        .chain(q_segments.iter())
+        .fold((0, 0), |(current, max), health| {
+            (current + health.current, max + health.max)
+        });
+
+    if max == 0 {
+        return;
+    }
+
+    let width = screen.width as f32 * 0.5;
+    let x = (screen.width as f32 - width) / 2.0;
+    let y = 24.0;
+
+    draw_rectangle(x, y, width, 4.0, DARKGRAY);
+    draw_rectangle(x, y, width * current as f32 / max as f32, 4.0, RED);
+}
+
+/// The equipped weapon in the bottom left, with a charge meter for the
+/// charge gun.
+/// The slow motion meter, in the bottom right corner.
+pub fn render_bullet_time(bullet_time: Res<BulletTime>, screen: Res<Screen>) {
+    let width = 40.0;
+    let x = screen.width as f32 - width - 8.0;
+    let y = screen.height as f32 - 8.0;
+    let label = t!("hud.slow");
+    let label_width = measure_text(&label, None, 16, 1.0).width;
+    let color = if bullet_time.active { GOLD } else { VIOLET };
+
+    draw_text(&label, x - label_width - 6.0, y, 16.0, WHITE);
+    draw_rectangle(x, y - 6.0, width, 4.0, DARKGRAY);
+    draw_rectangle(x, y - 6.0, width * bullet_time.meter, 4.0, color);
+}
+
+pub fn render_weapon(q_player: Single<(&Weapon, &ChargeShot), With<Player>>, screen: Res<Screen>) {
+    let (weapon, charge) = *q_player;
+    let y = screen.height as f32 - 8.0;
+
+    let name = weapon.kind.name();
+    draw_text(&name, 8.0, y, 16.0, WHITE);
+
+    if weapon.kind == WeaponKind::Charge {
+        let x = 8.0 + measure_text(&name, None, 16, 1.0).width + 6.0;
+        let width = 40.0;
+
+        draw_rectangle(x, y - 6.0, width, 4.0, DARKGRAY);
+        draw_rectangle(x, y - 6.0, width * charge.0 / MAX_CHARGE, 4.0, SKYBLUE);
+    }
+}
+
+const TOUCH_OVERLAY: Color = Color::new(1.0, 1.0, 1.0, 0.25);
+
+/// Outlines of the touch controls, once the screen has been touched. The
+/// stick and fire button only show in play; the pause button doubles as
+/// back in menus.
+pub fn render_touch_controls(
+    touch: Res<TouchInput>,
+    state: Res<CurrentState>,
+    screen: Res<Screen>,
+) {
+    if !touch.enabled {
+        return;
+    }
+
+    let pause = pause_button_rect(&screen);
+    let playing = state.get() == GameState::Playing;
+    let label = if playing { "II" } else { "<" };
+    let label_width = measure_text(label, None, 16, 1.0).width;
+
+    draw_rectangle_lines(pause.x, pause.y, pause.w, pause.h, 1.0, TOUCH_OVERLAY);
+    draw_text(
+        label,
+        pause.center().x - label_width / 2.0,
+        pause.bottom() - 5.0,
+        16.0,
+        TOUCH_OVERLAY,
+    );
+
+    if !playing {
+        return;
+    }
+
+    let base = touch.stick_origin.unwrap_or(vec2(
+        TOUCH_STICK_RADIUS + 16.0,
+        screen.height as f32 - TOUCH_STICK_RADIUS - 24.0,
+    ));
+    let knob = base + touch.stick * TOUCH_STICK_RADIUS;
+
+    draw_circle_lines(base.x, base.y, TOUCH_STICK_RADIUS, 1.0, TOUCH_OVERLAY);
+    draw_circle(knob.x, knob.y, TOUCH_STICK_RADIUS / 3.0, TOUCH_OVERLAY);
+
+    let fire = vec2(
+        screen.width as f32 - TOUCH_STICK_RADIUS - 16.0,
+        screen.height as f32 - TOUCH_STICK_RADIUS - 24.0,
+    );
+    let color = if touch.down.contains(&TouchButton::Fire) {
+        GOLD.with_alpha(0.5)
+    } else {
+        TOUCH_OVERLAY
+    };
+    let label = t!("hud.fire");
+    let label_width = measure_text(&label, None, 16, 1.0).width;
+
+    draw_circle_lines(fire.x, fire.y, TOUCH_STICK_RADIUS, 1.0, color);
+    draw_text(
+        &label,
+        fire.x - label_width / 2.0,
+        fire.y + 4.0,
+        16.0,
+        color,
+    );
+}
+
+/// Where shots are headed when aiming with the mouse.
+pub fn render_crosshair(aim: Res<AimMode>, mouse: Res<MouseInput>) {
+    if *aim != AimMode::Mouse {
+        return;
+    }
+
+    let Vec2 { x, y } = mouse.position;
+
+    draw_circle_lines(x, y, 4.0, 1.0, RED);
+    draw_line(x - 7.0, y, x - 2.0, y, 1.0, RED);
+    draw_line(x + 2.0, y, x + 7.0, y, 1.0, RED);
+    draw_line(x, y - 7.0, x, y - 2.0, 1.0, RED);
+    draw_line(x, y + 2.0, x, y + 7.0, 1.0, RED);
+}
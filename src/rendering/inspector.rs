@@ -0,0 +1,94 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::{
+    domain::{FIELDS, Inspector, LIST_ROWS, ROW_HEIGHT, field_row_rect, list_row_rect, panel_rect},
+    engine::{MouseInput, Screen},
+};
+
+const FONT_SIZE: f32 = 12.;
+
+fn draw_row(text: &str, rect: Rect, color: Color) {
+    draw_text(
+        text,
+        rect.x + 4.,
+        rect.y + ROW_HEIGHT - 2.,
+        FONT_SIZE,
+        color,
+    );
+}
+
+/// The entity list and the selected entity's fields down the right edge,
+/// with the selected entity outlined where it is, while the [`Inspector`]
+/// is open.
+pub fn render_inspector(inspector: Res<Inspector>, screen: Res<Screen>, mouse: Res<MouseInput>) {
+    let panel = panel_rect(&screen);
+
+    draw_rectangle(
+        panel.x,
+        panel.y,
+        panel.w,
+        panel.h,
+        Color::new(0., 0., 0., 0.75),
+    );
+
+    if let Some(bounds) = inspector.bounds {
+        draw_rectangle_lines(
+            bounds.x - 1.,
+            bounds.y - 1.,
+            bounds.w + 2.,
+            bounds.h + 2.,
+            1.,
+            GOLD,
+        );
+    }
+
+    let title = format!("inspector {}", inspector.rows.len());
+    draw_row(&title, Rect::new(panel.x, 4., panel.w, ROW_HEIGHT), SKYBLUE);
+
+    let rows = inspector.rows.iter().skip(inspector.scroll).take(LIST_ROWS);
+
+    for (row, (entity, label)) in rows.enumerate() {
+        let rect = list_row_rect(row, &screen);
+        let color = if inspector.selected == Some(*entity) {
+            GOLD
+        } else if rect.contains(mouse.position) {
+            WHITE
+        } else {
+            GRAY
+        };
+
+        draw_row(&format!("{} {}", entity, label), rect, color);
+    }
+
+    let Some(selected) = inspector.selected else {
+        return;
+    };
+
+    let label = inspector.selected_label().unwrap_or_default();
+    let rect = list_row_rect(LIST_ROWS + 1, &screen);
+    draw_row(&format!("{} {}", selected, label), rect, SKYBLUE);
+
+    for (row, (field, value)) in inspector.values.iter().enumerate() {
+        let rect = field_row_rect(row, &screen);
+        let field = &FIELDS[*field];
+        let name = format!("{} {}", field.component, field.name);
+
+        let (value, color) = match &inspector.editing {
+            Some((editing, input)) if *editing == row => (input.display(' '), GOLD),
+            _ if rect.contains(mouse.position) => (format!("{:.2}", value), WHITE),
+            _ => (format!("{:.2}", value), GRAY),
+        };
+
+        draw_row(&name, rect, color);
+
+        let width = measure_text(&value, None, FONT_SIZE as u16, 1.).width;
+        draw_text(
+            &value,
+            rect.right() - width - 4.,
+            rect.y + ROW_HEIGHT - 2.,
+            FONT_SIZE,
+            color,
+        );
+    }
+}
@@ -0,0 +1,35 @@
+mod canvas;
+mod console;
+mod crt;
+mod effects;
+mod frame_step;
+mod glyph;
+#[cfg(feature = "dev")]
+mod hitboxes;
+mod hud;
+mod inspector;
+mod plugin;
+mod profiler;
+mod shaders;
+mod spritesheet;
+mod starfield;
+mod tileset;
+mod uniforms;
+
+pub use canvas::*;
+pub use console::*;
+pub use crt::*;
+pub use effects::*;
+pub use frame_step::*;
+pub use glyph::*;
+#[cfg(feature = "dev")]
+pub use hitboxes::*;
+pub use hud::*;
+pub use inspector::*;
+pub use plugin::*;
+pub use profiler::*;
+pub use shaders::*;
+pub use spritesheet::*;
+pub use starfield::*;
+pub use tileset::*;
+pub use uniforms::*;
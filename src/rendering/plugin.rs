@@ -0,0 +1,157 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{
+    Canvas, GlyphBatch, GlyphMaterial, ScreenShake, Shockwave, Starfield, Tileset, UniformPanel,
+    Uniforms, animate_sprites, bind_canvas, load_crt_material, load_glyph_material,
+    load_starfield_material, present_canvas, render_boss_health, render_bullet_time,
+    render_console, render_crosshair, render_frame_step, render_hud, render_inspector,
+    render_profiler, render_shapes, render_sprites, render_starfield, render_touch_controls,
+    render_uniform_panel, render_weapon, setup_canvas, sync_glyph_batch, tick_effects,
+    uniform_panel_open, uniforms_command, update_uniform_panel,
+};
+use crate::{
+    domain::inspector_open,
+    engine::{
+        App, Assets, Config, Errors, GameSet, GameState, Plugin, PreUpdate, Render, Startup,
+        console_open, frame_step_paused, in_stack, in_state, profiler_enabled, update_actions,
+        update_console, update_mouse_input,
+    },
+};
+
+/// The canvas, starfield background and glyph renderer.
+pub struct RenderingPlugin;
+
+fn setup_materials(
+    mut cmds: Commands,
+    mut glyph_material: ResMut<GlyphMaterial>,
+    mut assets: ResMut<Assets>,
+) {
+    glyph_material.atlases = Tileset::ALL
+        .into_iter()
+        .map(|tileset| (tileset, assets.load_texture(tileset.path())))
+        .collect();
+
+    cmds.insert_resource(Starfield {
+        material: None,
+        direction_modifier: 0.0,
+        tint: Vec3::ONE,
+    });
+}
+
+/// Compiles every shader that hasn't yet. Any that fail are raised
+/// together, and trying again comes back here for them.
+fn load_materials(
+    mut canvas: ResMut<Canvas>,
+    mut glyph_material: ResMut<GlyphMaterial>,
+    mut starfield: ResMut<Starfield>,
+    mut errors: ResMut<Errors>,
+) {
+    let materials: [(&mut Option<Material>, fn() -> Result<Material, String>); 3] = [
+        (&mut canvas.crt, load_crt_material),
+        (&mut glyph_material.material, load_glyph_material),
+        (&mut starfield.material, load_starfield_material),
+    ];
+    let mut failures = Vec::new();
+
+    for (material, load) in materials {
+        if material.is_none() {
+            match load() {
+                Ok(loaded) => *material = Some(loaded),
+                Err(e) => failures.push(e),
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        errors.raise(failures.join("\n"), Some(retry_materials));
+    }
+}
+
+fn retry_materials(world: &mut World) {
+    let _ = world.run_system_cached(load_materials);
+}
+
+impl Plugin for RenderingPlugin {
+    fn build(&self, app: &mut App) {
+        let uniforms = Uniforms::new(app.world.resource::<Config>());
+
+        app.insert_resource(uniforms)
+            .init_resource::<UniformPanel>()
+            .init_resource::<GlyphMaterial>()
+            .init_resource::<GlyphBatch>()
+            .init_resource::<ScreenShake>()
+            .init_resource::<Shockwave>()
+            .add_systems(
+                Startup,
+                (
+                    setup_canvas,
+                    setup_materials,
+                    load_materials.after(setup_canvas).after(setup_materials),
+                ),
+            )
+            .add_systems(
+                Render,
+                (
+                    tick_effects.in_set(GameSet::Input).before(bind_canvas),
+                    animate_sprites.in_set(GameSet::Input),
+                    bind_canvas.in_set(GameSet::Input),
+                    render_starfield.in_set(GameSet::Render),
+                    sync_glyph_batch
+                        .in_set(GameSet::Render)
+                        .before(render_shapes),
+                    (render_shapes, render_sprites)
+                        .chain()
+                        .in_set(GameSet::Render)
+                        .after(render_starfield)
+                        .run_if(in_stack(GameState::Playing)),
+                    (
+                        render_hud,
+                        render_boss_health,
+                        render_weapon.param_warn_once(),
+                        render_bullet_time,
+                    )
+                        .in_set(GameSet::UI)
+                        .run_if(in_stack(GameState::Playing)),
+                    render_crosshair
+                        .in_set(GameSet::UI)
+                        .run_if(in_state(GameState::Playing)),
+                    render_touch_controls.in_set(GameSet::UI),
+                    render_inspector
+                        .after(GameSet::UI)
+                        .before(render_profiler)
+                        .run_if(inspector_open),
+                    render_uniform_panel
+                        .after(GameSet::UI)
+                        .before(render_profiler)
+                        .run_if(uniform_panel_open),
+                    render_frame_step
+                        .after(GameSet::UI)
+                        .before(render_profiler)
+                        .run_if(frame_step_paused),
+                    render_profiler
+                        .after(GameSet::UI)
+                        .before(present_canvas)
+                        .run_if(profiler_enabled),
+                    render_console
+                        .after(render_profiler)
+                        .before(present_canvas)
+                        .run_if(console_open),
+                    present_canvas.after(GameSet::UI),
+                ),
+            )
+            .add_systems(
+                PreUpdate,
+                update_uniform_panel
+                    .after(update_console)
+                    .after(update_mouse_input)
+                    .before(update_actions),
+            )
+            .add_console_command(
+                "uniforms",
+                "",
+                "opens or closes the shader uniform panel",
+                uniforms_command,
+            );
+    }
+}
@@ -0,0 +1,87 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::engine::{FRAME_HISTORY, Profiler};
+
+/// Systems listed under the graph.
+const WORST_SYSTEMS: usize = 8;
+
+/// The frame time the graph's top edge stands for, two 60Hz frames.
+const GRAPH_SCALE: f32 = 2. / 60.;
+const GRAPH_HEIGHT: f32 = 32.;
+
+const LINE_HEIGHT: f32 = 10.;
+const FONT_SIZE: f32 = 12.;
+
+fn millis(seconds: f32) -> String {
+    format!("{:.2}ms", seconds * 1000.)
+}
+
+/// Frame times, where the time goes and how much there is, drawn over
+/// everything else while the [`Profiler`] is open.
+pub fn render_profiler(profiler: Res<Profiler>) {
+    let x = 8.;
+    let width = FRAME_HISTORY as f32;
+    let lines = 3 + profiler.scopes.len() + WORST_SYSTEMS.min(profiler.systems.len());
+    let height = GRAPH_HEIGHT + lines as f32 * LINE_HEIGHT + 8.;
+    let mut y = 56.;
+
+    draw_rectangle(
+        x - 4.,
+        y - 4.,
+        width + 8.,
+        height,
+        Color::new(0., 0., 0., 0.75),
+    );
+
+    // Bars grow up from the bottom, red once a frame runs over 60Hz.
+    let budget = 1. / 60.;
+    let bottom = y + GRAPH_HEIGHT;
+
+    for (i, frame) in profiler.frames.iter().enumerate() {
+        let bar = (frame / GRAPH_SCALE).min(1.) * GRAPH_HEIGHT;
+        let color = if *frame > budget { RED } else { GREEN };
+        draw_rectangle(x + i as f32, bottom - bar, 1., bar, color);
+    }
+
+    let budget_y = bottom - budget / GRAPH_SCALE * GRAPH_HEIGHT;
+    draw_line(x, budget_y, x + width, budget_y, 1., GRAY);
+    y = bottom + LINE_HEIGHT;
+
+    let last = profiler.frames.back().copied().unwrap_or_default();
+    let worst = profiler.frames.iter().copied().fold(0., f32::max);
+    let text = format!("frame {} (max {})", millis(last), millis(worst));
+    draw_text(&text, x, y, FONT_SIZE, WHITE);
+    y += LINE_HEIGHT;
+
+    let text = format!(
+        "entities {} ({} pooled)",
+        profiler.entities, profiler.pooled
+    );
+    draw_text(&text, x, y, FONT_SIZE, WHITE);
+    y += LINE_HEIGHT;
+
+    for (name, seconds) in &profiler.scopes {
+        draw_text(
+            &format!("{} {}", name, millis(*seconds)),
+            x,
+            y,
+            FONT_SIZE,
+            SKYBLUE,
+        );
+        y += LINE_HEIGHT;
+    }
+
+    y += LINE_HEIGHT * 0.5;
+
+    for (name, seconds) in profiler.worst(WORST_SYSTEMS) {
+        draw_text(
+            &format!("{} {}", name, millis(seconds)),
+            x,
+            y,
+            FONT_SIZE,
+            GOLD,
+        );
+        y += LINE_HEIGHT;
+    }
+}
@@ -0,0 +1,51 @@
+pub const STARFIELD_FRAGMENT_SHADER: &str = include_str!("../starfield-shader.glsl");
+pub const STARFIELD_VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+varying float iTime;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+uniform vec4 _Time;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    iTime = _Time.x;
+}
+";
+
+pub const GLYPH_FRAGMENT_SHADER: &str = include_str!("../glyph-shader.glsl");
+pub const GLYPH_VERTEX_SHADER: &str = "#version 400
+attribute vec3 position;
+attribute vec2 texcoord;
+
+varying lowp vec2 uv;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    uv = texcoord;
+}
+";
+
+pub const CRT_FRAGMENT_SHADER: &str = include_str!("../crt-shader.glsl");
+pub const CRT_VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+
+varying lowp vec2 uv;
+varying lowp vec4 color;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    color = color0 / 255.0;
+    uv = texcoord;
+}
+";
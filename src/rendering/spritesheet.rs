@@ -0,0 +1,496 @@
+//! Spritesheets exported from Aseprite (or TexturePacker) as JSON, loaded
+//! into [`Assets`] with their named frames and animation tags, so sheets
+//! can be redrawn and re-exported without touching any rectangles here.
+//! Both the "hash" and "array" export layouts are understood.
+
+use std::{collections::HashMap, fmt, marker::PhantomData};
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{
+    Deserialize, Deserializer,
+    de::{MapAccess, Visitor},
+};
+
+use crate::domain::Position;
+use crate::engine::{Assets, Handle, Time, read_string, read_texture};
+
+/// How long a frame shows when the export doesn't say, in milliseconds.
+/// TexturePacker leaves durations out.
+const DEFAULT_FRAME_MS: u32 = 100;
+
+/// A JSON object read in file order. Frames in the hash layout are keyed by
+/// name, and tags refer to them by position.
+struct OrderedMap<V>(Vec<(String, V)>);
+
+impl<'de, V: Deserialize<'de>> Deserialize<'de> for OrderedMap<V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OrderedVisitor<V>(PhantomData<V>);
+
+        impl<'de, V: Deserialize<'de>> Visitor<'de> for OrderedVisitor<V> {
+            type Value = OrderedMap<V>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut entries = Vec::new();
+
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+
+                Ok(OrderedMap(entries))
+            }
+        }
+
+        deserializer.deserialize_map(OrderedVisitor(PhantomData))
+    }
+}
+
+#[derive(Deserialize)]
+struct ExportRect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+#[derive(Deserialize)]
+struct ExportFrame {
+    /// Only in the array layout; the hash layout keys frames by name.
+    #[serde(default)]
+    filename: Option<String>,
+    frame: ExportRect,
+    #[serde(default)]
+    duration: Option<u32>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ExportFrames {
+    Hash(OrderedMap<ExportFrame>),
+    Array(Vec<ExportFrame>),
+}
+
+#[derive(Deserialize)]
+struct ExportTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: AnimationDirection,
+}
+
+#[derive(Deserialize)]
+struct ExportMeta {
+    /// The sheet's image, relative to the JSON file.
+    image: String,
+    #[serde(default, rename = "frameTags")]
+    frame_tags: Vec<ExportTag>,
+}
+
+/// The export's top level. Untagged so JSON's `{ }` objects are read as
+/// maps rather than needing RON's `( )` struct syntax.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Export {
+    Sheet {
+        frames: ExportFrames,
+        meta: ExportMeta,
+    },
+}
+
+/// Which way an animation tag plays through its frames.
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimationDirection {
+    #[default]
+    Forward,
+    Reverse,
+    Pingpong,
+    PingpongReverse,
+}
+
+#[derive(Clone, Debug)]
+pub struct SpriteFrame {
+    pub name: String,
+    /// Where the frame is on the sheet, in pixels.
+    pub rect: Rect,
+    /// Seconds the frame shows for when animated.
+    pub duration: f32,
+}
+
+/// A run of frames played as an animation.
+#[derive(Clone, Debug)]
+pub struct SpriteTag {
+    pub from: usize,
+    pub to: usize,
+    pub direction: AnimationDirection,
+}
+
+impl SpriteTag {
+    /// Frame indices in the order one loop plays them.
+    pub fn sequence(&self) -> Vec<usize> {
+        let (from, to) = (self.from, self.to);
+        // The frames between the ends, which a ping-pong plays on the way
+        // back without repeating either end.
+        let inner = (from + 1)..to;
+
+        match self.direction {
+            AnimationDirection::Forward => (from..=to).collect(),
+            AnimationDirection::Reverse => (from..=to).rev().collect(),
+            AnimationDirection::Pingpong => (from..=to).chain(inner.rev()).collect(),
+            AnimationDirection::PingpongReverse => (from..=to).rev().chain(inner).collect(),
+        }
+    }
+}
+
+/// An export's frames and tags, everything but the image.
+pub struct SpriteLayout {
+    pub frames: Vec<SpriteFrame>,
+    pub tags: HashMap<String, SpriteTag>,
+}
+
+impl SpriteLayout {
+    /// Reads an exported JSON file, returning the layout and the path of
+    /// the image it points to, relative to the file.
+    pub fn parse(src: &str) -> Result<(Self, String), String> {
+        let Export::Sheet { frames, meta } = ron::from_str(src).map_err(|e| e.to_string())?;
+
+        let frames: Vec<(String, ExportFrame)> = match frames {
+            ExportFrames::Hash(frames) => frames.0,
+            ExportFrames::Array(frames) => frames
+                .into_iter()
+                .enumerate()
+                .map(|(i, frame)| (frame.filename.clone().unwrap_or(i.to_string()), frame))
+                .collect(),
+        };
+
+        let frames: Vec<SpriteFrame> = frames
+            .into_iter()
+            .map(|(name, frame)| SpriteFrame {
+                name,
+                rect: Rect::new(frame.frame.x, frame.frame.y, frame.frame.w, frame.frame.h),
+                duration: frame.duration.unwrap_or(DEFAULT_FRAME_MS) as f32 / 1000.,
+            })
+            .collect();
+
+        let mut tags = HashMap::new();
+
+        for tag in meta.frame_tags {
+            if tag.from > tag.to || tag.to >= frames.len() {
+                return Err(format!("tag {} is out of range", tag.name));
+            }
+
+            tags.insert(
+                tag.name,
+                SpriteTag {
+                    from: tag.from,
+                    to: tag.to,
+                    direction: tag.direction,
+                },
+            );
+        }
+
+        Ok((Self { frames, tags }, meta.image))
+    }
+
+    /// Index of the frame called `name`.
+    pub fn frame(&self, name: &str) -> Option<usize> {
+        self.frames.iter().position(|frame| frame.name == name)
+    }
+
+    /// The frame `tag` shows `seconds` into its animation, looping.
+    pub fn frame_at(&self, tag: &str, seconds: f32) -> Option<usize> {
+        let sequence = self.tags.get(tag)?.sequence();
+        let total: f32 = sequence.iter().map(|&i| self.frames[i].duration).sum();
+
+        if total <= 0. {
+            return sequence.first().copied();
+        }
+
+        let mut remaining = seconds.rem_euclid(total);
+
+        for &i in sequence.iter() {
+            let duration = self.frames[i].duration;
+
+            if remaining < duration {
+                return Some(i);
+            }
+
+            remaining -= duration;
+        }
+
+        sequence.last().copied()
+    }
+}
+
+pub struct SpriteSheet {
+    pub texture: Texture2D,
+    pub layout: SpriteLayout,
+}
+
+impl SpriteSheet {
+    /// Reads an exported JSON file and the image it points to.
+    pub async fn load(path: &str) -> Result<Self, String> {
+        let src = read_string(path).await?;
+        let (layout, image) = SpriteLayout::parse(&src)?;
+
+        let image = match path.rsplit_once('/') {
+            Some((dir, _)) => format!("{}/{}", dir, image),
+            None => image,
+        };
+        let texture = read_texture(&image).await?;
+        texture.set_filter(FilterMode::Nearest);
+
+        Ok(Self { texture, layout })
+    }
+}
+
+/// A frame from a [`SpriteSheet`], drawn at its own size centered on the
+/// entity's [`Position`].
+#[derive(Component, Clone, Copy, Debug)]
+pub struct Sprite {
+    pub sheet: Handle<SpriteSheet>,
+    pub frame: usize,
+}
+
+/// Plays one of the sheet's tags on a [`Sprite`].
+#[derive(Component, Clone, Debug)]
+pub struct SpriteAnimation {
+    pub tag: String,
+    pub elapsed: f32,
+}
+
+impl SpriteAnimation {
+    pub fn new(tag: &str) -> Self {
+        Self {
+            tag: tag.to_string(),
+            elapsed: 0.,
+        }
+    }
+}
+
+pub fn animate_sprites(
+    mut q_animated: Query<(&mut Sprite, &mut SpriteAnimation)>,
+    assets: Res<Assets>,
+    time: Res<Time>,
+) {
+    for (mut sprite, mut animation) in q_animated.iter_mut() {
+        animation.elapsed += time.dt;
+
+        let frame = assets
+            .get(sprite.sheet)
+            .and_then(|sheet| sheet.layout.frame_at(&animation.tag, animation.elapsed));
+
+        if let Some(frame) = frame
+            && sprite.frame != frame
+        {
+            sprite.frame = frame;
+        }
+    }
+}
+
+pub fn render_sprites(q_sprites: Query<(&Sprite, &Position)>, assets: Res<Assets>) {
+    for (sprite, position) in q_sprites.iter() {
+        let Some(sheet) = assets.get(sprite.sheet) else {
+            continue;
+        };
+        let Some(frame) = sheet.layout.frames.get(sprite.frame) else {
+            continue;
+        };
+
+        let size = frame.rect.size();
+        let corner = (position.0 - size / 2.).floor();
+
+        draw_texture_ex(
+            &sheet.texture,
+            corner.x,
+            corner.y,
+            WHITE,
+            DrawTextureParams {
+                dest_size: Some(size),
+                source: Some(frame.rect),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Aseprite's "hash" layout, frames named by tag.
+    const ASEPRITE_HASH: &str = r##"{ "frames": {
+   "idle0": {
+    "frame": { "x": 0, "y": 0, "w": 16, "h": 16 },
+    "rotated": false,
+    "trimmed": false,
+    "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+    "sourceSize": { "w": 16, "h": 16 },
+    "duration": 100
+   },
+   "idle1": {
+    "frame": { "x": 16, "y": 0, "w": 16, "h": 16 },
+    "rotated": false,
+    "trimmed": false,
+    "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+    "sourceSize": { "w": 16, "h": 16 },
+    "duration": 200
+   },
+   "fly0": {
+    "frame": { "x": 32, "y": 0, "w": 16, "h": 16 },
+    "rotated": false,
+    "trimmed": false,
+    "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+    "sourceSize": { "w": 16, "h": 16 },
+    "duration": 100
+   },
+   "fly1": {
+    "frame": { "x": 48, "y": 0, "w": 16, "h": 16 },
+    "rotated": false,
+    "trimmed": false,
+    "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+    "sourceSize": { "w": 16, "h": 16 },
+    "duration": 100
+   },
+   "fly2": {
+    "frame": { "x": 64, "y": 0, "w": 16, "h": 16 },
+    "rotated": false,
+    "trimmed": false,
+    "spriteSourceSize": { "x": 0, "y": 0, "w": 16, "h": 16 },
+    "sourceSize": { "w": 16, "h": 16 },
+    "duration": 100
+   }
+ },
+ "meta": {
+  "app": "https://www.aseprite.org/",
+  "version": "1.3.7-x64",
+  "image": "ship.png",
+  "format": "RGBA8888",
+  "size": { "w": 80, "h": 16 },
+  "scale": "1",
+  "frameTags": [
+   { "name": "idle", "from": 0, "to": 1, "direction": "forward", "color": "#000000ff" },
+   { "name": "fly", "from": 2, "to": 4, "direction": "pingpong", "color": "#000000ff" }
+  ],
+  "layers": [
+   { "name": "Layer 1", "opacity": 255, "blendMode": "normal" }
+  ],
+  "slices": [
+  ]
+ }
+}
+"##;
+
+    /// TexturePacker's "array" layout, which has no durations or tags.
+    const TEXTURE_PACKER_ARRAY: &str = r##"{"frames": [
+
+{
+	"filename": "walk_1.png",
+	"frame": {"x":0,"y":0,"w":8,"h":12},
+	"rotated": false,
+	"trimmed": false,
+	"spriteSourceSize": {"x":0,"y":0,"w":8,"h":12},
+	"sourceSize": {"w":8,"h":12},
+	"pivot": {"x":0.5,"y":0.5}
+},
+{
+	"filename": "walk_2.png",
+	"frame": {"x":8,"y":0,"w":8,"h":12},
+	"rotated": false,
+	"trimmed": false,
+	"spriteSourceSize": {"x":0,"y":0,"w":8,"h":12},
+	"sourceSize": {"w":8,"h":12},
+	"pivot": {"x":0.5,"y":0.5}
+}],
+"meta": {
+	"app": "https://www.codeandweb.com/texturepacker",
+	"version": "1.0",
+	"image": "walk.png",
+	"format": "RGBA8888",
+	"size": {"w":16,"h":12},
+	"scale": "1",
+	"smartupdate": "$TexturePacker:SmartUpdate:0123456789abcdef$"
+}
+}
+"##;
+
+    fn tag(from: usize, to: usize, direction: AnimationDirection) -> SpriteTag {
+        SpriteTag {
+            from,
+            to,
+            direction,
+        }
+    }
+
+    #[test]
+    fn hash_layout_keeps_frames_in_file_order() {
+        let (layout, image) = SpriteLayout::parse(ASEPRITE_HASH).unwrap();
+        let names: Vec<&str> = layout.frames.iter().map(|f| f.name.as_str()).collect();
+
+        assert_eq!(image, "ship.png");
+        assert_eq!(names, ["idle0", "idle1", "fly0", "fly1", "fly2"]);
+        assert_eq!(layout.frames[1].rect, Rect::new(16., 0., 16., 16.));
+        assert_eq!(layout.frames[1].duration, 0.2);
+        assert_eq!(layout.frame("fly0"), Some(2));
+
+        let fly = &layout.tags["fly"];
+        assert_eq!((fly.from, fly.to), (2, 4));
+        assert_eq!(fly.direction, AnimationDirection::Pingpong);
+    }
+
+    #[test]
+    fn array_layout_names_frames_by_filename() {
+        let (layout, image) = SpriteLayout::parse(TEXTURE_PACKER_ARRAY).unwrap();
+        let names: Vec<&str> = layout.frames.iter().map(|f| f.name.as_str()).collect();
+
+        assert_eq!(image, "walk.png");
+        assert_eq!(names, ["walk_1.png", "walk_2.png"]);
+        assert_eq!(layout.frames[1].rect, Rect::new(8., 0., 8., 12.));
+        assert_eq!(layout.frames[0].duration, DEFAULT_FRAME_MS as f32 / 1000.);
+        assert!(layout.tags.is_empty());
+    }
+
+    #[test]
+    fn tags_past_the_last_frame_are_rejected() {
+        let src = ASEPRITE_HASH.replace(r#""from": 2, "to": 4"#, r#""from": 2, "to": 5"#);
+
+        assert!(SpriteLayout::parse(&src).is_err());
+    }
+
+    #[test]
+    fn sequences_follow_the_direction() {
+        use AnimationDirection::*;
+
+        assert_eq!(tag(2, 4, Forward).sequence(), [2, 3, 4]);
+        assert_eq!(tag(2, 4, Reverse).sequence(), [4, 3, 2]);
+        assert_eq!(tag(2, 5, Pingpong).sequence(), [2, 3, 4, 5, 4, 3]);
+        assert_eq!(tag(2, 5, PingpongReverse).sequence(), [5, 4, 3, 2, 3, 4]);
+        assert_eq!(tag(3, 3, Pingpong).sequence(), [3]);
+        assert_eq!(tag(3, 4, Pingpong).sequence(), [3, 4]);
+    }
+
+    #[test]
+    fn frame_at_uses_durations_and_wraps_around() {
+        let (layout, _) = SpriteLayout::parse(ASEPRITE_HASH).unwrap();
+
+        // idle is 0.1s of frame 0 then 0.2s of frame 1.
+        assert_eq!(layout.frame_at("idle", 0.05), Some(0));
+        assert_eq!(layout.frame_at("idle", 0.15), Some(1));
+        assert_eq!(layout.frame_at("idle", 0.29), Some(1));
+        assert_eq!(layout.frame_at("idle", 0.35), Some(0));
+        assert_eq!(layout.frame_at("idle", -0.05), Some(1));
+
+        // fly ping-pongs 2, 3, 4, 3 at 0.1s each.
+        let frames: Vec<_> = (0..6)
+            .map(|i| layout.frame_at("fly", i as f32 * 0.1 + 0.05).unwrap())
+            .collect();
+        assert_eq!(frames, [2, 3, 4, 3, 2, 3]);
+
+        assert_eq!(layout.frame_at("missing", 0.), None);
+    }
+}
@@ -0,0 +1,53 @@
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use super::{STARFIELD_FRAGMENT_SHADER, STARFIELD_VERTEX_SHADER, Uniforms, tunable_uniforms};
+use crate::engine::Screen;
+
+#[derive(Resource)]
+pub struct Starfield {
+    /// `None` until it has compiled.
+    pub material: Option<Material>,
+    pub direction_modifier: f32,
+    /// Multiplies the colour of every star.
+    pub tint: Vec3,
+}
+
+pub fn load_starfield_material() -> Result<Material, String> {
+    load_material(
+        ShaderSource::Glsl {
+            vertex: STARFIELD_VERTEX_SHADER,
+            fragment: STARFIELD_FRAGMENT_SHADER,
+        },
+        MaterialParams {
+            uniforms: [
+                vec![
+                    UniformDesc::new("iResolution", UniformType::Float2),
+                    UniformDesc::new("direction_modifier", UniformType::Float1),
+                    UniformDesc::new("tint", UniformType::Float3),
+                ],
+                tunable_uniforms("starfield"),
+            ]
+            .concat(),
+            ..Default::default()
+        },
+    )
+    .map_err(|e| format!("Failed to compile the starfield shader: {}", e))
+}
+
+pub fn render_starfield(starfield: Res<Starfield>, screen: Res<Screen>, uniforms: Res<Uniforms>) {
+    let Some(material) = &starfield.material else {
+        return;
+    };
+
+    let width = screen.width as f32;
+    let height = screen.height as f32;
+
+    material.set_uniform("iResolution", (width, height));
+    material.set_uniform("direction_modifier", starfield.direction_modifier);
+    material.set_uniform("tint", starfield.tint);
+    uniforms.apply("starfield", material);
+    gl_use_material(material);
+    draw_rectangle(0., 0., width, height, WHITE);
+    gl_use_default_material();
+}
@@ -0,0 +1,69 @@
+//! The glyph atlases the game can be drawn with. Every atlas lays its
+//! glyphs out in the same [`ATLAS_GRID`], so a glyph index means the same
+//! thing in each; only the size of a cell differs. The [`Tileset`] resource
+//! picks the one glyphs are drawn from, and [`GlyphTileset`] keeps an
+//! entity on another.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::engine::cycle;
+
+/// Cells across and down every atlas.
+pub const ATLAS_GRID: UVec2 = uvec2(16, 16);
+
+#[derive(
+    Resource,
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+)]
+pub enum Tileset {
+    /// 16x24 cells.
+    #[default]
+    Cowboy,
+    /// 8x12 cells.
+    Chunky,
+}
+
+impl Tileset {
+    pub const ALL: [Tileset; 2] = [Tileset::Cowboy, Tileset::Chunky];
+
+    pub fn path(self) -> &'static str {
+        match self {
+            Tileset::Cowboy => "assets/cowboy.png",
+            Tileset::Chunky => "assets/chunky.png",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Tileset::Cowboy => "COWBOY",
+            Tileset::Chunky => "CHUNKY",
+        }
+    }
+
+    /// The tileset `offset` steps away from this one, wrapping around.
+    pub fn cycle(&self, offset: isize) -> Self {
+        cycle(&Self::ALL, self, offset)
+    }
+}
+
+/// The size in texels of one cell of `atlas`.
+pub fn cell_size(atlas: &Texture2D) -> Vec2 {
+    atlas.size() / ATLAS_GRID.as_vec2()
+}
+
+/// Draws the entity's [`Glyph`](super::Glyph) from this tileset instead of
+/// the active one, e.g. to keep a layer of the scene in its own style.
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GlyphTileset(pub Tileset);
@@ -0,0 +1,414 @@
+//! Shader uniforms that can be tuned while the game runs. Each material
+//! declares the ones in [`UNIFORMS`] alongside its own and uploads the
+//! current [`Uniforms`] every time it's drawn with.
+//!
+//! [`UNIFORM_PANEL_KEY`], or `uniforms` in the console, opens a panel down
+//! the left edge with a slider for every value and every colour channel.
+//! Dragging one applies it straight away, right clicking puts it back, and
+//! the last row writes whatever differs from the defaults to the `uniforms`
+//! section of [`CONFIG_PATH`], where it's read back on the next run.
+
+use bevy_ecs::prelude::*;
+use macroquad::prelude::*;
+
+use crate::engine::{CONFIG_PATH, CommandResult, Config, KeyInput, MouseInput, Screen};
+
+/// Opens and closes the panel, from anywhere.
+pub const UNIFORM_PANEL_KEY: KeyCode = KeyCode::F11;
+
+const PANEL_WIDTH: f32 = 180.;
+const ROW_HEIGHT: f32 = 10.;
+const FONT_SIZE: f32 = 12.;
+
+/// Where the slider starts along each row.
+const SLIDER_OFFSET: f32 = 96.;
+const SLIDER_WIDTH: f32 = 50.;
+const SWATCH_WIDTH: f32 = 24.;
+
+#[derive(Clone, Copy, Debug)]
+pub enum UniformKind {
+    /// A single value between the bounds.
+    Slider { min: f32, max: f32 },
+    /// RGBA, each channel between 0 and 1.
+    Color,
+}
+
+/// A uniform the panel can change.
+#[derive(Clone, Copy, Debug)]
+pub struct TunableUniform {
+    /// The material that declares it, e.g. `crt`.
+    pub material: &'static str,
+    /// Its name in the shader.
+    pub name: &'static str,
+    pub kind: UniformKind,
+    pub default: &'static [f32],
+}
+
+impl TunableUniform {
+    /// How it's named in the config, `material.name`.
+    pub fn key(&self) -> String {
+        format!("{}.{}", self.material, self.name)
+    }
+
+    fn range(&self) -> (f32, f32) {
+        match self.kind {
+            UniformKind::Slider { min, max } => (min, max),
+            UniformKind::Color => (0., 1.),
+        }
+    }
+
+    fn uniform_type(&self) -> UniformType {
+        match self.default.len() {
+            1 => UniformType::Float1,
+            2 => UniformType::Float2,
+            3 => UniformType::Float3,
+            _ => UniformType::Float4,
+        }
+    }
+}
+
+pub const UNIFORMS: [TunableUniform; 5] = [
+    TunableUniform {
+        material: "crt",
+        name: "curvature",
+        kind: UniformKind::Slider { min: 0., max: 4. },
+        default: &[1.],
+    },
+    TunableUniform {
+        material: "crt",
+        name: "scanlines",
+        kind: UniformKind::Slider { min: 0., max: 1. },
+        default: &[0.25],
+    },
+    TunableUniform {
+        material: "crt",
+        name: "vignette",
+        kind: UniformKind::Slider { min: 0., max: 1. },
+        default: &[0.3],
+    },
+    TunableUniform {
+        material: "starfield",
+        name: "speed",
+        kind: UniformKind::Slider { min: 0., max: 10. },
+        default: &[3.],
+    },
+    TunableUniform {
+        material: "glyph",
+        name: "outline_tint",
+        kind: UniformKind::Color,
+        default: &[1., 1., 1., 1.],
+    },
+];
+
+/// Declarations for `material`'s tunable uniforms, to add to its own.
+pub fn tunable_uniforms(material: &str) -> Vec<UniformDesc> {
+    UNIFORMS
+        .iter()
+        .filter(|uniform| uniform.material == material)
+        .map(|uniform| UniformDesc::new(uniform.name, uniform.uniform_type()))
+        .collect()
+}
+
+/// The current value of every uniform in [`UNIFORMS`], in the same order.
+#[derive(Resource)]
+pub struct Uniforms {
+    pub values: Vec<Vec<f32>>,
+}
+
+impl Uniforms {
+    /// The defaults, with anything tuned in `config` applied over them.
+    pub fn new(config: &Config) -> Self {
+        let values = UNIFORMS
+            .iter()
+            .map(|uniform| match config.uniforms.get(&uniform.key()) {
+                Some(value) if value.len() == uniform.default.len() => value.clone(),
+                Some(_) => {
+                    warn!(
+                        "Ignoring {} in {}, it takes {} values",
+                        uniform.key(),
+                        CONFIG_PATH,
+                        uniform.default.len()
+                    );
+                    uniform.default.to_vec()
+                }
+                None => uniform.default.to_vec(),
+            })
+            .collect();
+
+        for key in config.uniforms.keys() {
+            if !UNIFORMS.iter().any(|uniform| uniform.key() == *key) {
+                warn!("Ignoring unknown uniform {} in {}", key, CONFIG_PATH);
+            }
+        }
+
+        Self { values }
+    }
+
+    /// Uploads `material`'s tunable uniforms.
+    pub fn apply(&self, name: &str, material: &Material) {
+        for (uniform, value) in UNIFORMS.iter().zip(&self.values) {
+            if uniform.material != name {
+                continue;
+            }
+
+            match value[..] {
+                [x] => material.set_uniform(uniform.name, x),
+                [x, y] => material.set_uniform(uniform.name, vec2(x, y)),
+                [x, y, z] => material.set_uniform(uniform.name, vec3(x, y, z)),
+                [x, y, z, w, ..] => material.set_uniform(uniform.name, vec4(x, y, z, w)),
+                [] => {}
+            }
+        }
+    }
+
+    /// Writes the values that differ from their defaults into the
+    /// `uniforms` section of [`CONFIG_PATH`], leaving the rest of the file
+    /// as it is.
+    pub fn export(&self) -> Result<usize, String> {
+        let mut config = Config::load(CONFIG_PATH);
+
+        config.uniforms = UNIFORMS
+            .iter()
+            .zip(&self.values)
+            .filter(|(uniform, value)| uniform.default != &value[..])
+            .map(|(uniform, value)| (uniform.key(), value.clone()))
+            .collect();
+
+        config.save(CONFIG_PATH)?;
+        Ok(config.uniforms.len())
+    }
+}
+
+/// A line of the panel.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum UniformRow {
+    /// One value of a uniform, with a slider: the uniform's index in
+    /// [`UNIFORMS`] and which of its values.
+    Value(usize, usize),
+    /// A colour's name and a swatch of it.
+    Swatch(usize),
+    Export,
+}
+
+/// Every row, top to bottom.
+pub fn uniform_rows() -> Vec<UniformRow> {
+    let mut rows = Vec::new();
+
+    for (i, uniform) in UNIFORMS.iter().enumerate() {
+        match uniform.kind {
+            UniformKind::Slider { .. } => rows.push(UniformRow::Value(i, 0)),
+            UniformKind::Color => {
+                rows.push(UniformRow::Swatch(i));
+                rows.extend((0..uniform.default.len()).map(|c| UniformRow::Value(i, c)));
+            }
+        }
+    }
+
+    rows.push(UniformRow::Export);
+    rows
+}
+
+pub fn uniform_panel_rect(screen: &Screen) -> Rect {
+    Rect::new(0., 0., PANEL_WIDTH, screen.height as f32)
+}
+
+pub fn uniform_row_rect(row: usize) -> Rect {
+    Rect::new(
+        0.,
+        (row + 1) as f32 * ROW_HEIGHT + 4.,
+        PANEL_WIDTH,
+        ROW_HEIGHT,
+    )
+}
+
+fn slider_rect(row: Rect) -> Rect {
+    Rect::new(
+        row.x + SLIDER_OFFSET,
+        row.y + 3.,
+        SLIDER_WIDTH,
+        ROW_HEIGHT - 6.,
+    )
+}
+
+#[derive(Resource, Default)]
+pub struct UniformPanel {
+    open: bool,
+    /// The row whose slider is held.
+    dragging: Option<usize>,
+    /// What the last export did.
+    pub status: Option<String>,
+}
+
+impl UniformPanel {
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    pub fn toggle(&mut self) {
+        self.open = !self.open;
+        self.dragging = None;
+    }
+}
+
+/// Toggles the panel and, while it's open, drags sliders and takes any
+/// click over it away from the game.
+pub fn update_uniform_panel(
+    keys: Res<KeyInput>,
+    screen: Res<Screen>,
+    mut mouse: ResMut<MouseInput>,
+    mut panel: ResMut<UniformPanel>,
+    mut uniforms: ResMut<Uniforms>,
+) {
+    if keys.is_pressed(UNIFORM_PANEL_KEY) {
+        panel.toggle();
+    }
+
+    if !panel.open {
+        return;
+    }
+
+    let point = mouse.position;
+    let rows = uniform_rows();
+    let hovered = (0..rows.len()).find(|&row| uniform_row_rect(row).contains(point));
+
+    if !mouse.is_down(MouseButton::Left) {
+        panel.dragging = None;
+    }
+
+    if mouse.is_pressed(MouseButton::Left) {
+        match hovered.map(|row| (row, rows[row])) {
+            Some((row, UniformRow::Value(..))) => panel.dragging = Some(row),
+            Some((_, UniformRow::Export)) => {
+                panel.status = Some(match uniforms.export() {
+                    Ok(count) => format!("exported {} to {}", count, CONFIG_PATH),
+                    Err(e) => format!("export failed: {}", e),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(row) = panel.dragging
+        && let UniformRow::Value(i, c) = rows[row]
+    {
+        let slider = slider_rect(uniform_row_rect(row));
+        let (min, max) = UNIFORMS[i].range();
+        let t = ((point.x - slider.x) / slider.w).clamp(0., 1.);
+        uniforms.values[i][c] = min + (max - min) * t;
+    }
+
+    if mouse.is_pressed(MouseButton::Right)
+        && let Some(UniformRow::Value(i, c)) = hovered.map(|row| rows[row])
+    {
+        uniforms.values[i][c] = UNIFORMS[i].default[c];
+    }
+
+    if panel.dragging.is_some() || uniform_panel_rect(&screen).contains(point) {
+        mouse.down.clear();
+        mouse.pressed.clear();
+        mouse.released.clear();
+        mouse.wheel = Vec2::ZERO;
+    }
+}
+
+pub fn uniform_panel_open(panel: Res<UniformPanel>) -> bool {
+    panel.is_open()
+}
+
+pub fn uniforms_command(world: &mut World, _args: &[&str]) -> CommandResult {
+    let mut panel = world.resource_mut::<UniformPanel>();
+    panel.toggle();
+
+    Ok(format!(
+        "Uniform panel {}",
+        if panel.is_open() { "open" } else { "closed" }
+    ))
+}
+
+fn draw_row(text: &str, rect: Rect, color: Color) {
+    draw_text(
+        text,
+        rect.x + 4.,
+        rect.y + ROW_HEIGHT - 2.,
+        FONT_SIZE,
+        color,
+    );
+}
+
+pub fn render_uniform_panel(
+    panel: Res<UniformPanel>,
+    uniforms: Res<Uniforms>,
+    screen: Res<Screen>,
+    mouse: Res<MouseInput>,
+) {
+    let rect = uniform_panel_rect(&screen);
+
+    draw_rectangle(rect.x, rect.y, rect.w, rect.h, Color::new(0., 0., 0., 0.75));
+    draw_row(
+        "uniforms",
+        Rect::new(0., 4., PANEL_WIDTH, ROW_HEIGHT),
+        SKYBLUE,
+    );
+
+    for (row, kind) in uniform_rows().into_iter().enumerate() {
+        let rect = uniform_row_rect(row);
+        let color = if panel.dragging == Some(row) {
+            GOLD
+        } else if rect.contains(mouse.position) {
+            WHITE
+        } else {
+            GRAY
+        };
+
+        match kind {
+            UniformRow::Swatch(i) => {
+                let uniform = &UNIFORMS[i];
+                let value = &uniforms.values[i];
+
+                draw_row(&uniform.key(), rect, color);
+                draw_rectangle(
+                    rect.right() - SWATCH_WIDTH - 4.,
+                    rect.y + 1.,
+                    SWATCH_WIDTH,
+                    ROW_HEIGHT - 2.,
+                    Color::new(value[0], value[1], value[2], value[3]),
+                );
+            }
+            UniformRow::Value(i, c) => {
+                let uniform = &UNIFORMS[i];
+                let value = uniforms.values[i][c];
+                let (min, max) = uniform.range();
+                let slider = slider_rect(rect);
+
+                let name = match uniform.kind {
+                    UniformKind::Slider { .. } => uniform.key(),
+                    UniformKind::Color => format!("  {}", ["r", "g", "b", "a"][c]),
+                };
+                draw_row(&name, rect, color);
+
+                draw_rectangle(slider.x, slider.y, slider.w, slider.h, DARKGRAY);
+                draw_rectangle(
+                    slider.x,
+                    slider.y,
+                    slider.w * (value - min) / (max - min),
+                    slider.h,
+                    color,
+                );
+
+                let text = format!("{:.2}", value);
+                let width = measure_text(&text, None, FONT_SIZE as u16, 1.).width;
+                draw_text(
+                    &text,
+                    rect.right() - width - 4.,
+                    rect.y + ROW_HEIGHT - 2.,
+                    FONT_SIZE,
+                    color,
+                );
+            }
+            UniformRow::Export => {
+                let text = panel.status.as_deref().unwrap_or("export to config");
+                draw_row(text, rect, color);
+            }
+        }
+    }
+}
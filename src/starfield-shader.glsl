@@ -13,6 +13,9 @@ varying float iTime;
 
 uniform vec2 iResolution;
 uniform float direction_modifier;
+uniform vec3 tint;
+// Tunable, see rendering/uniforms.rs.
+uniform float speed;
 
 #define NUM_LAYERS 4.
 
@@ -70,7 +73,6 @@ void main()
     vec2 uv = (gl_FragCoord.xy - .5 * iResolution.xy) / iResolution.y;
     float t = iTime * .02;
 
-    float speed = 3.0;
     vec2 direction = vec2(-0.25 + direction_modifier, -1.0) * speed;
 
     uv += direction;
@@ -83,5 +85,5 @@ void main()
         col += StarLayer(uv * scale + i * 453.2) * fade;
     }
 
-    gl_FragColor = vec4(col, 1.0);
+    gl_FragColor = vec4(col * tint, 1.0);
 }
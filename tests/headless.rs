@@ -0,0 +1,85 @@
+//! Whole runs of the game, stepped tick by tick through the headless
+//! harness.
+
+use cathedral::{
+    domain::{Faller, Position, Score, Swept},
+    engine::{Action, Time},
+    headless::Headless,
+};
+use macroquad::prelude::*;
+
+const SEED: u64 = 42;
+
+#[test]
+fn bullet_destroys_faller_within_60_ticks() {
+    let mut game = Headless::new(SEED);
+    let player = game.player().expect("the run starts with a player");
+    let below = game.world().get::<Position>(player).unwrap().0;
+    let faller = game
+        .spawn("faller", below - vec2(0., 60.))
+        .expect("the faller prefab loads");
+
+    let ticks = game.run_until(60, &[Action::Fire], |world| {
+        world
+            .get_entity(faller)
+            .map_or(true, |entity| !entity.contains::<Faller>())
+    });
+
+    assert!(ticks.is_some(), "the faller survived 60 ticks of fire");
+    assert!(game.world().resource::<Score>().0 > 0);
+}
+
+#[test]
+fn plain_bullet_damages_only_one_of_two_stacked_fallers() {
+    let mut game = Headless::new(SEED);
+    let player = game.player().expect("the run starts with a player");
+    let above = game.world().get::<Position>(player).unwrap().0 - vec2(0., 80.);
+
+    let fallers: Vec<_> = (0..2)
+        .map(|_| {
+            game.spawn("faller", above)
+                .expect("the faller prefab loads")
+        })
+        .collect();
+    let bullet = game
+        .spawn("bullet", above)
+        .expect("the bullet prefab loads");
+    game.world_mut().entity_mut(bullet).remove::<Swept>();
+
+    game.run(2, &[]);
+
+    let survivors = fallers
+        .iter()
+        .filter(|&&faller| {
+            game.world()
+                .get_entity(faller)
+                .is_ok_and(|entity| entity.contains::<Faller>())
+        })
+        .count();
+    assert_eq!(
+        survivors, 1,
+        "one bullet should take out exactly one faller"
+    );
+}
+
+#[test]
+fn same_seed_and_input_play_out_the_same() {
+    let play = || {
+        let mut game = Headless::new(SEED);
+
+        game.run(120, &[Action::MoveLeft, Action::Fire]);
+        game.run(120, &[Action::MoveRight]);
+
+        let world = game.world_mut();
+        let mut positions: Vec<_> = world
+            .query::<&Position>()
+            .iter(world)
+            .map(|position| position.0.to_array())
+            .collect();
+        positions.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        (world.resource::<Time>().tick, positions)
+    };
+
+    assert_eq!(play(), play());
+}